@@ -164,7 +164,7 @@ fn main() {
 
 #[cfg(test)]
 mod test {
-    use tokio_cron_scheduler::{Job, JobScheduler};
+    use tokio_cron_scheduler::{Job, JobScheduler, JobSchedulerError};
     use tracing::{info, Level};
     use tracing_subscriber::FmtSubscriber;
 
@@ -197,4 +197,3170 @@ mod test {
 
         tokio::time::sleep(core::time::Duration::from_secs(20)).await;
     }
+
+    #[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+    async fn test_add_duplicate_id() {
+        let scheduler = JobScheduler::new().await.unwrap();
+        let mut job = Job::new("*/1  * * * * *", |_, _| {}).unwrap();
+        let data = job.job_data().unwrap();
+
+        let guid = scheduler
+            .add(job)
+            .await
+            .expect("Should be able to add a job");
+
+        let duplicate = Job::from_job_data(data.clone(), |_, _| {}).unwrap();
+        assert_eq!(duplicate.guid(), guid);
+        let added = scheduler.add(duplicate).await;
+        assert!(matches!(added, Err(JobSchedulerError::DuplicateId)));
+
+        let updated = Job::from_job_data(data, |_, _| {}).unwrap();
+        let updated = scheduler
+            .add_or_update(updated)
+            .await
+            .expect("Should be able to update a job");
+        assert_eq!(updated, guid);
+    }
+
+    #[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+    async fn test_list_jobs() {
+        let scheduler = JobScheduler::new().await.unwrap();
+        let cron = scheduler
+            .add(Job::new("*/1  * * * * *", |_, _| {}).unwrap())
+            .await
+            .expect("Should be able to add a job");
+        let one_shot = scheduler
+            .add(Job::new_one_shot(core::time::Duration::from_secs(60), |_, _| {}).unwrap())
+            .await
+            .expect("Should be able to add a job");
+
+        let jobs = scheduler.list_jobs().await.unwrap();
+        assert_eq!(jobs.len(), 2);
+        for id in [cron, one_shot] {
+            let (_, _, next_tick) = jobs.iter().find(|(job_id, _, _)| *job_id == id).unwrap();
+            assert!(next_tick.is_some());
+        }
+    }
+
+    #[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+    async fn test_update_job_schedule() {
+        let scheduler = JobScheduler::new().await.unwrap();
+        let guid = scheduler
+            .add(Job::new("0 0 0 1 1 *", |_, _| {}).unwrap())
+            .await
+            .expect("Should be able to add a job");
+
+        let invalid = scheduler.update_job_schedule(guid, "not a schedule").await;
+        assert!(matches!(invalid, Err(JobSchedulerError::ParseSchedule)));
+
+        scheduler
+            .update_job_schedule(guid, "*/1 * * * * *")
+            .await
+            .expect("Should be able to update the schedule");
+        let jobs = scheduler.list_jobs().await.unwrap();
+        let (_, _, next_tick) = jobs.iter().find(|(job_id, _, _)| *job_id == guid).unwrap();
+        let next_tick = next_tick.expect("Should have a next tick");
+        assert!(next_tick - chrono::Utc::now() <= chrono::Duration::seconds(1));
+    }
+
+    #[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+    async fn test_shutdown_graceful_cancels_stragglers() {
+        let mut scheduler = JobScheduler::new().await.unwrap();
+        let guid = scheduler
+            .add(
+                Job::new_one_shot_async(core::time::Duration::from_secs(1), |_, _| {
+                    Box::pin(async {
+                        tokio::time::sleep(core::time::Duration::from_secs(60)).await;
+                    })
+                })
+                .unwrap(),
+            )
+            .await
+            .expect("Should be able to add a job");
+        scheduler.start().await.unwrap();
+        tokio::time::sleep(core::time::Duration::from_secs(3)).await;
+
+        let shutdown = scheduler
+            .shutdown_graceful(core::time::Duration::from_millis(100))
+            .await;
+        match shutdown {
+            Err(JobSchedulerError::JobsCancelledOnShutdown(ids)) => assert_eq!(ids, vec![guid]),
+            other => panic!("Expected the running job to be cancelled, got {:?}", other),
+        }
+    }
+
+    #[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+    async fn test_tick_interval_has_a_floor() {
+        let scheduler = JobScheduler::new().await.unwrap();
+        assert_eq!(
+            scheduler.tick_interval().await,
+            core::time::Duration::from_millis(500)
+        );
+        scheduler
+            .set_tick_interval(core::time::Duration::from_secs(2))
+            .await;
+        assert_eq!(
+            scheduler.tick_interval().await,
+            core::time::Duration::from_secs(2)
+        );
+        scheduler
+            .set_tick_interval(core::time::Duration::ZERO)
+            .await;
+        assert_eq!(
+            scheduler.tick_interval().await,
+            core::time::Duration::from_millis(10)
+        );
+    }
+
+    #[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+    async fn test_runs_on_next_tick_with_long_tick_interval() {
+        let scheduler = JobScheduler::new().await.unwrap();
+        scheduler
+            .set_tick_interval(core::time::Duration::from_secs(60))
+            .await;
+        scheduler.start().await.unwrap();
+
+        let (tx, mut rx) = tokio::sync::mpsc::unbounded_channel();
+        scheduler
+            .add(
+                Job::new_one_shot(core::time::Duration::from_secs(1), move |_, _| {
+                    tx.send(()).unwrap();
+                })
+                .unwrap(),
+            )
+            .await
+            .expect("Should be able to add a job");
+
+        let ran = tokio::time::timeout(core::time::Duration::from_secs(3), rx.recv()).await;
+        assert!(matches!(ran, Ok(Some(()))));
+    }
+
+    fn utc(s: &str) -> chrono::DateTime<chrono::Utc> {
+        s.parse().unwrap()
+    }
+
+    #[test]
+    fn test_upcoming_for_tz_spring_forward() {
+        // London skips 01:00 to 02:00 on 2023-03-26, 01:30 fires an hour later
+        let upcoming = tokio_cron_scheduler::upcoming_for_tz(
+            "0 30 1 * * *",
+            chrono_tz::Europe::London,
+            utc("2023-03-25T12:00:00Z"),
+            2,
+        )
+        .unwrap();
+        assert_eq!(
+            upcoming,
+            vec![utc("2023-03-26T01:30:00Z"), utc("2023-03-27T00:30:00Z")]
+        );
+    }
+
+    #[test]
+    fn test_upcoming_for_tz_fall_back() {
+        // London has 01:00 to 02:00 twice on 2023-10-29, 01:30 fires only the first time
+        let upcoming = tokio_cron_scheduler::upcoming_for_tz(
+            "0 30 1 * * *",
+            chrono_tz::Europe::London,
+            utc("2023-10-28T12:00:00Z"),
+            2,
+        )
+        .unwrap();
+        assert_eq!(
+            upcoming,
+            vec![utc("2023-10-29T00:30:00Z"), utc("2023-10-30T01:30:00Z")]
+        );
+
+        let hourly = tokio_cron_scheduler::upcoming_for_tz(
+            "0 30 * * * *",
+            chrono_tz::Europe::London,
+            utc("2023-10-29T00:00:00Z"),
+            3,
+        )
+        .unwrap();
+        assert_eq!(
+            hourly,
+            vec![
+                utc("2023-10-29T00:30:00Z"),
+                utc("2023-10-29T02:30:00Z"),
+                utc("2023-10-29T03:30:00Z")
+            ]
+        );
+    }
+
+    #[test]
+    fn test_cron_job_tz() {
+        let mut london =
+            Job::new_cron_job_tz("0 0 9 * * *", chrono_tz::Europe::London, |_, _| {}).unwrap();
+        let mut new_york =
+            Job::new_cron_job_tz("0 0 9 * * *", chrono_tz::America::New_York, |_, _| {}).unwrap();
+        let london = london.job_data().unwrap();
+        let new_york = new_york.job_data().unwrap();
+        assert_eq!(london.timezone(), Some(chrono_tz::Europe::London));
+        assert_ne!(london.next_tick, new_york.next_tick);
+        let london_local = london
+            .next_tick_utc()
+            .unwrap()
+            .with_timezone(&chrono_tz::Europe::London);
+        assert_eq!(london_local.format("%H:%M").to_string(), "09:00");
+    }
+
+    #[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+    async fn test_jitter_is_seeded() {
+        let mut ticks = vec![];
+        for _ in 0..2 {
+            let mut scheduler = JobScheduler::new().await.unwrap();
+            scheduler.set_jitter_seed(42).await;
+            let job = Job::new("0 0 0 * * *", |_, _| {})
+                .unwrap()
+                .with_jitter(core::time::Duration::from_secs(3600))
+                .unwrap();
+            let guid = scheduler.add(job).await.unwrap();
+            let next_tick = scheduler.next_tick_for_job(guid).await.unwrap();
+            ticks.push(next_tick.expect("Should have a next tick"));
+        }
+        assert_eq!(ticks[0], ticks[1]);
+
+        let midnight =
+            tokio_cron_scheduler::upcoming_for("0 0 0 * * *", chrono::Utc::now(), 1).unwrap()[0];
+        assert!(ticks[0] >= midnight);
+        assert!(ticks[0] <= midnight + chrono::Duration::hours(1));
+
+        let one_shot = Job::new_one_shot(core::time::Duration::from_secs(1), |_, _| {})
+            .unwrap()
+            .with_jitter(core::time::Duration::from_secs(1));
+        assert!(matches!(one_shot, Err(JobSchedulerError::UpdateJobData)));
+    }
+
+    #[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+    async fn test_run_job_now() {
+        let mut scheduler = JobScheduler::new().await.unwrap();
+        let (tx, mut rx) = tokio::sync::mpsc::unbounded_channel();
+        let guid = scheduler
+            .add(
+                Job::new("0 0 0 1 1 *", move |_, _| {
+                    tx.send(()).unwrap();
+                })
+                .unwrap(),
+            )
+            .await
+            .expect("Should be able to add a job");
+        let next_tick = scheduler.next_tick_for_job(guid).await.unwrap();
+
+        scheduler.run_job_now(guid).await.unwrap();
+        let ran = tokio::time::timeout(core::time::Duration::from_secs(1), rx.recv()).await;
+        assert!(matches!(ran, Ok(Some(()))));
+        assert_eq!(scheduler.next_tick_for_job(guid).await.unwrap(), next_tick);
+
+        let unknown = scheduler.run_job_now(uuid::Uuid::new_v4()).await;
+        assert!(matches!(unknown, Err(JobSchedulerError::JobNotFound)));
+    }
+
+    #[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+    async fn test_upcoming_ticks_for_job() {
+        let scheduler = JobScheduler::new().await.unwrap();
+        let cron = scheduler
+            .add(Job::new("0 0 * * * *", |_, _| {}).unwrap())
+            .await
+            .unwrap();
+        let ticks = scheduler.upcoming_ticks_for_job(cron, 5).await.unwrap();
+        assert_eq!(ticks.len(), 5);
+        for pair in ticks.windows(2) {
+            assert_eq!(pair[1] - pair[0], chrono::Duration::hours(1));
+        }
+
+        let one_shot = scheduler
+            .add(Job::new_one_shot(core::time::Duration::from_secs(3600), |_, _| {}).unwrap())
+            .await
+            .unwrap();
+        let ticks = scheduler.upcoming_ticks_for_job(one_shot, 5).await.unwrap();
+        assert_eq!(ticks.len(), 1);
+    }
+
+    #[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+    async fn test_jobs_by_tag() {
+        let scheduler = JobScheduler::new().await.unwrap();
+        let tagged = |tags: &[&str]| {
+            Job::new("0 0 * * * *", |_, _| {})
+                .unwrap()
+                .with_tags(tags.iter().map(|t| t.to_string()).collect())
+                .unwrap()
+        };
+        let billing = scheduler.add(tagged(&["billing"])).await.unwrap();
+        let both = scheduler
+            .add(tagged(&["billing", "reports"]))
+            .await
+            .unwrap();
+        let reports = scheduler.add(tagged(&["reports"])).await.unwrap();
+
+        let mut found = scheduler.jobs_by_tag("billing").await.unwrap();
+        found.sort();
+        let mut expected = vec![billing, both];
+        expected.sort();
+        assert_eq!(found, expected);
+
+        let mut removed = scheduler.remove_by_tag("reports").await.unwrap();
+        removed.sort();
+        let mut expected = vec![both, reports];
+        expected.sort();
+        assert_eq!(removed, expected);
+        assert_eq!(
+            scheduler.jobs_by_tag("billing").await.unwrap(),
+            vec![billing]
+        );
+        assert!(scheduler.jobs_by_tag("reports").await.unwrap().is_empty());
+    }
+
+    #[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+    async fn test_count_jobs() {
+        let scheduler = JobScheduler::new().await.unwrap();
+        assert_eq!(scheduler.count_jobs().await.unwrap(), 0);
+        let guid = scheduler
+            .add(Job::new("0 0 * * * *", |_, _| {}).unwrap())
+            .await
+            .unwrap();
+        scheduler
+            .add(Job::new("0 30 * * * *", |_, _| {}).unwrap())
+            .await
+            .unwrap();
+        assert_eq!(scheduler.count_jobs().await.unwrap(), 2);
+        scheduler.remove(&guid).await.unwrap();
+        assert_eq!(scheduler.count_jobs().await.unwrap(), 1);
+    }
+
+    #[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+    async fn test_remove_all() {
+        let mut scheduler = JobScheduler::new().await.unwrap();
+        let (tx, mut rx) = tokio::sync::mpsc::unbounded_channel();
+        for _ in 0..3 {
+            let tx = tx.clone();
+            scheduler
+                .add(
+                    Job::new("1/1 * * * * *", move |_, _| {
+                        let _ = tx.send(());
+                    })
+                    .unwrap(),
+                )
+                .await
+                .unwrap();
+        }
+        scheduler.start().await.unwrap();
+        scheduler.remove_all().await.unwrap();
+        assert_eq!(scheduler.count_jobs().await.unwrap(), 0);
+
+        tokio::time::sleep(core::time::Duration::from_millis(100)).await;
+        while rx.try_recv().is_ok() {}
+        tokio::time::sleep(core::time::Duration::from_millis(2100)).await;
+        assert!(rx.try_recv().is_err());
+        scheduler.shutdown().await.unwrap();
+    }
+
+    #[test]
+    fn test_validate_schedule() {
+        use tokio_cron_scheduler::validate_schedule;
+
+        assert!(validate_schedule("0 15 6,8,10 * Mar,Jun Fri").is_ok());
+        assert!(validate_schedule("0 15 6,8,10 * Mar,Jun Fri 2017").is_ok());
+        let invalid = validate_schedule("0 61 * * * *");
+        assert!(
+            matches!(invalid, Err(JobSchedulerError::InvalidSchedule(reason)) if !reason.is_empty())
+        );
+    }
+
+    #[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+    async fn test_run_history() {
+        use tokio_cron_scheduler::{JobError, Outcome, SimpleHistoryStore};
+
+        let scheduler = JobScheduler::new().await.unwrap();
+        scheduler
+            .set_history_store(Box::new(SimpleHistoryStore::new(2)))
+            .await;
+        let (tx, mut rx) = tokio::sync::mpsc::unbounded_channel();
+        let guid = scheduler
+            .add(
+                Job::new_async_result("0 0 0 1 1 *", move |_, _| {
+                    let tx = tx.clone();
+                    Box::pin(async move {
+                        let _ = tx.send(());
+                        Err(JobError::from("failed"))
+                    })
+                })
+                .unwrap(),
+            )
+            .await
+            .unwrap();
+
+        for _ in 0..3 {
+            scheduler.run_job_now(guid).await.unwrap();
+            rx.recv().await.unwrap();
+        }
+        tokio::time::sleep(core::time::Duration::from_millis(100)).await;
+        let history = scheduler.run_history(guid).await.unwrap();
+        assert_eq!(history.len(), 2);
+        assert!(history.iter().all(|run| run.outcome == Outcome::Failed));
+        assert!(history[0].started <= history[1].started);
+    }
+
+    #[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+    async fn test_middleware_error_is_notified_as_failed() {
+        use tokio_cron_scheduler::{JobMiddleware, JobRun, JobRunContext};
+
+        struct Failing;
+        impl JobMiddleware for Failing {
+            fn wrap(&self, _context: JobRunContext, next: JobRun) -> JobRun {
+                Box::pin(async move {
+                    next.await?;
+                    Err(JobSchedulerError::JobTick)
+                })
+            }
+        }
+
+        let scheduler = JobScheduler::new().await.unwrap();
+        scheduler.add_job_middleware(Box::new(Failing)).await;
+        let mut failed = scheduler.subscribe_failed();
+        let guid = scheduler
+            .add(Job::new("0 0 0 1 1 *", |_, _| {}).unwrap())
+            .await
+            .unwrap();
+        scheduler.run_job_now(guid).await.unwrap();
+        let (job_id, message) =
+            tokio::time::timeout(core::time::Duration::from_secs(1), failed.recv())
+                .await
+                .unwrap()
+                .unwrap();
+        assert_eq!(job_id, guid);
+        assert!(message.contains("JobTick"));
+    }
+
+    #[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+    async fn test_one_shot_at() {
+        let mut scheduler = JobScheduler::new().await.unwrap();
+        let (tx, mut rx) = tokio::sync::mpsc::unbounded_channel();
+        for when in [
+            chrono::Utc::now() - chrono::Duration::hours(1),
+            chrono::Utc::now() + chrono::Duration::seconds(1),
+        ] {
+            let tx = tx.clone();
+            scheduler
+                .add(
+                    Job::new_one_shot_at(when, move |job_id, _| {
+                        tx.send(job_id).unwrap();
+                    })
+                    .unwrap(),
+                )
+                .await
+                .unwrap();
+        }
+        scheduler.start().await.unwrap();
+        for _ in 0..2 {
+            let ran = tokio::time::timeout(core::time::Duration::from_secs(3), rx.recv()).await;
+            assert!(matches!(ran, Ok(Some(_))));
+        }
+        scheduler.shutdown().await.unwrap();
+    }
+
+    #[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+    async fn test_misfire_policy() {
+        use tokio_cron_scheduler::{MisfirePolicy, SkipReason};
+
+        let mut scheduler = JobScheduler::new().await.unwrap();
+        let (tx, mut rx) = tokio::sync::mpsc::unbounded_channel();
+        let fire_all = scheduler
+            .add(
+                Job::new("* * * * * *", move |job_id, _| {
+                    tx.send(job_id).unwrap();
+                })
+                .unwrap()
+                .with_misfire_policy(MisfirePolicy::FireAll)
+                .unwrap(),
+            )
+            .await
+            .unwrap();
+        let skip = scheduler
+            .add(
+                Job::new_repeated(core::time::Duration::from_secs(1), |_, _| {})
+                    .unwrap()
+                    .with_misfire_policy(MisfirePolicy::Skip)
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+        let mut skipped = scheduler.subscribe_skipped();
+
+        // Both jobs miss a few ticks before the scheduler starts
+        tokio::time::sleep(core::time::Duration::from_secs(4)).await;
+        scheduler.start().await.unwrap();
+
+        let (job_id, reason) =
+            tokio::time::timeout(core::time::Duration::from_secs(1), skipped.recv())
+                .await
+                .unwrap()
+                .unwrap();
+        assert_eq!(job_id, skip);
+        assert_eq!(reason, SkipReason::Misfire);
+        for _ in 0..3 {
+            let ran = tokio::time::timeout(core::time::Duration::from_millis(500), rx.recv()).await;
+            assert_eq!(ran.unwrap(), Some(fire_all));
+        }
+        scheduler.shutdown().await.unwrap();
+    }
+
+    #[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+    async fn test_max_concurrent_jobs() {
+        use std::sync::atomic::{AtomicUsize, Ordering};
+        use std::sync::Arc;
+
+        let scheduler = JobScheduler::new().await.unwrap();
+        scheduler.set_max_concurrent_jobs(Some(1)).await;
+        let running = Arc::new(AtomicUsize::new(0));
+        let most_running = Arc::new(AtomicUsize::new(0));
+        let (tx, mut rx) = tokio::sync::mpsc::unbounded_channel();
+        let mut guids = vec![];
+        for _ in 0..3 {
+            let running = running.clone();
+            let most_running = most_running.clone();
+            let tx = tx.clone();
+            let job = Job::new_async("0 0 0 1 1 *", move |_, _| {
+                let running = running.clone();
+                let most_running = most_running.clone();
+                let tx = tx.clone();
+                Box::pin(async move {
+                    let now_running = running.fetch_add(1, Ordering::SeqCst) + 1;
+                    most_running.fetch_max(now_running, Ordering::SeqCst);
+                    tokio::time::sleep(core::time::Duration::from_millis(100)).await;
+                    running.fetch_sub(1, Ordering::SeqCst);
+                    tx.send(()).unwrap();
+                })
+            })
+            .unwrap();
+            guids.push(scheduler.add(job).await.unwrap());
+        }
+        for guid in guids {
+            scheduler.run_job_now(guid).await.unwrap();
+        }
+        for _ in 0..3 {
+            let ran = tokio::time::timeout(core::time::Duration::from_secs(2), rx.recv()).await;
+            assert!(matches!(ran, Ok(Some(()))));
+        }
+        assert_eq!(most_running.load(Ordering::SeqCst), 1);
+    }
+
+    #[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+    async fn test_job_builder() {
+        use tokio_cron_scheduler::JobBuilder;
+
+        let scheduler = JobScheduler::new().await.unwrap();
+        let job = JobBuilder::new()
+            .with_cron_schedule("0 0 7 * * *")
+            .with_timezone(chrono_tz::Europe::Amsterdam)
+            .with_async_run(|_, _| Box::pin(async move {}))
+            .with_tags(vec!["reports".to_string()])
+            .with_timeout(core::time::Duration::from_secs(60))
+            .build()
+            .unwrap();
+        let guid = scheduler.add(job).await.unwrap();
+        assert_eq!(scheduler.jobs_by_tag("reports").await.unwrap(), vec![guid]);
+        let ticks = scheduler.upcoming_ticks_for_job(guid, 1).await.unwrap();
+        assert_eq!(
+            ticks[0]
+                .with_timezone(&chrono_tz::Europe::Amsterdam)
+                .format("%H:%M:%S")
+                .to_string(),
+            "07:00:00"
+        );
+
+        let missing_run = JobBuilder::new().with_cron_schedule("0 0 7 * * *").build();
+        assert!(matches!(
+            missing_run,
+            Err(JobSchedulerError::BuilderNeedsField(field)) if field == "run"
+        ));
+    }
+
+    #[cfg(feature = "blocking")]
+    #[test]
+    fn test_blocking_scheduler() {
+        use std::sync::atomic::{AtomicUsize, Ordering};
+        use std::sync::Arc;
+        use tokio_cron_scheduler::BlockingScheduler;
+
+        let mut scheduler = BlockingScheduler::new().unwrap();
+        let runs = Arc::new(AtomicUsize::new(0));
+        let counted = runs.clone();
+        let job = Job::new_repeated(core::time::Duration::from_secs(1), move |_, _| {
+            counted.fetch_add(1, Ordering::SeqCst);
+        })
+        .unwrap();
+        let guid = scheduler.add(job).unwrap();
+        assert_eq!(scheduler.block_on(|s| s.count_jobs()).unwrap(), 1);
+        scheduler.start().unwrap();
+        std::thread::sleep(core::time::Duration::from_millis(2500));
+        assert!(runs.load(Ordering::SeqCst) >= 1);
+        scheduler.remove(&guid).unwrap();
+        scheduler.shutdown().unwrap();
+    }
+
+    #[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+    async fn test_job_metadata() {
+        let scheduler = JobScheduler::new().await.unwrap();
+        let job = Job::new("0 0 7 * * *", |_, _| {})
+            .unwrap()
+            .with_tags(vec!["reports".to_string()])
+            .unwrap();
+        let guid = scheduler.add(job).await.unwrap();
+
+        let data = scheduler.job_metadata(guid).await.unwrap().unwrap();
+        assert_eq!(data.schedule_str(), Some("0 0 7 * * *"));
+        assert_eq!(data.tags, vec!["reports".to_string()]);
+        assert_eq!(data.count, 0);
+        assert!(data.next_tick_utc().is_some());
+
+        let unknown = scheduler.job_metadata(uuid::Uuid::new_v4()).await.unwrap();
+        assert!(unknown.is_none());
+    }
+
+    #[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+    async fn test_run_count() {
+        let scheduler = JobScheduler::new().await.unwrap();
+        let job = Job::new_repeated(core::time::Duration::from_secs(1), |_, _| {}).unwrap();
+        let guid = scheduler.add(job).await.unwrap();
+        assert_eq!(scheduler.run_count(guid).await.unwrap(), 0);
+        scheduler.start().await.unwrap();
+        tokio::time::sleep(core::time::Duration::from_millis(2500)).await;
+        assert!(scheduler.run_count(guid).await.unwrap() >= 1);
+
+        let unknown = scheduler.run_count(uuid::Uuid::new_v4()).await;
+        assert!(matches!(unknown, Err(JobSchedulerError::JobNotFound)));
+    }
+
+    #[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+    async fn test_repeated_limited() {
+        use std::sync::atomic::{AtomicUsize, Ordering};
+        use std::sync::Arc;
+
+        let scheduler = JobScheduler::new().await.unwrap();
+        let runs = Arc::new(AtomicUsize::new(0));
+        let counted = runs.clone();
+        let job = Job::new_repeated_limited(core::time::Duration::from_secs(1), 2, move |_, _| {
+            counted.fetch_add(1, Ordering::SeqCst);
+        })
+        .unwrap();
+        let guid = scheduler.add(job).await.unwrap();
+        scheduler.start().await.unwrap();
+        tokio::time::sleep(core::time::Duration::from_millis(4500)).await;
+        assert_eq!(runs.load(Ordering::SeqCst), 2);
+        assert!(scheduler.job_metadata(guid).await.unwrap().is_none());
+
+        let unlimited = Job::new_repeated_limited(core::time::Duration::from_secs(1), 0, |_, _| {});
+        assert!(matches!(unlimited, Err(JobSchedulerError::InvalidMaxRuns)));
+    }
+
+    #[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+    async fn test_schedule_with_year() {
+        use chrono::TimeZone;
+
+        let scheduler = JobScheduler::new().await.unwrap();
+        let once = scheduler
+            .add(Job::new("0 0 12 1 Jan * 2099", |_, _| {}).unwrap())
+            .await
+            .unwrap();
+        let ticks = scheduler.upcoming_ticks_for_job(once, 3).await.unwrap();
+        assert_eq!(ticks, vec![chrono::Utc.ymd(2099, 1, 1).and_hms(12, 0, 0)]);
+
+        let past = scheduler
+            .add(Job::new("0 0 12 1 Jan * 2017", |_, _| {}).unwrap())
+            .await
+            .unwrap();
+        let data = scheduler.job_metadata(past).await.unwrap().unwrap();
+        assert!(data.next_tick_utc().is_none());
+    }
+
+    #[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+    async fn test_notification_builder() {
+        use tokio_cron_scheduler::NotificationBuilder;
+
+        let scheduler = JobScheduler::new().await.unwrap();
+        let job = Job::new_one_shot(core::time::Duration::from_secs(1), |_, _| {}).unwrap();
+        let (tx, mut rx) = tokio::sync::mpsc::unbounded_channel();
+        let (logged, saved) = (tx.clone(), tx);
+        let ids = NotificationBuilder::new()
+            .on_done(Box::new(move |_, _, _| {
+                let logged = logged.clone();
+                Box::pin(async move {
+                    logged.send("logged").unwrap();
+                })
+            }))
+            .on_done(Box::new(move |_, _, _| {
+                let saved = saved.clone();
+                Box::pin(async move {
+                    saved.send("saved").unwrap();
+                })
+            }))
+            .add_to(&job, &scheduler)
+            .await
+            .unwrap();
+        assert_eq!(ids.len(), 2);
+        scheduler.add(job).await.unwrap();
+        scheduler.start().await.unwrap();
+
+        let mut ran = vec![];
+        for _ in 0..2 {
+            let done = tokio::time::timeout(core::time::Duration::from_secs(3), rx.recv()).await;
+            ran.push(done.unwrap().unwrap());
+        }
+        ran.sort_unstable();
+        assert_eq!(ran, vec!["logged", "saved"]);
+    }
+
+    #[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+    async fn test_running_jobs() {
+        let scheduler = JobScheduler::new().await.unwrap();
+        let job = Job::new_one_shot_async(core::time::Duration::from_secs(1), |_, _| {
+            Box::pin(async move {
+                tokio::time::sleep(core::time::Duration::from_secs(2)).await;
+            })
+        })
+        .unwrap();
+        let guid = scheduler.add(job).await.unwrap();
+        assert!(scheduler.running_jobs().await.unwrap().is_empty());
+        scheduler.start().await.unwrap();
+
+        tokio::time::sleep(core::time::Duration::from_millis(2000)).await;
+        assert_eq!(scheduler.running_jobs().await.unwrap(), vec![guid]);
+        tokio::time::sleep(core::time::Duration::from_millis(2500)).await;
+        assert!(scheduler.running_jobs().await.unwrap().is_empty());
+    }
+
+    #[cfg(feature = "serde")]
+    #[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+    async fn test_job_stored_data_serde() {
+        let scheduler = JobScheduler::new().await.unwrap();
+        let job = Job::new_repeated(core::time::Duration::from_secs(60), |_, _| {}).unwrap();
+        let guid = scheduler.add(job).await.unwrap();
+        let data = scheduler.job_metadata(guid).await.unwrap().unwrap();
+
+        let json = serde_json::to_value(&data).unwrap();
+        assert_eq!(json["next_tick"].as_u64(), Some(data.next_tick));
+        assert_eq!(
+            json["job"]["NonCronJob"]["repeated_every"].as_u64(),
+            Some(60)
+        );
+
+        let back = serde_json::from_value(json).unwrap();
+        assert_eq!(data, back);
+    }
+
+    #[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+    async fn test_recompute_all_ticks() {
+        use tokio_cron_scheduler::store::DataStore;
+
+        let scheduler = JobScheduler::new().await.unwrap();
+        let job = Job::new_repeated(core::time::Duration::from_secs(3600), |_, _| {}).unwrap();
+        let guid = scheduler.add(job).await.unwrap();
+        {
+            let mut w = scheduler.context.metadata_storage.write().await;
+            let mut data = w.get(guid).await.unwrap().unwrap();
+            data.next_tick = 1_000_000;
+            data.count = 3;
+            w.add_or_update(data).await.unwrap();
+        }
+
+        scheduler.recompute_all_ticks().await.unwrap();
+        let data = scheduler.job_metadata(guid).await.unwrap().unwrap();
+        let next_tick = data.next_tick_utc().unwrap();
+        assert!(next_tick > chrono::Utc::now() + chrono::Duration::seconds(3500));
+        assert_eq!(data.count, 3);
+    }
+
+    #[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+    async fn test_orphaned_jobs() {
+        use tokio_cron_scheduler::store::DataStore;
+
+        let scheduler = JobScheduler::new().await.unwrap();
+        let job = Job::new("0 0 7 * * *", |_, _| {}).unwrap();
+        let guid = scheduler.add(job).await.unwrap();
+        assert!(scheduler.orphaned_jobs().await.unwrap().is_empty());
+
+        // A job stored without its code, like one loaded after a restart
+        let orphan = uuid::Uuid::new_v4();
+        let mut data = scheduler.job_metadata(guid).await.unwrap().unwrap();
+        data.id = Some(orphan.into());
+        {
+            let mut w = scheduler.context.metadata_storage.write().await;
+            w.add_or_update(data.clone()).await.unwrap();
+        }
+        assert_eq!(scheduler.orphaned_jobs().await.unwrap(), vec![orphan]);
+
+        let job = Job::from_job_data(data, |_, _| {}).unwrap();
+        scheduler.add_or_update(job).await.unwrap();
+        tokio::time::sleep(core::time::Duration::from_millis(100)).await;
+        assert!(scheduler.orphaned_jobs().await.unwrap().is_empty());
+    }
+
+    #[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+    async fn test_job_removes_itself() {
+        let scheduler = JobScheduler::new().await.unwrap();
+        let job = Job::new("* * * * * *", |uuid, l| {
+            tokio::spawn(async move {
+                l.remove(&uuid).await.unwrap();
+            });
+        })
+        .unwrap();
+        let guid = scheduler.add(job).await.unwrap();
+        scheduler.start().await.unwrap();
+        tokio::time::sleep(core::time::Duration::from_millis(2500)).await;
+        assert!(scheduler.job_metadata(guid).await.unwrap().is_none());
+    }
+
+    #[cfg(feature = "sqlite_storage")]
+    #[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+    async fn test_store_init_error() {
+        use std::sync::Arc;
+        use tokio::sync::RwLock;
+        use tokio_cron_scheduler::{
+            SimpleJobCode, SimpleNotificationCode, SqliteMetadataStore, SqliteNotificationStore,
+            SqliteStore,
+        };
+
+        let store = Arc::new(RwLock::new(SqliteStore::new(
+            "/does/not/exist/tokio_cron_scheduler.db",
+        )));
+        let scheduler = JobScheduler::new_with_storage_and_code(
+            Box::new(SqliteMetadataStore::new(store.clone())),
+            Box::new(SqliteNotificationStore::new(store)),
+            Box::new(SimpleJobCode::default()),
+            Box::new(SimpleNotificationCode::default()),
+        )
+        .await;
+        assert!(matches!(scheduler, Err(JobSchedulerError::StoreInit(_))));
+    }
+
+    #[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+    async fn test_simple_metadata_store_max_finished() {
+        use tokio_cron_scheduler::store::DataStore;
+        use tokio_cron_scheduler::{MetaDataStorage, SimpleMetadataStore};
+
+        let mut store = SimpleMetadataStore::with_max_finished(1);
+        let mut live = Job::new_repeated(core::time::Duration::from_secs(60), |_, _| {}).unwrap();
+        store.add_or_update(live.job_data().unwrap()).await.unwrap();
+
+        let mut finished = vec![];
+        for last_tick in 1..=3 {
+            let mut job =
+                Job::new_one_shot(core::time::Duration::from_secs(60), |_, _| {}).unwrap();
+            let mut data = job.job_data().unwrap();
+            data.next_tick = 0;
+            data.last_tick = Some(last_tick);
+            data.count = 1;
+            finished.push(job.guid());
+            store.add_or_update(data).await.unwrap();
+        }
+        // Not run yet, so never evicted
+        let mut pending =
+            Job::new_one_shot(core::time::Duration::from_secs(60), |_, _| {}).unwrap();
+        let mut data = pending.job_data().unwrap();
+        data.next_tick = 0;
+        store.add_or_update(data).await.unwrap();
+
+        assert_eq!(store.count().await.unwrap(), 3);
+        assert!(store.get(live.guid()).await.unwrap().is_some());
+        assert!(store.get(pending.guid()).await.unwrap().is_some());
+        assert!(store.get(finished[2]).await.unwrap().is_some());
+        assert!(store.get(finished[0]).await.unwrap().is_none());
+    }
+
+    #[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+    async fn test_next_job() {
+        let scheduler = JobScheduler::new().await.unwrap();
+        assert!(scheduler.next_job().await.unwrap().is_none());
+
+        let soon = Job::new_one_shot(core::time::Duration::from_secs(60), |_, _| {}).unwrap();
+        let soon = scheduler.add(soon).await.unwrap();
+        let later = Job::new_one_shot(core::time::Duration::from_secs(120), |_, _| {}).unwrap();
+        let later = scheduler.add(later).await.unwrap();
+
+        let (job_id, next_tick) = scheduler.next_job().await.unwrap().unwrap();
+        assert_eq!(job_id, soon);
+        assert!(next_tick > chrono::Utc::now());
+
+        scheduler.pause(&soon).await.unwrap();
+        let (job_id, _) = scheduler.next_job().await.unwrap().unwrap();
+        assert_eq!(job_id, later);
+    }
+
+    #[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+    async fn test_repeated_with_delay() {
+        let scheduler = JobScheduler::new().await.unwrap();
+        let job = Job::new_repeated_with_delay(
+            core::time::Duration::from_secs(1),
+            core::time::Duration::from_secs(3600),
+            |_, _| {},
+        )
+        .unwrap();
+        let guid = scheduler.add(job).await.unwrap();
+        let next_tick = scheduler
+            .job_metadata(guid)
+            .await
+            .unwrap()
+            .unwrap()
+            .next_tick_utc()
+            .unwrap();
+        assert!(next_tick <= chrono::Utc::now() + chrono::Duration::seconds(1));
+
+        scheduler.start().await.unwrap();
+        tokio::time::sleep(core::time::Duration::from_millis(2500)).await;
+        assert_eq!(scheduler.run_count(guid).await.unwrap(), 1);
+        let data = scheduler.job_metadata(guid).await.unwrap().unwrap();
+        assert_eq!(
+            data.next_tick_utc().unwrap(),
+            data.anchor().unwrap() + chrono::Duration::seconds(3600)
+        );
+    }
+
+    #[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+    async fn test_simulate() {
+        use chrono::TimeZone;
+
+        let scheduler = JobScheduler::new().await.unwrap();
+        let from = chrono::Utc.ymd(2030, 1, 1).and_hms(0, 0, 0);
+        let to = from + chrono::Duration::hours(1);
+
+        let hourly = Job::new("0 0 * * * *", |_, _| panic!("simulated only")).unwrap();
+        let hourly = scheduler.add(hourly).await.unwrap();
+        let anchored = Job::new_repeated_from(
+            from + chrono::Duration::minutes(10),
+            core::time::Duration::from_secs(20 * 60),
+            |_, _| panic!("simulated only"),
+        )
+        .unwrap();
+        let anchored = scheduler.add(anchored).await.unwrap();
+        let paused = Job::new("0 */5 * * * *", |_, _| panic!("simulated only")).unwrap();
+        let paused = scheduler.add(paused).await.unwrap();
+        scheduler.pause(&paused).await.unwrap();
+
+        let plan = scheduler.simulate(from, to).await.unwrap();
+        assert_eq!(
+            plan,
+            vec![
+                (from, hourly),
+                (from + chrono::Duration::minutes(10), anchored),
+                (from + chrono::Duration::minutes(30), anchored),
+                (from + chrono::Duration::minutes(50), anchored),
+                (to, hourly),
+            ]
+        );
+    }
+
+    #[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+    async fn test_remove_notification() {
+        let scheduler = JobScheduler::new().await.unwrap();
+        let mut job = Job::new("0 0 * * * *", |_, _| {}).unwrap();
+        let job_id = job.guid();
+        let removed = job
+            .on_done_notification_add(&scheduler, Box::new(|_, _, _| Box::pin(async {})))
+            .await
+            .unwrap();
+        let kept = job
+            .on_start_notification_add(&scheduler, Box::new(|_, _, _| Box::pin(async {})))
+            .await
+            .unwrap();
+        scheduler.add(job).await.unwrap();
+
+        scheduler.remove_notification(removed).await.unwrap();
+        let notifications = scheduler.notifications_for(&job_id).await.unwrap();
+        assert!(notifications.iter().all(|(id, _)| *id != removed));
+        assert!(notifications.iter().any(|(id, _)| *id == kept));
+
+        let unknown = scheduler.remove_notification(uuid::Uuid::new_v4()).await;
+        assert!(matches!(
+            unknown,
+            Err(JobSchedulerError::NotificationNotFound)
+        ));
+    }
+
+    #[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+    async fn test_subscribe_job_events() {
+        let mut scheduler = JobScheduler::new().await.unwrap();
+        let mut events = scheduler.subscribe();
+        let job = Job::new_one_shot(core::time::Duration::from_secs(1), |_, _| {}).unwrap();
+        let job_id = scheduler.add(job).await.unwrap();
+        scheduler.start().await.unwrap();
+
+        let mut kinds = vec![];
+        while !kinds.contains(&tokio_cron_scheduler::JobNotification::Done) {
+            let event = tokio::time::timeout(core::time::Duration::from_secs(5), events.recv())
+                .await
+                .unwrap()
+                .unwrap();
+            assert_eq!(event.job_id, job_id);
+            assert!(event.at <= chrono::Utc::now());
+            kinds.push(event.kind);
+        }
+        assert!(kinds.contains(&tokio_cron_scheduler::JobNotification::Started));
+        scheduler.shutdown().await.unwrap();
+    }
+
+    #[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+    async fn test_schedule_shorthands() {
+        let scheduler = JobScheduler::new().await.unwrap();
+
+        let daily = Job::new_with_shorthand("@daily", |_, _| {}).unwrap();
+        let daily = scheduler.add(daily).await.unwrap();
+        let hourly = Job::new_async_with_shorthand("@hourly", |_, _| Box::pin(async {})).unwrap();
+        let hourly = scheduler.add(hourly).await.unwrap();
+        let every = Job::new_with_shorthand("@every 1m30s", |_, _| {}).unwrap();
+        let every = scheduler.add(every).await.unwrap();
+        let raw = Job::new_with_shorthand("0 0 7 * * Mon-Fri", |_, _| {}).unwrap();
+        let raw = scheduler.add(raw).await.unwrap();
+
+        use tokio_cron_scheduler::store::DataStore;
+        let mut storage = scheduler.context().metadata_storage.write().await;
+        let daily = storage.get(daily).await.unwrap().unwrap();
+        assert_eq!(daily.schedule_str(), Some("0 0 0 * * *"));
+        let hourly = storage.get(hourly).await.unwrap().unwrap();
+        assert_eq!(hourly.schedule_str(), Some("0 0 * * * *"));
+        let every = storage.get(every).await.unwrap().unwrap();
+        assert_eq!(every.repeated_every(), Some(90));
+        let raw = storage.get(raw).await.unwrap().unwrap();
+        assert_eq!(raw.schedule_str(), Some("0 0 7 * * Mon-Fri"));
+
+        for invalid in ["@fortnightly", "@every", "@every 0s", "@every 10 parsecs"] {
+            assert!(matches!(
+                Job::new_with_shorthand(invalid, |_, _| {}),
+                Err(JobSchedulerError::ParseSchedule)
+            ));
+        }
+    }
+
+    #[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+    async fn test_job_runtime() {
+        let runtime = tokio::runtime::Builder::new_multi_thread()
+            .worker_threads(1)
+            .thread_name("job-runtime")
+            .enable_all()
+            .build()
+            .unwrap();
+        let mut scheduler = JobScheduler::new().await.unwrap();
+        scheduler
+            .set_job_runtime(Some(runtime.handle().clone()))
+            .await;
+        let (tx, rx) = std::sync::mpsc::channel();
+        let tx = std::sync::Mutex::new(tx);
+        let job = Job::new_one_shot(core::time::Duration::from_secs(1), move |_, _| {
+            let name = std::thread::current().name().map(String::from);
+            tx.lock().unwrap().send(name).unwrap();
+        })
+        .unwrap();
+        scheduler.add(job).await.unwrap();
+        scheduler.start().await.unwrap();
+
+        let name = tokio::task::spawn_blocking(move || {
+            rx.recv_timeout(core::time::Duration::from_secs(5))
+        })
+        .await
+        .unwrap()
+        .unwrap();
+        assert_eq!(name.as_deref(), Some("job-runtime"));
+        scheduler.shutdown().await.unwrap();
+        runtime.shutdown_background();
+    }
+
+    #[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+    async fn test_blocking_job_graceful_shutdown() {
+        let mut scheduler = JobScheduler::new().await.unwrap();
+        let mut events = scheduler.subscribe();
+        let finished = std::sync::Arc::new(std::sync::atomic::AtomicBool::new(false));
+        let for_job = finished.clone();
+        let job = Job::new_blocking("* * * * * *", move |_, _| {
+            std::thread::sleep(core::time::Duration::from_millis(1500));
+            for_job.store(true, std::sync::atomic::Ordering::SeqCst);
+        })
+        .unwrap();
+        scheduler.add(job).await.unwrap();
+        scheduler.start().await.unwrap();
+
+        loop {
+            let event = tokio::time::timeout(core::time::Duration::from_secs(5), events.recv())
+                .await
+                .unwrap()
+                .unwrap();
+            if event.kind == tokio_cron_scheduler::JobNotification::Started {
+                break;
+            }
+        }
+        scheduler
+            .shutdown_graceful(core::time::Duration::from_secs(5))
+            .await
+            .unwrap();
+        assert!(finished.load(std::sync::atomic::Ordering::SeqCst));
+    }
+
+    #[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+    async fn test_pause_all() {
+        let mut scheduler = JobScheduler::new().await.unwrap();
+        let runs = std::sync::Arc::new(std::sync::atomic::AtomicUsize::new(0));
+        let for_job = runs.clone();
+        let job = Job::new("* * * * * *", move |_, _| {
+            for_job.fetch_add(1, std::sync::atomic::Ordering::SeqCst);
+        })
+        .unwrap();
+        scheduler.add(job).await.unwrap();
+        scheduler.start().await.unwrap();
+
+        scheduler.pause_all().await;
+        // A run that was already activated may still finish
+        tokio::time::sleep(core::time::Duration::from_millis(1200)).await;
+        let paused_at = runs.load(std::sync::atomic::Ordering::SeqCst);
+        tokio::time::sleep(core::time::Duration::from_millis(2500)).await;
+        assert_eq!(runs.load(std::sync::atomic::Ordering::SeqCst), paused_at);
+
+        scheduler.resume_all().await;
+        tokio::time::sleep(core::time::Duration::from_millis(2500)).await;
+        assert!(runs.load(std::sync::atomic::Ordering::SeqCst) > paused_at);
+        scheduler.shutdown().await.unwrap();
+    }
+
+    #[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+    async fn test_fallible_shutdown_handler() {
+        let mut scheduler = JobScheduler::new().await.unwrap();
+        scheduler.set_shutdown_handler_fallible(Box::new(|| {
+            Box::pin(async move { Err(JobSchedulerError::ShutdownNotifier) })
+        }));
+        scheduler.start().await.unwrap();
+        let shutdown = scheduler.shutdown().await;
+        assert!(matches!(shutdown, Err(JobSchedulerError::ShutdownNotifier)));
+
+        let mut scheduler = JobScheduler::new().await.unwrap();
+        scheduler.set_shutdown_handler_fallible(Box::new(|| Box::pin(async move { Ok(()) })));
+        scheduler.start().await.unwrap();
+        scheduler.shutdown().await.unwrap();
+    }
+
+    #[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+    async fn test_wait_until_started() {
+        let scheduler = JobScheduler::new().await.unwrap();
+        let waiting = {
+            let scheduler = scheduler.clone();
+            tokio::spawn(async move { scheduler.wait_until_started().await })
+        };
+        tokio::time::sleep(core::time::Duration::from_millis(200)).await;
+        assert!(!waiting.is_finished());
+
+        scheduler.start().await.unwrap();
+        tokio::time::timeout(core::time::Duration::from_secs(2), waiting)
+            .await
+            .unwrap()
+            .unwrap();
+        // Already started, returns right away
+        tokio::time::timeout(
+            core::time::Duration::from_millis(100),
+            scheduler.wait_until_started(),
+        )
+        .await
+        .unwrap();
+    }
+
+    #[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+    async fn test_dst_policy() {
+        use chrono::TimeZone;
+        use tokio_cron_scheduler::{DstGapPolicy, DstOverlapPolicy};
+
+        async fn ticks(
+            gap: DstGapPolicy,
+            overlap: DstOverlapPolicy,
+            from: chrono::DateTime<chrono::Utc>,
+        ) -> Vec<chrono::DateTime<chrono::Utc>> {
+            let scheduler = JobScheduler::new().await.unwrap();
+            let job = Job::new_cron_job_tz("0 30 2 * * *", chrono_tz::Europe::Amsterdam, |_, _| {
+                panic!("simulated only")
+            })
+            .unwrap()
+            .with_dst_policy(gap, overlap)
+            .unwrap();
+            scheduler.add(job).await.unwrap();
+            let plan = scheduler
+                .simulate(from, from + chrono::Duration::days(1))
+                .await
+                .unwrap();
+            plan.into_iter().map(|(tick, _)| tick).collect()
+        }
+
+        // The clocks go forward from 02:00 to 03:00 at 01:00 UTC, there's no 02:30
+        let spring = chrono::Utc.ymd(2023, 3, 25).and_hms(12, 0, 0);
+        let first = DstOverlapPolicy::FirstOccurrence;
+        assert_eq!(
+            ticks(DstGapPolicy::ShiftByGap, first, spring).await,
+            vec![chrono::Utc.ymd(2023, 3, 26).and_hms(1, 30, 0)]
+        );
+        assert_eq!(ticks(DstGapPolicy::SkipGap, first, spring).await, vec![]);
+        assert_eq!(
+            ticks(DstGapPolicy::FireAtTransition, first, spring).await,
+            vec![chrono::Utc.ymd(2023, 3, 26).and_hms(1, 0, 0)]
+        );
+
+        // The clocks go back from 03:00 to 02:00 at 01:00 UTC, 02:30 happens twice
+        let autumn = chrono::Utc.ymd(2023, 10, 28).and_hms(12, 0, 0);
+        let shift = DstGapPolicy::ShiftByGap;
+        let first = chrono::Utc.ymd(2023, 10, 29).and_hms(0, 30, 0);
+        let last = chrono::Utc.ymd(2023, 10, 29).and_hms(1, 30, 0);
+        assert_eq!(
+            ticks(shift, DstOverlapPolicy::FirstOccurrence, autumn).await,
+            vec![first]
+        );
+        assert_eq!(
+            ticks(shift, DstOverlapPolicy::LastOccurrence, autumn).await,
+            vec![last]
+        );
+        assert_eq!(
+            ticks(shift, DstOverlapPolicy::BothOccurrences, autumn).await,
+            vec![first, last]
+        );
+    }
+
+    #[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+    async fn test_job_state() {
+        struct Counter {
+            step: usize,
+            total: std::sync::Arc<std::sync::atomic::AtomicUsize>,
+        }
+
+        let mut scheduler = JobScheduler::new().await.unwrap();
+        let ones = std::sync::Arc::new(std::sync::atomic::AtomicUsize::new(0));
+        let tens = std::sync::Arc::new(std::sync::atomic::AtomicUsize::new(0));
+        let counter = |step, total: &std::sync::Arc<std::sync::atomic::AtomicUsize>| Counter {
+            step,
+            total: total.clone(),
+        };
+        let job = Job::new_with_state("* * * * * *", counter(1, &ones), |_, _, state| {
+            assert!(state.downcast_ref::<String>().is_none());
+            let counter = state.downcast_ref::<Counter>().unwrap();
+            counter
+                .total
+                .fetch_add(counter.step, std::sync::atomic::Ordering::SeqCst);
+        })
+        .unwrap();
+        scheduler.add(job).await.unwrap();
+        let job = Job::new_async_with_state("* * * * * *", counter(10, &tens), |_, _, state| {
+            let counter = state.downcast_ref::<Counter>().unwrap();
+            let (step, total) = (counter.step, counter.total.clone());
+            Box::pin(async move {
+                total.fetch_add(step, std::sync::atomic::Ordering::SeqCst);
+            })
+        })
+        .unwrap();
+        scheduler.add(job).await.unwrap();
+        scheduler.start().await.unwrap();
+
+        tokio::time::sleep(core::time::Duration::from_millis(2500)).await;
+        scheduler.shutdown().await.unwrap();
+        let ones = ones.load(std::sync::atomic::Ordering::SeqCst);
+        let tens = tens.load(std::sync::atomic::Ordering::SeqCst);
+        assert!(ones > 0);
+        assert!(tens > 0 && tens % 10 == 0);
+    }
+
+    #[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+    async fn test_manual_clock() {
+        use chrono::TimeZone;
+        use tokio_cron_scheduler::store::DataStore;
+
+        let start = chrono::Utc.ymd(2030, 1, 1).and_hms(0, 0, 0);
+        let clock = tokio_cron_scheduler::ManualClock::new(start);
+        let mut scheduler = JobScheduler::new().await.unwrap();
+        scheduler.set_clock(Box::new(clock.clone())).await;
+        scheduler
+            .set_tick_interval(core::time::Duration::from_millis(50))
+            .await;
+        assert_eq!(scheduler.now().await, start);
+
+        let runs = std::sync::Arc::new(std::sync::atomic::AtomicUsize::new(0));
+        let for_job = runs.clone();
+        let job = Job::new("0 0 * * * *", move |_, _| {
+            for_job.fetch_add(1, std::sync::atomic::Ordering::SeqCst);
+        })
+        .unwrap();
+        let job_id = scheduler.add(job).await.unwrap();
+        scheduler.recompute_all_ticks().await.unwrap();
+        assert_eq!(
+            scheduler.upcoming_ticks_for_job(job_id, 1).await.unwrap(),
+            vec![start + chrono::Duration::hours(1)]
+        );
+        scheduler.start().await.unwrap();
+
+        tokio::time::sleep(core::time::Duration::from_millis(500)).await;
+        assert_eq!(runs.load(std::sync::atomic::Ordering::SeqCst), 0);
+
+        clock.advance(core::time::Duration::from_secs(60 * 60));
+        tokio::time::sleep(core::time::Duration::from_millis(500)).await;
+        assert_eq!(runs.load(std::sync::atomic::Ordering::SeqCst), 1);
+        let data = {
+            let mut storage = scheduler.context().metadata_storage.write().await;
+            storage.get(job_id).await.unwrap().unwrap()
+        };
+        assert_eq!(
+            data.next_tick_utc(),
+            Some(start + chrono::Duration::hours(2))
+        );
+        scheduler.shutdown().await.unwrap();
+    }
+
+    #[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+    async fn test_schedule_horizon() {
+        use chrono::TimeZone;
+        use tokio_cron_scheduler::{CronScheduleParser, ScheduleParser};
+
+        let after = chrono::Utc.ymd(2030, 3, 1).and_hms(0, 0, 0);
+        let leap_day = "0 0 0 29 2 *";
+        assert_eq!(
+            CronScheduleParser::default()
+                .next_after(leap_day, &after)
+                .unwrap(),
+            Some(chrono::Utc.ymd(2032, 2, 29).and_hms(0, 0, 0))
+        );
+        let parser =
+            CronScheduleParser::with_horizon(core::time::Duration::from_secs(365 * 24 * 60 * 60));
+        assert!(matches!(
+            parser.next_after(leap_day, &after),
+            Err(JobSchedulerError::ScheduleNeverFires)
+        ));
+        // Named years aren't held to the horizon
+        assert!(parser
+            .next_after("0 0 12 1 Jan * 2099", &after)
+            .unwrap()
+            .is_some());
+
+        let scheduler = JobScheduler::new().await.unwrap();
+        scheduler.set_schedule_parser(Box::new(parser)).await;
+        scheduler
+            .set_clock(Box::new(tokio_cron_scheduler::ManualClock::new(after)))
+            .await;
+        let job_id = scheduler
+            .add(Job::new("0 0 0 * * *", |_, _| {}).unwrap())
+            .await
+            .unwrap();
+        assert!(matches!(
+            scheduler.update_job_schedule(job_id, leap_day).await,
+            Err(JobSchedulerError::ScheduleNeverFires)
+        ));
+        let data = scheduler.job_metadata(job_id).await.unwrap().unwrap();
+        assert_eq!(data.schedule_str(), Some("0 0 0 * * *"));
+    }
+
+    #[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+    async fn test_add_all() {
+        let mut scheduler = JobScheduler::new().await.unwrap();
+        let runs = std::sync::Arc::new(std::sync::atomic::AtomicUsize::new(0));
+        let jobs = (0..3)
+            .map(|_| {
+                let runs = runs.clone();
+                Job::new("* * * * * *", move |_, _| {
+                    runs.fetch_add(1, std::sync::atomic::Ordering::SeqCst);
+                })
+                .unwrap()
+            })
+            .collect::<Vec<_>>();
+        let expected = jobs.iter().map(|job| job.guid()).collect::<Vec<_>>();
+        let job_ids = scheduler.add_all(jobs).await.unwrap();
+        assert_eq!(job_ids, expected);
+        for job_id in job_ids.iter() {
+            assert!(scheduler.job_metadata(*job_id).await.unwrap().is_some());
+        }
+
+        let fresh = Job::new("* * * * * *", |_, _| {}).unwrap();
+        let fresh_id = fresh.guid();
+        let mut again = Job::new("* * * * * *", |_, _| {}).unwrap();
+        let mut again_data = again.job_data().unwrap();
+        again_data.id = Some(job_ids[0].into());
+        let again = Job::from_job_data(again_data, |_, _| {}).unwrap();
+        assert!(matches!(
+            scheduler.add_all(vec![fresh, again]).await,
+            Err(JobSchedulerError::DuplicateId)
+        ));
+        assert!(scheduler.job_metadata(fresh_id).await.unwrap().is_none());
+
+        scheduler.start().await.unwrap();
+        tokio::time::sleep(core::time::Duration::from_millis(1500)).await;
+        scheduler.shutdown().await.unwrap();
+        assert!(runs.load(std::sync::atomic::Ordering::SeqCst) >= 3);
+    }
+
+    #[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+    async fn test_expires_at() {
+        let mut scheduler = JobScheduler::new().await.unwrap();
+        scheduler.start().await.unwrap();
+
+        let runs = std::sync::Arc::new(std::sync::atomic::AtomicUsize::new(0));
+        let removed = std::sync::Arc::new(std::sync::atomic::AtomicUsize::new(0));
+        let expiring = {
+            let runs = runs.clone();
+            Job::new("* * * * * *", move |_, _| {
+                runs.fetch_add(1, std::sync::atomic::Ordering::SeqCst);
+            })
+            .unwrap()
+            .with_expires_at(chrono::Utc::now() + chrono::Duration::seconds(2))
+            .unwrap()
+        };
+        let expired_runs = std::sync::Arc::new(std::sync::atomic::AtomicUsize::new(0));
+        let mut expired = {
+            let expired_runs = expired_runs.clone();
+            Job::new("* * * * * *", move |_, _| {
+                expired_runs.fetch_add(1, std::sync::atomic::Ordering::SeqCst);
+            })
+            .unwrap()
+            .with_expires_at(chrono::Utc::now() - chrono::Duration::seconds(1))
+            .unwrap()
+        };
+        {
+            let removed = removed.clone();
+            expired
+                .on_removed_notification_add(
+                    &scheduler,
+                    Box::new(move |_, _, _| {
+                        let removed = removed.clone();
+                        Box::pin(async move {
+                            removed.fetch_add(1, std::sync::atomic::Ordering::SeqCst);
+                        })
+                    }),
+                )
+                .await
+                .unwrap();
+        }
+        let expiring_id = scheduler.add(expiring).await.unwrap();
+        let expired_id = scheduler.add(expired).await.unwrap();
+
+        tokio::time::sleep(core::time::Duration::from_millis(4000)).await;
+        assert!(scheduler.job_metadata(expired_id).await.unwrap().is_none());
+        assert!(scheduler.job_metadata(expiring_id).await.unwrap().is_none());
+        assert_eq!(expired_runs.load(std::sync::atomic::Ordering::SeqCst), 0);
+        assert_eq!(removed.load(std::sync::atomic::Ordering::SeqCst), 1);
+        let runs_at_expiry = runs.load(std::sync::atomic::Ordering::SeqCst);
+        assert!(runs_at_expiry <= 3);
+
+        tokio::time::sleep(core::time::Duration::from_millis(1500)).await;
+        assert_eq!(
+            runs.load(std::sync::atomic::Ordering::SeqCst),
+            runs_at_expiry
+        );
+        scheduler.shutdown().await.unwrap();
+    }
+
+    #[test]
+    fn test_day_modifiers() {
+        use chrono::Datelike;
+
+        let after = utc("2024-01-10T00:00:00Z");
+        let last_day = tokio_cron_scheduler::upcoming_for("0 0 12 L * *", after, 3).unwrap();
+        assert_eq!(
+            last_day,
+            vec![
+                utc("2024-01-31T12:00:00Z"),
+                utc("2024-02-29T12:00:00Z"),
+                utc("2024-03-31T12:00:00Z")
+            ]
+        );
+        let last_friday = tokio_cron_scheduler::upcoming_for("0 0 12 * * FriL", after, 3).unwrap();
+        assert_eq!(
+            last_friday,
+            vec![
+                utc("2024-01-26T12:00:00Z"),
+                utc("2024-02-23T12:00:00Z"),
+                utc("2024-03-29T12:00:00Z")
+            ]
+        );
+        assert_eq!(
+            tokio_cron_scheduler::upcoming_for("0 0 12 * * 6L", after, 3).unwrap(),
+            last_friday
+        );
+        let third_monday =
+            tokio_cron_scheduler::upcoming_for("0 0 12 * * Mon#3", after, 3).unwrap();
+        assert_eq!(
+            third_monday,
+            vec![
+                utc("2024-01-15T12:00:00Z"),
+                utc("2024-02-19T12:00:00Z"),
+                utc("2024-03-18T12:00:00Z")
+            ]
+        );
+
+        assert!(tokio_cron_scheduler::validate_schedule("0 0 12 * * Mon#3").is_ok());
+        assert!(tokio_cron_scheduler::validate_schedule("0 0 12 * * Mon#6").is_err());
+        assert!(tokio_cron_scheduler::validate_schedule("0 0 12 1L * *").is_err());
+        let mut job = Job::new_with_shorthand("0 0 12 * * FriL", |_, _| {}).unwrap();
+        let next_tick = job.job_data().unwrap().next_tick_utc().unwrap();
+        assert_eq!(next_tick.weekday(), chrono::Weekday::Fri);
+        assert!((next_tick + chrono::Duration::days(7)).month() != next_tick.month());
+        assert!(matches!(
+            Job::new("0 0 12 * * FriL", |_, _| {}),
+            Err(JobSchedulerError::ParseSchedule)
+        ));
+        assert!(tokio_cron_scheduler::JobBuilder::new()
+            .with_cron_schedule("0 0 12 * * FriL")
+            .with_run_sync(|_, _| {})
+            .build()
+            .is_ok());
+    }
+
+    #[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+    async fn test_drain() {
+        let mut scheduler = JobScheduler::new().await.unwrap();
+        let runs = std::sync::Arc::new(std::sync::atomic::AtomicUsize::new(0));
+        let done = std::sync::Arc::new(std::sync::atomic::AtomicUsize::new(0));
+        let mut job = {
+            let runs = runs.clone();
+            Job::new_async("* * * * * *", move |_, _| {
+                let runs = runs.clone();
+                Box::pin(async move {
+                    tokio::time::sleep(core::time::Duration::from_millis(300)).await;
+                    runs.fetch_add(1, std::sync::atomic::Ordering::SeqCst);
+                })
+            })
+            .unwrap()
+        };
+        {
+            let done = done.clone();
+            job.on_done_notification_add(
+                &scheduler,
+                Box::new(move |_, _, _| {
+                    let done = done.clone();
+                    Box::pin(async move {
+                        done.fetch_add(1, std::sync::atomic::Ordering::SeqCst);
+                    })
+                }),
+            )
+            .await
+            .unwrap();
+        }
+        scheduler.add(job).await.unwrap();
+        scheduler.start().await.unwrap();
+
+        assert!(!scheduler.is_drained().await);
+        scheduler.drain().await;
+        assert!(matches!(
+            scheduler
+                .add(Job::new("* * * * * *", |_, _| {}).unwrap())
+                .await,
+            Err(JobSchedulerError::Draining)
+        ));
+        tokio::time::sleep(core::time::Duration::from_millis(2500)).await;
+        assert!(runs.load(std::sync::atomic::Ordering::SeqCst) >= 1);
+        assert!(done.load(std::sync::atomic::Ordering::SeqCst) >= 1);
+
+        tokio::time::timeout(core::time::Duration::from_secs(5), scheduler.wait_drained())
+            .await
+            .unwrap();
+        scheduler.shutdown().await.unwrap();
+    }
+
+    #[test]
+    fn test_describe_schedule() {
+        use tokio_cron_scheduler::describe_schedule;
+
+        assert_eq!(
+            describe_schedule("0 0 9 * * *").unwrap(),
+            "every day at 09:00"
+        );
+        assert_eq!(
+            describe_schedule("0 30 9 * * Mon-Fri").unwrap(),
+            "at 09:30 on Monday through Friday"
+        );
+        assert_eq!(
+            describe_schedule("0 */5 * * * *").unwrap(),
+            "every 5 minutes"
+        );
+        assert_eq!(
+            describe_schedule("0 15 * * * *").unwrap(),
+            "at minute 15 of every hour"
+        );
+        assert_eq!(
+            describe_schedule("0 0 12 1 Jan,Jul *").unwrap(),
+            "at 12:00 on day 1 of the month in January and July"
+        );
+        assert_eq!(
+            describe_schedule("0 0 12 * * Mon#3").unwrap(),
+            "at 12:00 on the third Monday of the month"
+        );
+        assert_eq!(describe_schedule("@daily").unwrap(), "every day at 00:00");
+        assert_eq!(describe_schedule("@every 30s").unwrap(), "every 30 seconds");
+        assert!(matches!(
+            describe_schedule("0 0 25 * * *"),
+            Err(JobSchedulerError::InvalidSchedule(_))
+        ));
+    }
+
+    #[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+    async fn test_next_ticks_all() {
+        let mut scheduler = JobScheduler::new().await.unwrap();
+        let cron_id = scheduler
+            .add(Job::new("0 0 0 * * *", |_, _| {}).unwrap())
+            .await
+            .unwrap();
+        let one_shot_id = scheduler
+            .add(Job::new_one_shot(core::time::Duration::from_secs(3600), |_, _| {}).unwrap())
+            .await
+            .unwrap();
+
+        let mut next_ticks = scheduler.next_ticks_all().await.unwrap();
+        next_ticks.sort();
+        let mut expected = vec![
+            (cron_id, scheduler.next_tick_for_job(cron_id).await.unwrap()),
+            (
+                one_shot_id,
+                scheduler.next_tick_for_job(one_shot_id).await.unwrap(),
+            ),
+        ];
+        expected.sort();
+        assert_eq!(next_ticks, expected);
+        assert!(next_ticks.iter().all(|(_, next_tick)| next_tick.is_some()));
+    }
+
+    #[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+    async fn test_job_panic_handler() {
+        let mut scheduler = JobScheduler::new().await.unwrap();
+        let panics = std::sync::Arc::new(std::sync::Mutex::new(vec![]));
+        {
+            let panics = panics.clone();
+            scheduler
+                .set_job_panic_handler(Box::new(move |job_id, message, _| {
+                    let panics = panics.clone();
+                    Box::pin(async move {
+                        panics.lock().unwrap().push((job_id, message));
+                    })
+                }))
+                .await;
+        }
+        let mut failed = scheduler.subscribe_failed();
+        let sync_id = scheduler
+            .add(Job::new("* * * * * *", |_, _| panic!("sync job panicked")).unwrap())
+            .await
+            .unwrap();
+        let async_id = scheduler
+            .add(
+                Job::new_async("* * * * * *", |_, _| {
+                    Box::pin(async move { panic!("async job panicked") })
+                })
+                .unwrap(),
+            )
+            .await
+            .unwrap();
+        scheduler.start().await.unwrap();
+
+        let (job_id, message) =
+            tokio::time::timeout(core::time::Duration::from_secs(3), failed.recv())
+                .await
+                .unwrap()
+                .unwrap();
+        assert!(job_id == sync_id || job_id == async_id);
+        assert!(message.ends_with("job panicked"));
+
+        tokio::time::sleep(core::time::Duration::from_millis(2500)).await;
+        scheduler.shutdown().await.unwrap();
+        let panics = panics.lock().unwrap();
+        // The jobs keep their schedule after a panic
+        let sync_panics = panics.iter().filter(|(id, _)| *id == sync_id);
+        assert!(sync_panics.clone().count() >= 2);
+        assert!(sync_panics.all(|(_, message)| message == "sync job panicked"));
+        let async_panics = panics.iter().filter(|(id, _)| *id == async_id);
+        assert!(async_panics.clone().count() >= 2);
+        assert!(async_panics.all(|(_, message)| message == "async job panicked"));
+    }
+
+    #[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+    async fn test_aligned_after_restart() {
+        let restart = utc("2030-01-01T12:00:37Z");
+        let clock = tokio_cron_scheduler::ManualClock::new(restart);
+        let mut scheduler = JobScheduler::new().await.unwrap();
+        scheduler.set_clock(Box::new(clock.clone())).await;
+        scheduler
+            .set_tick_interval(core::time::Duration::from_millis(50))
+            .await;
+
+        // The stored metadata of a minute-aligned job whose next tick drifted to :37
+        let mut job = Job::new("0 * * * * *", |_, _| {}).unwrap();
+        let mut data = job.job_data().unwrap();
+        data.set_last_tick(Some(utc("2030-01-01T11:59:37Z")));
+        data.set_next_tick(Some(utc("2030-01-01T12:00:37Z")));
+
+        let runs = std::sync::Arc::new(std::sync::atomic::AtomicUsize::new(0));
+        let for_job = runs.clone();
+        let job = Job::from_job_data(data, move |_, _| {
+            for_job.fetch_add(1, std::sync::atomic::Ordering::SeqCst);
+        })
+        .unwrap();
+        let guid = scheduler.add_or_update(job).await.unwrap();
+        let data = scheduler.job_metadata(guid).await.unwrap().unwrap();
+        assert_eq!(data.next_tick_utc(), Some(utc("2030-01-01T12:01:00Z")));
+        scheduler.start().await.unwrap();
+
+        tokio::time::sleep(core::time::Duration::from_millis(500)).await;
+        assert_eq!(runs.load(std::sync::atomic::Ordering::SeqCst), 0);
+
+        clock.set(utc("2030-01-01T12:01:00Z"));
+        tokio::time::sleep(core::time::Duration::from_millis(500)).await;
+        assert_eq!(runs.load(std::sync::atomic::Ordering::SeqCst), 1);
+        let data = scheduler.job_metadata(guid).await.unwrap().unwrap();
+        assert_eq!(data.last_tick_utc(), Some(utc("2030-01-01T12:01:00Z")));
+        assert_eq!(data.next_tick_utc(), Some(utc("2030-01-01T12:02:00Z")));
+        scheduler.shutdown().await.unwrap();
+    }
+
+    #[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+    async fn test_priority() {
+        // A single worker starts the runs in the order they're dispatched
+        let runtime = tokio::runtime::Builder::new_multi_thread()
+            .worker_threads(1)
+            .enable_all()
+            .build()
+            .unwrap();
+        let start = utc("2030-01-01T00:00:00Z");
+        let clock = tokio_cron_scheduler::ManualClock::new(start);
+        let mut scheduler = JobScheduler::new().await.unwrap();
+        scheduler.set_clock(Box::new(clock.clone())).await;
+        scheduler
+            .set_tick_interval(core::time::Duration::from_millis(50))
+            .await;
+        scheduler
+            .set_job_runtime(Some(runtime.handle().clone()))
+            .await;
+
+        let order = std::sync::Arc::new(std::sync::Mutex::new(vec![]));
+        for priority in [-5, 0, 10, 3] {
+            let order = order.clone();
+            let job = Job::new("0 * * * * *", move |_, _| {
+                order.lock().unwrap().push(priority);
+            })
+            .unwrap()
+            .with_priority(priority)
+            .unwrap();
+            scheduler.add(job).await.unwrap();
+        }
+        scheduler.recompute_all_ticks().await.unwrap();
+        scheduler.start().await.unwrap();
+
+        clock.advance(core::time::Duration::from_secs(60));
+        tokio::time::sleep(core::time::Duration::from_millis(500)).await;
+        assert_eq!(*order.lock().unwrap(), vec![10, 3, 0, -5]);
+        scheduler.shutdown().await.unwrap();
+        runtime.shutdown_background();
+    }
+
+    #[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+    async fn test_export_import_jobs() {
+        let source = JobScheduler::new().await.unwrap();
+        let cron = Job::new("0 0 7 * * *", |_, _| {})
+            .unwrap()
+            .with_tags(vec!["reports".to_string()])
+            .unwrap();
+        let cron = source.add(cron).await.unwrap();
+        let repeated = Job::new_repeated(core::time::Duration::from_secs(1), |_, _| {}).unwrap();
+        let repeated = source.add(repeated).await.unwrap();
+
+        let mut configs = source.export_jobs().await.unwrap();
+        configs.sort_by_key(|config| config.repeated_every.is_some());
+        assert_eq!(configs.len(), 2);
+        assert_eq!(configs[0].id, cron.to_string());
+        assert_eq!(configs[0].schedule.as_deref(), Some("0 0 7 * * *"));
+        assert_eq!(configs[0].tags, vec!["reports".to_string()]);
+        assert_eq!(configs[1].guid().unwrap(), repeated);
+        assert_eq!(configs[1].repeated_every, Some(1));
+        #[cfg(feature = "serde")]
+        {
+            let json = serde_json::to_string(&configs).unwrap();
+            assert_eq!(configs, serde_json::from_str::<Vec<_>>(&json).unwrap());
+        }
+        configs[0].schedule = Some("0 30 7 * * *".to_string());
+
+        let target = JobScheduler::new().await.unwrap();
+        let runs = std::sync::Arc::new(std::sync::Mutex::new(vec![]));
+        let for_jobs = runs.clone();
+        let imported = target
+            .import_jobs(configs, |job_id| {
+                let runs = for_jobs.clone();
+                Box::new(move |_, _| {
+                    let runs = runs.clone();
+                    Box::pin(async move {
+                        runs.lock().unwrap().push(job_id);
+                    })
+                })
+            })
+            .await
+            .unwrap();
+        assert_eq!(imported, vec![cron, repeated]);
+        let data = target.job_metadata(cron).await.unwrap().unwrap();
+        assert_eq!(data.schedule_str(), Some("0 30 7 * * *"));
+        assert_eq!(target.jobs_by_tag("reports").await.unwrap(), vec![cron]);
+
+        target.start().await.unwrap();
+        tokio::time::sleep(core::time::Duration::from_millis(2500)).await;
+        assert!(runs.lock().unwrap().contains(&repeated));
+        target.shutdown().await.unwrap();
+    }
+
+    #[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+    async fn test_active_window() {
+        let start = utc("2030-12-01T00:00:00Z");
+        let end = utc("2031-01-01T00:00:00Z");
+        assert!(matches!(
+            Job::new("0 0 12 * * *", |_, _| {})
+                .unwrap()
+                .with_active_window(end, start),
+            Err(JobSchedulerError::InvalidActiveWindow)
+        ));
+
+        let clock = tokio_cron_scheduler::ManualClock::new(utc("2030-11-30T12:00:00Z"));
+        let mut scheduler = JobScheduler::new().await.unwrap();
+        scheduler.set_clock(Box::new(clock.clone())).await;
+        scheduler
+            .set_tick_interval(core::time::Duration::from_millis(50))
+            .await;
+        let runs = std::sync::Arc::new(std::sync::atomic::AtomicUsize::new(0));
+        let for_job = runs.clone();
+        let job = Job::new("0 0 12 * * *", move |_, _| {
+            for_job.fetch_add(1, std::sync::atomic::Ordering::SeqCst);
+        })
+        .unwrap()
+        .with_active_window(start, end)
+        .unwrap();
+        let guid = scheduler.add(job).await.unwrap();
+        let data = scheduler.job_metadata(guid).await.unwrap().unwrap();
+        assert_eq!(data.next_tick_utc(), Some(utc("2030-12-01T12:00:00Z")));
+        scheduler.start().await.unwrap();
+
+        tokio::time::sleep(core::time::Duration::from_millis(500)).await;
+        assert_eq!(runs.load(std::sync::atomic::Ordering::SeqCst), 0);
+
+        clock.set(utc("2030-12-01T12:00:00Z"));
+        tokio::time::sleep(core::time::Duration::from_millis(500)).await;
+        assert_eq!(runs.load(std::sync::atomic::Ordering::SeqCst), 1);
+        let data = scheduler.job_metadata(guid).await.unwrap().unwrap();
+        assert_eq!(data.next_tick_utc(), Some(utc("2030-12-02T12:00:00Z")));
+
+        clock.set(end);
+        tokio::time::sleep(core::time::Duration::from_millis(500)).await;
+        assert!(scheduler.job_metadata(guid).await.unwrap().is_none());
+        assert_eq!(runs.load(std::sync::atomic::Ordering::SeqCst), 1);
+        scheduler.shutdown().await.unwrap();
+    }
+
+    #[cfg(not(feature = "has_bytes"))]
+    #[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+    async fn test_flush_on_shutdown() {
+        use std::future::Future;
+        use std::pin::Pin;
+        use std::sync::atomic::{AtomicUsize, Ordering};
+        use std::sync::Arc;
+        use tokio_cron_scheduler::job::job_data::{JobAndNextTick, JobStoredData};
+        use tokio_cron_scheduler::store::{DataStore, InitStore};
+        use tokio_cron_scheduler::{
+            MetaDataStorage, SimpleJobCode, SimpleMetadataStore, SimpleNotificationCode,
+            SimpleNotificationStore,
+        };
+
+        type Pinned<T> = Pin<Box<dyn Future<Output = Result<T, JobSchedulerError>> + Send>>;
+
+        // Counts the flushes, passes everything else on to the simple store
+        struct Flushing {
+            store: SimpleMetadataStore,
+            flushes: Arc<AtomicUsize>,
+        }
+        impl DataStore<JobStoredData> for Flushing {
+            fn get(&mut self, id: uuid::Uuid) -> Pinned<Option<JobStoredData>> {
+                self.store.get(id)
+            }
+            fn add_or_update(&mut self, data: JobStoredData) -> Pinned<()> {
+                self.store.add_or_update(data)
+            }
+            fn delete(&mut self, guid: uuid::Uuid) -> Pinned<()> {
+                self.store.delete(guid)
+            }
+        }
+        impl InitStore for Flushing {
+            fn init(&mut self) -> Pinned<()> {
+                self.store.init()
+            }
+            fn inited(&mut self) -> Pinned<bool> {
+                self.store.inited()
+            }
+        }
+        impl MetaDataStorage for Flushing {
+            fn list_next_ticks(&mut self) -> Pinned<Vec<JobAndNextTick>> {
+                self.store.list_next_ticks()
+            }
+            fn list_jobs(&mut self) -> Pinned<Vec<JobAndNextTick>> {
+                self.store.list_jobs()
+            }
+            fn count(&mut self) -> Pinned<usize> {
+                self.store.count()
+            }
+            fn set_next_and_last_tick(
+                &mut self,
+                guid: uuid::Uuid,
+                next_tick: Option<chrono::DateTime<chrono::Utc>>,
+                last_tick: Option<chrono::DateTime<chrono::Utc>>,
+            ) -> Pinned<()> {
+                self.store
+                    .set_next_and_last_tick(guid, next_tick, last_tick)
+            }
+            fn time_till_next_job(&mut self) -> Pinned<Option<core::time::Duration>> {
+                self.store.time_till_next_job()
+            }
+            fn flush(&mut self) -> Pinned<()> {
+                self.flushes.fetch_add(1, Ordering::SeqCst);
+                Box::pin(std::future::ready(Ok(())))
+            }
+        }
+
+        let flushes = Arc::new(AtomicUsize::new(0));
+        let mut scheduler = JobScheduler::new_with_storage_and_code(
+            Box::new(Flushing {
+                store: SimpleMetadataStore::default(),
+                flushes: flushes.clone(),
+            }),
+            Box::new(SimpleNotificationStore::default()),
+            Box::new(SimpleJobCode::default()),
+            Box::new(SimpleNotificationCode::default()),
+        )
+        .await
+        .unwrap();
+        let flushed_before_handler = Arc::new(AtomicUsize::new(0));
+        let for_handler = (flushes.clone(), flushed_before_handler.clone());
+        scheduler.set_shutdown_handler(Box::new(move || {
+            let (flushes, flushed_before_handler) = for_handler.clone();
+            Box::pin(async move {
+                flushed_before_handler.store(flushes.load(Ordering::SeqCst), Ordering::SeqCst);
+            })
+        }));
+        scheduler.start().await.unwrap();
+        assert_eq!(flushes.load(Ordering::SeqCst), 0);
+
+        scheduler.shutdown().await.unwrap();
+        assert_eq!(flushes.load(Ordering::SeqCst), 1);
+        assert_eq!(flushed_before_handler.load(Ordering::SeqCst), 1);
+    }
+
+    #[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+    async fn test_timeout_aborts_job() {
+        let scheduler = JobScheduler::new().await.unwrap();
+        let mut events = scheduler.subscribe();
+        let finished = std::sync::Arc::new(std::sync::atomic::AtomicBool::new(false));
+        let for_job = finished.clone();
+        let mut job = Job::new_one_shot_async(core::time::Duration::from_secs(1), move |_, _| {
+            let finished = for_job.clone();
+            Box::pin(async move {
+                tokio::time::sleep(core::time::Duration::from_secs(2)).await;
+                finished.store(true, std::sync::atomic::Ordering::SeqCst);
+            })
+        })
+        .unwrap();
+        job.set_timeout(Some(core::time::Duration::from_millis(500)))
+            .unwrap();
+        let job_id = scheduler.add(job).await.unwrap();
+        scheduler.start().await.unwrap();
+
+        loop {
+            let event = tokio::time::timeout(core::time::Duration::from_secs(5), events.recv())
+                .await
+                .unwrap()
+                .unwrap();
+            assert_eq!(event.job_id, job_id);
+            assert_ne!(event.kind, tokio_cron_scheduler::JobNotification::Done);
+            if event.kind == tokio_cron_scheduler::JobNotification::TimedOut {
+                break;
+            }
+        }
+        tokio::time::sleep(core::time::Duration::from_millis(2500)).await;
+        assert!(!finished.load(std::sync::atomic::Ordering::SeqCst));
+    }
+
+    #[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+    async fn test_sleeps_until_next_tick() {
+        let scheduler = JobScheduler::new().await.unwrap();
+        let ticks = std::sync::Arc::new(std::sync::atomic::AtomicUsize::new(0));
+        let counted = ticks.clone();
+        scheduler
+            .set_heartbeat_handler(Box::new(move |_| {
+                counted.fetch_add(1, std::sync::atomic::Ordering::SeqCst);
+                Box::pin(async {})
+            }))
+            .await;
+        let mut events = scheduler.subscribe();
+        let job =
+            Job::new_one_shot_async(
+                core::time::Duration::from_secs(4),
+                |_, _| Box::pin(async {}),
+            )
+            .unwrap();
+        let job_id = scheduler.add(job).await.unwrap();
+        scheduler.start().await.unwrap();
+
+        // Woken up once for the added job, then asleep until its tick
+        tokio::time::sleep(core::time::Duration::from_secs(3)).await;
+        assert!(ticks.load(std::sync::atomic::Ordering::SeqCst) <= 2);
+
+        loop {
+            let event = tokio::time::timeout(core::time::Duration::from_secs(5), events.recv())
+                .await
+                .unwrap()
+                .unwrap();
+            if event.job_id == job_id && event.kind == tokio_cron_scheduler::JobNotification::Done {
+                break;
+            }
+        }
+    }
+
+    #[cfg(feature = "admin-http")]
+    async fn admin_request(addr: std::net::SocketAddr, method: &str, path: &str) -> String {
+        use tokio::io::{AsyncReadExt, AsyncWriteExt};
+        let mut stream = loop {
+            match tokio::net::TcpStream::connect(addr).await {
+                Ok(stream) => break stream,
+                Err(_) => tokio::time::sleep(core::time::Duration::from_millis(50)).await,
+            }
+        };
+        let request = format!(
+            "{} {} HTTP/1.1\r\nHost: localhost\r\nContent-Length: 0\r\nConnection: close\r\n\r\n",
+            method, path
+        );
+        stream.write_all(request.as_bytes()).await.unwrap();
+        let mut response = String::new();
+        stream.read_to_string(&mut response).await.unwrap();
+        response
+    }
+
+    #[cfg(feature = "admin-http")]
+    #[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+    async fn test_admin_http() {
+        let scheduler = JobScheduler::new().await.unwrap();
+        let job = Job::new_async("0 0 0 1 1 *", |_, _| Box::pin(async {})).unwrap();
+        let job_id = scheduler.add(job).await.unwrap();
+        let addr: std::net::SocketAddr = ([127, 0, 0, 1], 38226).into();
+        let admin = scheduler.clone();
+        tokio::spawn(async move { admin.serve_admin(addr).await });
+
+        let ids = admin_request(addr, "GET", "/jobs").await;
+        assert!(ids.starts_with("HTTP/1.1 200"));
+        assert!(ids.contains(&job_id.to_string()));
+
+        let describe = admin_request(addr, "GET", &format!("/jobs/{}", job_id)).await;
+        assert!(describe.starts_with("HTTP/1.1 200"));
+        assert!(describe.contains("0 0 0 1 1 *"));
+
+        let pause = admin_request(addr, "POST", &format!("/jobs/{}/pause", job_id)).await;
+        assert!(pause.starts_with("HTTP/1.1 204"));
+        assert!(
+            scheduler
+                .job_metadata(job_id)
+                .await
+                .unwrap()
+                .unwrap()
+                .stopped
+        );
+        let resume = admin_request(addr, "POST", &format!("/jobs/{}/resume", job_id)).await;
+        assert!(resume.starts_with("HTTP/1.1 204"));
+        assert!(
+            !scheduler
+                .job_metadata(job_id)
+                .await
+                .unwrap()
+                .unwrap()
+                .stopped
+        );
+
+        let invalid = admin_request(addr, "POST", "/jobs/not-a-guid/run_now").await;
+        assert!(invalid.starts_with("HTTP/1.1 400"));
+
+        let remove = admin_request(addr, "DELETE", &format!("/jobs/{}", job_id)).await;
+        assert!(remove.starts_with("HTTP/1.1 204"));
+        let missing = admin_request(addr, "GET", &format!("/jobs/{}", job_id)).await;
+        assert!(missing.starts_with("HTTP/1.1 404"));
+        assert!(missing.contains("JobNotFound"));
+    }
+
+    #[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+    async fn test_boost_priority() {
+        // A single worker starts the runs in the order they're dispatched
+        let runtime = tokio::runtime::Builder::new_multi_thread()
+            .worker_threads(1)
+            .enable_all()
+            .build()
+            .unwrap();
+        let start = utc("2030-01-01T00:00:00Z");
+        let clock = tokio_cron_scheduler::ManualClock::new(start);
+        let mut scheduler = JobScheduler::new().await.unwrap();
+        scheduler.set_clock(Box::new(clock.clone())).await;
+        scheduler
+            .set_tick_interval(core::time::Duration::from_millis(50))
+            .await;
+        scheduler
+            .set_job_runtime(Some(runtime.handle().clone()))
+            .await;
+
+        let order = std::sync::Arc::new(std::sync::Mutex::new(vec![]));
+        let mut job_ids = vec![];
+        for priority in [10, 0] {
+            let order = order.clone();
+            let job = Job::new("0 * * * * *", move |_, _| {
+                order.lock().unwrap().push(priority);
+            })
+            .unwrap()
+            .with_priority(priority)
+            .unwrap();
+            job_ids.push(scheduler.add(job).await.unwrap());
+        }
+        scheduler
+            .boost_priority(&job_ids[1], 20, utc("2030-01-01T00:01:30Z"))
+            .await
+            .unwrap();
+        let data = scheduler.job_metadata(job_ids[1]).await.unwrap().unwrap();
+        assert_eq!(data.priority, 0);
+        assert_eq!(data.boosted_priority, Some(20));
+        scheduler.recompute_all_ticks().await.unwrap();
+        scheduler.start().await.unwrap();
+
+        // Boosted above the other job at 00:01, back to its own priority at 00:02
+        clock.advance(core::time::Duration::from_secs(60));
+        tokio::time::sleep(core::time::Duration::from_millis(500)).await;
+        clock.advance(core::time::Duration::from_secs(60));
+        tokio::time::sleep(core::time::Duration::from_millis(500)).await;
+        assert_eq!(*order.lock().unwrap(), vec![0, 10, 10, 0]);
+
+        assert!(matches!(
+            scheduler
+                .boost_priority(&uuid::Uuid::new_v4(), 20, start)
+                .await,
+            Err(tokio_cron_scheduler::JobSchedulerError::JobNotFound)
+        ));
+        scheduler.shutdown().await.unwrap();
+        runtime.shutdown_background();
+    }
+
+    #[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+    async fn test_fallback() {
+        let mut scheduler = JobScheduler::new().await.unwrap();
+        let mut events = scheduler.subscribe();
+        let fallbacks = std::sync::Arc::new(std::sync::Mutex::new(vec![]));
+        let ran = fallbacks.clone();
+        let failing = Job::new_async_result("* * * * * *", |_, _| {
+            Box::pin(async { Err(tokio_cron_scheduler::JobError::from("service down")) })
+        })
+        .unwrap()
+        .with_fallback(move |job_id, _| {
+            let ran = ran.clone();
+            Box::pin(async move {
+                ran.lock().unwrap().push(job_id);
+            })
+        })
+        .unwrap();
+        let failing_id = scheduler.add(failing).await.unwrap();
+        let succeeding = Job::new_async("* * * * * *", |_, _| Box::pin(async {}))
+            .unwrap()
+            .with_fallback(|_, _| Box::pin(async { panic!("must not run") }))
+            .unwrap();
+        let succeeding_id = scheduler.add(succeeding).await.unwrap();
+        scheduler.start().await.unwrap();
+
+        let mut failed = false;
+        loop {
+            let event = tokio::time::timeout(core::time::Duration::from_secs(5), events.recv())
+                .await
+                .unwrap()
+                .unwrap();
+            assert!(
+                !(event.job_id == succeeding_id
+                    && event.kind == tokio_cron_scheduler::JobNotification::FallbackRan)
+            );
+            if event.job_id != failing_id {
+                continue;
+            }
+            match event.kind {
+                tokio_cron_scheduler::JobNotification::Failed => failed = true,
+                tokio_cron_scheduler::JobNotification::FallbackRan => {
+                    // The fallback runs after the failed run was notified
+                    assert!(failed);
+                    break;
+                }
+                _ => {}
+            }
+        }
+        assert!(fallbacks.lock().unwrap().contains(&failing_id));
+        scheduler.shutdown().await.unwrap();
+    }
+
+    #[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+    async fn test_effective_next_run() {
+        let scheduler = JobScheduler::new().await.unwrap();
+        let job = Job::new("0 0 12 * * *", |_, _| {}).unwrap();
+        let job_id = scheduler.add(job).await.unwrap();
+        let next_tick = scheduler.clone().next_tick_for_job(job_id).await.unwrap();
+        assert!(next_tick.is_some());
+        assert_eq!(
+            scheduler.effective_next_run(&job_id).await.unwrap(),
+            next_tick
+        );
+
+        scheduler.pause(&job_id).await.unwrap();
+        assert_eq!(scheduler.effective_next_run(&job_id).await.unwrap(), None);
+        scheduler.resume(&job_id).await.unwrap();
+        scheduler.pause_all().await;
+        assert_eq!(scheduler.effective_next_run(&job_id).await.unwrap(), None);
+        scheduler.resume_all().await;
+        assert_eq!(
+            scheduler.effective_next_run(&job_id).await.unwrap(),
+            next_tick
+        );
+
+        // Noon on the first day of the window, nothing after the window closes
+        let windowed = Job::new("0 0 12 * * *", |_, _| {})
+            .unwrap()
+            .with_active_window(utc("2040-06-01T00:00:00Z"), utc("2040-07-01T00:00:00Z"))
+            .unwrap();
+        let windowed_id = scheduler.add(windowed).await.unwrap();
+        assert_eq!(
+            scheduler.effective_next_run(&windowed_id).await.unwrap(),
+            Some(utc("2040-06-01T12:00:00Z"))
+        );
+        let closed = Job::new("0 0 12 * * *", |_, _| {})
+            .unwrap()
+            .with_active_window(utc("2040-06-01T13:00:00Z"), utc("2040-06-01T14:00:00Z"))
+            .unwrap();
+        let closed_id = scheduler.add(closed).await.unwrap();
+        assert_eq!(
+            scheduler.effective_next_run(&closed_id).await.unwrap(),
+            None
+        );
+
+        assert!(matches!(
+            scheduler.effective_next_run(&uuid::Uuid::new_v4()).await,
+            Err(JobSchedulerError::JobNotFound)
+        ));
+    }
+
+    #[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+    async fn test_update_retry_policy() {
+        use tokio_cron_scheduler::RetryPolicy;
+        let scheduler = JobScheduler::new().await.unwrap();
+        let mut job =
+            Job::new_async_result("0 0 0 1 1 *", |_, _| Box::pin(async { Ok(()) })).unwrap();
+        job.set_retries(1, core::time::Duration::from_millis(100))
+            .unwrap();
+        let job_id = scheduler.add(job).await.unwrap();
+
+        scheduler
+            .update_retry_policy(
+                &job_id,
+                RetryPolicy::new(4, core::time::Duration::from_secs(2)),
+            )
+            .await
+            .unwrap();
+        let data = scheduler.job_metadata(job_id).await.unwrap().unwrap();
+        assert_eq!(data.retries, 3);
+        assert_eq!(data.retry_backoff_ms, 2000);
+
+        let invalid = scheduler
+            .update_retry_policy(&job_id, RetryPolicy::new(0, core::time::Duration::ZERO))
+            .await;
+        assert!(matches!(
+            invalid,
+            Err(JobSchedulerError::InvalidRetryPolicy(_))
+        ));
+        let data = scheduler.job_metadata(job_id).await.unwrap().unwrap();
+        assert_eq!(data.retries, 3);
+
+        let unknown = scheduler
+            .update_retry_policy(
+                &uuid::Uuid::new_v4(),
+                RetryPolicy::new(2, core::time::Duration::ZERO),
+            )
+            .await;
+        assert!(matches!(unknown, Err(JobSchedulerError::JobNotFound)));
+    }
+
+    #[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+    async fn test_retries() {
+        let mut scheduler = JobScheduler::new().await.unwrap();
+        let mut failed = scheduler.subscribe_failed();
+        let attempts = std::sync::Arc::new(std::sync::Mutex::new(vec![]));
+        let tried = attempts.clone();
+        let mut job = Job::new_async_result("0 0 0 1 1 *", move |_, _| {
+            let tried = tried.clone();
+            Box::pin(async move {
+                tried.lock().unwrap().push(std::time::Instant::now());
+                Err(tokio_cron_scheduler::JobError::from("service down"))
+            })
+        })
+        .unwrap();
+        job.set_retries(2, core::time::Duration::from_millis(300))
+            .unwrap();
+        let job_id = scheduler.add(job).await.unwrap();
+        scheduler.start().await.unwrap();
+        scheduler.run_job_now(job_id).await.unwrap();
+
+        let (failed_id, _) =
+            tokio::time::timeout(core::time::Duration::from_secs(5), failed.recv())
+                .await
+                .unwrap()
+                .unwrap();
+        assert_eq!(failed_id, job_id);
+        let attempts = attempts.lock().unwrap().clone();
+        // The first attempt and two retries, each after the backoff
+        assert_eq!(attempts.len(), 3);
+        for pair in attempts.windows(2) {
+            assert!(pair[1] - pair[0] >= core::time::Duration::from_millis(300));
+        }
+        scheduler.shutdown().await.unwrap();
+    }
+
+    #[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+    async fn test_shutdown_cancels_retry_backoff() {
+        let mut scheduler = JobScheduler::new().await.unwrap();
+        let mut events = scheduler.subscribe();
+        let attempts = std::sync::Arc::new(std::sync::atomic::AtomicUsize::new(0));
+        let tried = attempts.clone();
+        let mut job = Job::new_async_result("0 0 0 1 1 *", move |_, _| {
+            tried.fetch_add(1, std::sync::atomic::Ordering::SeqCst);
+            Box::pin(async { Err(tokio_cron_scheduler::JobError::from("service down")) })
+        })
+        .unwrap();
+        job.set_retries(1, core::time::Duration::from_secs(60))
+            .unwrap();
+        let job_id = scheduler.add(job).await.unwrap();
+        scheduler.start().await.unwrap();
+        scheduler.run_job_now(job_id).await.unwrap();
+        while attempts.load(std::sync::atomic::Ordering::SeqCst) == 0 {
+            tokio::time::sleep(core::time::Duration::from_millis(10)).await;
+        }
+
+        let shut_down_at = std::time::Instant::now();
+        scheduler.shutdown().await.unwrap();
+        // The run ends without waiting out the 60s backoff, and without a retry
+        loop {
+            let event = tokio::time::timeout(core::time::Duration::from_secs(5), events.recv())
+                .await
+                .unwrap()
+                .unwrap();
+            if event.job_id == job_id && event.kind == tokio_cron_scheduler::JobNotification::Done {
+                break;
+            }
+        }
+        assert!(shut_down_at.elapsed() < core::time::Duration::from_secs(5));
+        assert_eq!(attempts.load(std::sync::atomic::Ordering::SeqCst), 1);
+    }
+
+    #[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+    async fn test_jitter_below_max() {
+        let scheduler = JobScheduler::new().await.unwrap();
+        scheduler.set_jitter_seed(7).await;
+        let midnight =
+            tokio_cron_scheduler::upcoming_for("0 0 0 * * *", chrono::Utc::now(), 1).unwrap()[0];
+        let mut offsets = vec![];
+        for _ in 0..20 {
+            let job = Job::new("0 0 0 * * *", |_, _| {})
+                .unwrap()
+                .with_jitter(core::time::Duration::from_millis(2500))
+                .unwrap();
+            let guid = scheduler.add(job).await.unwrap();
+            let next_tick = scheduler.next_tick_for_job(guid).await.unwrap().unwrap();
+            offsets.push((next_tick - midnight).num_seconds());
+        }
+        // Offsets of up to 2.5s land on the second they fall in
+        assert!(offsets.iter().all(|offset| (0..=2).contains(offset)));
+        assert!(offsets.iter().any(|offset| *offset > 0));
+    }
+
+    #[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+    async fn test_middleware_order() {
+        use tokio_cron_scheduler::{JobMiddleware, JobRun, JobRunContext};
+
+        type Calls = std::sync::Arc<std::sync::Mutex<Vec<String>>>;
+        struct Recording {
+            name: &'static str,
+            calls: Calls,
+            contexts: std::sync::Arc<std::sync::Mutex<Vec<JobRunContext>>>,
+        }
+        impl JobMiddleware for Recording {
+            fn wrap(&self, context: JobRunContext, next: JobRun) -> JobRun {
+                let name = self.name;
+                let calls = self.calls.clone();
+                self.contexts.lock().unwrap().push(context);
+                Box::pin(async move {
+                    calls.lock().unwrap().push(format!("{} before", name));
+                    let ran = next.await;
+                    calls.lock().unwrap().push(format!("{} after", name));
+                    ran
+                })
+            }
+        }
+
+        let calls: Calls = Default::default();
+        let contexts = std::sync::Arc::new(std::sync::Mutex::new(vec![]));
+        let scheduler = JobScheduler::new().await.unwrap();
+        for name in ["outer", "inner"] {
+            scheduler
+                .add_job_middleware(Box::new(Recording {
+                    name,
+                    calls: calls.clone(),
+                    contexts: contexts.clone(),
+                }))
+                .await;
+        }
+        let job_calls = calls.clone();
+        let guid = scheduler
+            .add(
+                Job::new("0 0 0 1 1 *", move |_, _| {
+                    job_calls.lock().unwrap().push("job".to_string());
+                })
+                .unwrap(),
+            )
+            .await
+            .unwrap();
+        let before_run = chrono::Utc::now();
+        scheduler.run_job_now(guid).await.unwrap();
+
+        tokio::time::timeout(core::time::Duration::from_secs(2), async {
+            while calls.lock().unwrap().len() < 5 {
+                tokio::time::sleep(core::time::Duration::from_millis(10)).await;
+            }
+        })
+        .await
+        .unwrap();
+        assert_eq!(
+            *calls.lock().unwrap(),
+            vec![
+                "outer before",
+                "inner before",
+                "job",
+                "inner after",
+                "outer after"
+            ]
+        );
+        let contexts = contexts.lock().unwrap().clone();
+        assert_eq!(contexts.len(), 2);
+        // Both middlewares of a run see the same context
+        assert_eq!(contexts[0], contexts[1]);
+        assert_eq!(contexts[0].job_id, guid);
+        assert!(contexts[0].started_at >= before_run - chrono::Duration::seconds(1));
+        assert!(contexts[0].started_at <= chrono::Utc::now());
+    }
+
+    #[cfg(not(feature = "has_bytes"))]
+    #[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+    async fn test_corrupt_stored_schedule_is_quarantined() {
+        use tokio_cron_scheduler::job::job_data::job_stored_data::Job as StoredJob;
+        use tokio_cron_scheduler::store::DataStore;
+        use tokio_cron_scheduler::{
+            SimpleJobCode, SimpleMetadataStore, SimpleNotificationCode, SimpleNotificationStore,
+        };
+
+        // Both jobs are in the store before the scheduler starts, as after a restart
+        let mut store = SimpleMetadataStore::default();
+        let mut good = Job::new("* * * * * *", |_, _| {}).unwrap();
+        let good_id = good.guid();
+        let good_data = good.job_data().unwrap();
+        let first_tick = good_data.next_tick;
+        store.add_or_update(good_data).await.unwrap();
+        let mut bad = Job::new("* * * * * *", |_, _| {}).unwrap();
+        let bad_id = bad.guid();
+        let mut bad_data = bad.job_data().unwrap();
+        match bad_data.job.as_mut() {
+            Some(StoredJob::CronJob(cron)) => cron.schedule = "not a schedule".to_string(),
+            _ => unreachable!("A cron job"),
+        }
+        store.add_or_update(bad_data).await.unwrap();
+
+        let mut scheduler = JobScheduler::new_with_storage_and_code(
+            Box::new(store),
+            Box::new(SimpleNotificationStore::default()),
+            Box::new(SimpleJobCode::default()),
+            Box::new(SimpleNotificationCode::default()),
+        )
+        .await
+        .unwrap();
+        scheduler.start().await.unwrap();
+
+        tokio::time::timeout(core::time::Duration::from_secs(5), async {
+            while !scheduler.quarantined_jobs().await.contains(&bad_id) {
+                tokio::time::sleep(core::time::Duration::from_millis(50)).await;
+            }
+        })
+        .await
+        .unwrap();
+        tokio::time::timeout(core::time::Duration::from_secs(5), async {
+            loop {
+                let data = scheduler.job_metadata(good_id).await.unwrap().unwrap();
+                if data.next_tick > first_tick {
+                    break;
+                }
+                tokio::time::sleep(core::time::Duration::from_millis(50)).await;
+            }
+        })
+        .await
+        .unwrap();
+        assert_eq!(scheduler.quarantined_jobs().await, vec![bad_id]);
+        // Kept as it was, to be inspected and fixed
+        assert!(scheduler.job_metadata(bad_id).await.unwrap().is_some());
+        scheduler.shutdown().await.unwrap();
+    }
+
+    #[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+    async fn test_closed_global_gate() {
+        let mut scheduler = JobScheduler::new().await.unwrap();
+        let mut skipped = scheduler.subscribe_skipped();
+        let mut events = scheduler.subscribe();
+        let runs = std::sync::Arc::new(std::sync::atomic::AtomicUsize::new(0));
+        let mut job_ids = vec![];
+        for _ in 0..2 {
+            let runs = runs.clone();
+            let job = Job::new("* * * * * *", move |_, _| {
+                runs.fetch_add(1, std::sync::atomic::Ordering::SeqCst);
+            })
+            .unwrap();
+            job_ids.push(scheduler.add(job).await.unwrap());
+        }
+        scheduler
+            .set_global_gate(Box::new(|| Box::pin(async { false })))
+            .await;
+        scheduler.start().await.unwrap();
+
+        // Every due job is skipped with the gate as its reason
+        let mut gated = vec![];
+        while !job_ids.iter().all(|id| gated.contains(id)) {
+            let (job_id, reason) =
+                tokio::time::timeout(core::time::Duration::from_secs(3), skipped.recv())
+                    .await
+                    .unwrap()
+                    .unwrap();
+            assert_eq!(reason, tokio_cron_scheduler::SkipReason::GlobalGate);
+            gated.push(job_id);
+        }
+        let mut notified = vec![];
+        while !job_ids.iter().all(|id| notified.contains(id)) {
+            let event = tokio::time::timeout(core::time::Duration::from_secs(3), events.recv())
+                .await
+                .unwrap()
+                .unwrap();
+            assert_ne!(event.kind, tokio_cron_scheduler::JobNotification::Started);
+            if event.kind == tokio_cron_scheduler::JobNotification::Skipped {
+                notified.push(event.job_id);
+            }
+        }
+        assert_eq!(runs.load(std::sync::atomic::Ordering::SeqCst), 0);
+        scheduler.shutdown().await.unwrap();
+    }
+
+    #[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+    async fn test_blocking_job_runs_on_blocking_pool() {
+        let scheduler = JobScheduler::new().await.unwrap();
+        let (ran_tx, mut ran_rx) = tokio::sync::mpsc::unbounded_channel();
+        let mut job = Job::new_blocking("0 0 0 1 1 *", move |_, _| {
+            // Only allowed off the runtime's workers, it panics on a worker thread
+            let blocking = std::panic::catch_unwind(|| {
+                tokio::runtime::Handle::current().block_on(async { true })
+            })
+            .unwrap_or(false);
+            ran_tx.send(blocking).unwrap();
+        })
+        .unwrap();
+        let (done_tx, mut done_rx) = tokio::sync::mpsc::unbounded_channel();
+        job.on_done_notification_add(
+            &scheduler,
+            Box::new(move |job_id, _, _| {
+                let done_tx = done_tx.clone();
+                Box::pin(async move {
+                    done_tx.send(job_id).unwrap();
+                })
+            }),
+        )
+        .await
+        .unwrap();
+        let job_id = scheduler.add(job).await.unwrap();
+        scheduler.run_job_now(job_id).await.unwrap();
+
+        let blocking = tokio::time::timeout(core::time::Duration::from_secs(2), ran_rx.recv())
+            .await
+            .unwrap()
+            .unwrap();
+        assert!(blocking);
+        let done = tokio::time::timeout(core::time::Duration::from_secs(2), done_rx.recv())
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(done, job_id);
+    }
+
+    #[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+    async fn test_last_tick_at_advances() {
+        let mut scheduler = JobScheduler::new().await.unwrap();
+        assert!(scheduler.last_tick_at().await.is_none());
+        scheduler
+            .set_tick_interval(core::time::Duration::from_millis(100))
+            .await;
+        scheduler.start().await.unwrap();
+
+        let first = tokio::time::timeout(core::time::Duration::from_secs(2), async {
+            loop {
+                if let Some(at) = scheduler.last_tick_at().await {
+                    break at;
+                }
+                tokio::time::sleep(core::time::Duration::from_millis(20)).await;
+            }
+        })
+        .await
+        .unwrap();
+        tokio::time::sleep(core::time::Duration::from_millis(500)).await;
+        let later = scheduler.last_tick_at().await.unwrap();
+        assert!(later > first);
+        assert!(later <= chrono::Utc::now());
+        scheduler.shutdown().await.unwrap();
+    }
+
+    #[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+    async fn test_slow_heartbeat_does_not_pile_up() {
+        use std::sync::atomic::{AtomicUsize, Ordering};
+
+        let mut scheduler = JobScheduler::new().await.unwrap();
+        scheduler
+            .set_tick_interval(core::time::Duration::from_millis(100))
+            .await;
+        let calls = std::sync::Arc::new(AtomicUsize::new(0));
+        let counted = calls.clone();
+        scheduler
+            .set_heartbeat_handler(Box::new(move |_| {
+                counted.fetch_add(1, Ordering::SeqCst);
+                Box::pin(async {
+                    tokio::time::sleep(core::time::Duration::from_millis(1500)).await;
+                })
+            }))
+            .await;
+        scheduler.start().await.unwrap();
+        tokio::time::sleep(core::time::Duration::from_secs(2)).await;
+        scheduler.shutdown().await.unwrap();
+
+        // About 20 ticks, but only the ticks that found the handler idle called it
+        let at_shutdown = calls.load(Ordering::SeqCst);
+        assert!((1..=2).contains(&at_shutdown));
+        tokio::time::sleep(core::time::Duration::from_secs(2)).await;
+        assert_eq!(calls.load(Ordering::SeqCst), at_shutdown);
+    }
+
+    #[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+    async fn test_skip_overlapping() {
+        use std::sync::atomic::{AtomicUsize, Ordering};
+
+        let mut scheduler = JobScheduler::new().await.unwrap();
+        let mut skipped = scheduler.subscribe_skipped();
+        let running = std::sync::Arc::new(AtomicUsize::new(0));
+        let most_running = std::sync::Arc::new(AtomicUsize::new(0));
+        let (for_running, for_most) = (running.clone(), most_running.clone());
+        let mut job = Job::new_async("* * * * * *", move |_, _| {
+            let (running, most_running) = (for_running.clone(), for_most.clone());
+            Box::pin(async move {
+                let now_running = running.fetch_add(1, Ordering::SeqCst) + 1;
+                most_running.fetch_max(now_running, Ordering::SeqCst);
+                tokio::time::sleep(core::time::Duration::from_millis(2500)).await;
+                running.fetch_sub(1, Ordering::SeqCst);
+            })
+        })
+        .unwrap();
+        job.set_skip_overlapping(true).unwrap();
+        let job_id = scheduler.add(job).await.unwrap();
+        scheduler.start().await.unwrap();
+
+        // The run takes 2.5s, so a tick or two come while it's going
+        let (skipped_id, reason) =
+            tokio::time::timeout(core::time::Duration::from_secs(5), skipped.recv())
+                .await
+                .unwrap()
+                .unwrap();
+        assert_eq!(skipped_id, job_id);
+        assert_eq!(reason, tokio_cron_scheduler::SkipReason::Overlap);
+        tokio::time::sleep(core::time::Duration::from_secs(2)).await;
+        assert_eq!(most_running.load(Ordering::SeqCst), 1);
+        scheduler.shutdown().await.unwrap();
+    }
+
+    #[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+    async fn test_async_result_error_is_surfaced() {
+        let scheduler = JobScheduler::new().await.unwrap();
+        let mut failed = scheduler.subscribe_failed();
+        let mut events = scheduler.subscribe();
+        let job = Job::new_async_result("0 0 0 1 1 *", |_, _| {
+            Box::pin(async { Err(tokio_cron_scheduler::JobError::from("disk full")) })
+        })
+        .unwrap();
+        let job_id = scheduler.add(job).await.unwrap();
+        scheduler.run_job_now(job_id).await.unwrap();
+
+        let (failed_id, message) =
+            tokio::time::timeout(core::time::Duration::from_secs(2), failed.recv())
+                .await
+                .unwrap()
+                .unwrap();
+        assert_eq!(failed_id, job_id);
+        assert!(message.contains("disk full"));
+
+        // Failed is notified before the run's Done
+        let mut kinds = vec![];
+        while !kinds.contains(&tokio_cron_scheduler::JobNotification::Done) {
+            let event = tokio::time::timeout(core::time::Duration::from_secs(2), events.recv())
+                .await
+                .unwrap()
+                .unwrap();
+            if event.job_id == job_id {
+                kinds.push(event.kind);
+            }
+        }
+        let failed_at = kinds
+            .iter()
+            .position(|kind| *kind == tokio_cron_scheduler::JobNotification::Failed)
+            .expect("Should be notified as failed");
+        let done_at = kinds
+            .iter()
+            .position(|kind| *kind == tokio_cron_scheduler::JobNotification::Done)
+            .unwrap();
+        assert!(failed_at < done_at);
+    }
+
+    #[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+    async fn test_paused_job_does_not_fire() {
+        let mut scheduler = JobScheduler::new().await.unwrap();
+        let mut skipped = scheduler.subscribe_skipped();
+        let (tx, mut rx) = tokio::sync::mpsc::unbounded_channel();
+        let job = Job::new("* * * * * *", move |_, _| {
+            let _ = tx.send(());
+        })
+        .unwrap();
+        let job_id = scheduler.add(job).await.unwrap();
+        scheduler.pause(&job_id).await.unwrap();
+        scheduler.start().await.unwrap();
+
+        // Its ticks come and go without a run
+        for _ in 0..2 {
+            let (skipped_id, reason) =
+                tokio::time::timeout(core::time::Duration::from_secs(3), skipped.recv())
+                    .await
+                    .unwrap()
+                    .unwrap();
+            assert_eq!(skipped_id, job_id);
+            assert_eq!(reason, tokio_cron_scheduler::SkipReason::Paused);
+        }
+        assert!(rx.try_recv().is_err());
+
+        scheduler.resume(&job_id).await.unwrap();
+        let ran = tokio::time::timeout(core::time::Duration::from_secs(3), rx.recv()).await;
+        assert!(matches!(ran, Ok(Some(()))));
+        scheduler.shutdown().await.unwrap();
+    }
+
+    #[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+    async fn test_run_sink_records_each_run() {
+        use std::future::Future;
+        use std::pin::Pin;
+        use tokio_cron_scheduler::{Outcome, RunRecord, RunSink};
+
+        struct Collecting(tokio::sync::mpsc::UnboundedSender<RunRecord>);
+        impl RunSink for Collecting {
+            fn record(
+                &self,
+                record: RunRecord,
+            ) -> Pin<Box<dyn Future<Output = Result<(), JobSchedulerError>> + Send>> {
+                let _ = self.0.send(record);
+                Box::pin(async { Ok(()) })
+            }
+        }
+
+        let scheduler = JobScheduler::new().await.unwrap();
+        let (tx, mut rx) = tokio::sync::mpsc::unbounded_channel();
+        scheduler.set_run_sink(Box::new(Collecting(tx))).await;
+        let runs = std::sync::Arc::new(std::sync::atomic::AtomicUsize::new(0));
+        let job = Job::new_async_result("0 0 0 1 1 *", move |_, _| {
+            let run = runs.fetch_add(1, std::sync::atomic::Ordering::SeqCst);
+            Box::pin(async move {
+                tokio::time::sleep(core::time::Duration::from_millis(100)).await;
+                match run {
+                    0 => Ok(()),
+                    _ => Err(tokio_cron_scheduler::JobError::from("quota exceeded")),
+                }
+            })
+        })
+        .unwrap();
+        let job_id = scheduler.add(job).await.unwrap();
+
+        let mut records = vec![];
+        for _ in 0..2 {
+            scheduler.run_job_now(job_id).await.unwrap();
+            let record = tokio::time::timeout(core::time::Duration::from_secs(2), rx.recv())
+                .await
+                .unwrap()
+                .unwrap();
+            assert_eq!(record.job_id, job_id);
+            assert!(record.finished_at - record.started_at >= chrono::Duration::milliseconds(100));
+            records.push(record);
+        }
+        assert_eq!(records[0].outcome, Outcome::Succeeded);
+        assert_eq!(records[0].error, None);
+        assert_eq!(records[1].outcome, Outcome::Failed);
+        assert!(records[1]
+            .error
+            .as_ref()
+            .unwrap()
+            .contains("quota exceeded"));
+        // One record per run
+        tokio::time::sleep(core::time::Duration::from_millis(200)).await;
+        assert!(rx.try_recv().is_err());
+    }
+
+    #[cfg(not(feature = "has_bytes"))]
+    #[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+    async fn test_remove_all_deletes_each_job_once() {
+        use std::future::Future;
+        use std::pin::Pin;
+        use std::sync::{Arc, Mutex};
+        use tokio_cron_scheduler::job::job_data::{JobAndNextTick, JobStoredData};
+        use tokio_cron_scheduler::store::{DataStore, InitStore};
+        use tokio_cron_scheduler::{
+            MetaDataStorage, SimpleJobCode, SimpleMetadataStore, SimpleNotificationCode,
+            SimpleNotificationStore,
+        };
+
+        type Pinned<T> = Pin<Box<dyn Future<Output = Result<T, JobSchedulerError>> + Send>>;
+
+        // Keeps the deletes, passes everything on to the simple store
+        struct Deleting {
+            store: SimpleMetadataStore,
+            deletes: Arc<Mutex<Vec<Vec<uuid::Uuid>>>>,
+        }
+        impl DataStore<JobStoredData> for Deleting {
+            fn get(&mut self, id: uuid::Uuid) -> Pinned<Option<JobStoredData>> {
+                self.store.get(id)
+            }
+            fn add_or_update(&mut self, data: JobStoredData) -> Pinned<()> {
+                self.store.add_or_update(data)
+            }
+            fn delete(&mut self, guid: uuid::Uuid) -> Pinned<()> {
+                self.deletes.lock().unwrap().push(vec![guid]);
+                self.store.delete(guid)
+            }
+        }
+        impl InitStore for Deleting {
+            fn init(&mut self) -> Pinned<()> {
+                self.store.init()
+            }
+            fn inited(&mut self) -> Pinned<bool> {
+                self.store.inited()
+            }
+        }
+        impl MetaDataStorage for Deleting {
+            fn list_next_ticks(&mut self) -> Pinned<Vec<JobAndNextTick>> {
+                self.store.list_next_ticks()
+            }
+            fn list_jobs(&mut self) -> Pinned<Vec<JobAndNextTick>> {
+                self.store.list_jobs()
+            }
+            fn delete_all(&mut self, guids: Vec<uuid::Uuid>) -> Pinned<()> {
+                self.deletes.lock().unwrap().push(guids.clone());
+                self.store.delete_all(guids)
+            }
+            fn count(&mut self) -> Pinned<usize> {
+                self.store.count()
+            }
+            fn set_next_and_last_tick(
+                &mut self,
+                guid: uuid::Uuid,
+                next_tick: Option<chrono::DateTime<chrono::Utc>>,
+                last_tick: Option<chrono::DateTime<chrono::Utc>>,
+            ) -> Pinned<()> {
+                self.store
+                    .set_next_and_last_tick(guid, next_tick, last_tick)
+            }
+            fn time_till_next_job(&mut self) -> Pinned<Option<core::time::Duration>> {
+                self.store.time_till_next_job()
+            }
+        }
+
+        let deletes = Arc::new(Mutex::new(vec![]));
+        let scheduler = JobScheduler::new_with_storage_and_code(
+            Box::new(Deleting {
+                store: SimpleMetadataStore::default(),
+                deletes: deletes.clone(),
+            }),
+            Box::new(SimpleNotificationStore::default()),
+            Box::new(SimpleJobCode::default()),
+            Box::new(SimpleNotificationCode::default()),
+        )
+        .await
+        .unwrap();
+        let mut ids = vec![];
+        for _ in 0..3 {
+            let job = Job::new("0 0 0 1 1 *", |_, _| {}).unwrap();
+            ids.push(scheduler.add(job).await.unwrap());
+        }
+        scheduler.remove_all().await.unwrap();
+        tokio::time::sleep(core::time::Duration::from_millis(200)).await;
+
+        // One delete of all of the jobs, nothing deleted on its own after
+        let deletes = deletes.lock().unwrap().clone();
+        assert_eq!(deletes.len(), 1);
+        let mut deleted = deletes[0].clone();
+        deleted.sort();
+        ids.sort();
+        assert_eq!(deleted, ids);
+        assert_eq!(scheduler.count_jobs().await.unwrap(), 0);
+    }
+
+    #[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+    async fn test_scheduler_stopped_event() {
+        for graceful in [false, true] {
+            let mut scheduler = JobScheduler::new().await.unwrap();
+            let mut events = scheduler.subscribe();
+            let job = Job::new("0 0 0 1 1 *", |_, _| {}).unwrap();
+            let job_id = scheduler.add(job).await.unwrap();
+            scheduler.start().await.unwrap();
+            if graceful {
+                scheduler
+                    .shutdown_graceful(core::time::Duration::from_secs(1))
+                    .await
+                    .unwrap();
+            } else {
+                scheduler.shutdown().await.unwrap();
+            }
+
+            let event = loop {
+                let event = tokio::time::timeout(core::time::Duration::from_secs(2), events.recv())
+                    .await
+                    .unwrap()
+                    .unwrap();
+                // The job isn't removed by the shutdown
+                assert!(
+                    !(event.job_id == job_id
+                        && event.kind == tokio_cron_scheduler::JobNotification::Removed)
+                );
+                if event.kind == tokio_cron_scheduler::JobNotification::SchedulerStopped {
+                    break event;
+                }
+            };
+            assert!(event.job_id.is_nil());
+            assert!(scheduler.job_metadata(job_id).await.unwrap().is_some());
+        }
+    }
+
+    #[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+    async fn test_jitter_rng() {
+        // Always draws 2.5s into an hour of jitter
+        struct Fixed;
+        impl rand_core::RngCore for Fixed {
+            fn next_u32(&mut self) -> u32 {
+                2500
+            }
+            fn next_u64(&mut self) -> u64 {
+                2500
+            }
+            fn fill_bytes(&mut self, dest: &mut [u8]) {
+                rand_core::impls::fill_bytes_via_next(self, dest)
+            }
+            fn try_fill_bytes(&mut self, dest: &mut [u8]) -> Result<(), rand_core::Error> {
+                self.fill_bytes(dest);
+                Ok(())
+            }
+        }
+
+        let scheduler = JobScheduler::new().await.unwrap();
+        scheduler.set_jitter_rng(Box::new(Fixed)).await;
+        let midnight =
+            tokio_cron_scheduler::upcoming_for("0 0 0 * * *", chrono::Utc::now(), 1).unwrap()[0];
+        for _ in 0..3 {
+            let job = Job::new("0 0 0 * * *", |_, _| {})
+                .unwrap()
+                .with_jitter(core::time::Duration::from_secs(3600))
+                .unwrap();
+            let guid = scheduler.add(job).await.unwrap();
+            let next_tick = scheduler.next_tick_for_job(guid).await.unwrap().unwrap();
+            assert_eq!(next_tick, midnight + chrono::Duration::seconds(2));
+        }
+    }
+
+    #[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+    async fn test_run_job_now_outside_max_runs() {
+        let scheduler = JobScheduler::new().await.unwrap();
+        let (tx, mut rx) = tokio::sync::mpsc::unbounded_channel();
+        let job =
+            Job::new_repeated_limited(core::time::Duration::from_secs(3600), 2, move |_, _| {
+                tx.send(()).unwrap();
+            })
+            .unwrap();
+        let guid = scheduler.add(job).await.unwrap();
+
+        for _ in 0..3 {
+            scheduler.run_job_now(guid).await.unwrap();
+            let ran = tokio::time::timeout(core::time::Duration::from_secs(1), rx.recv()).await;
+            assert!(matches!(ran, Ok(Some(()))));
+        }
+        tokio::time::sleep(core::time::Duration::from_millis(200)).await;
+        let data = scheduler.job_metadata(guid).await.unwrap().unwrap();
+        assert_eq!(data.count, 0);
+        assert_eq!(data.max_runs, Some(2));
+    }
+
+    #[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+    async fn test_first_tick_from_scheduler_parser() {
+        struct WeeklyParser;
+        impl tokio_cron_scheduler::ScheduleParser for WeeklyParser {
+            fn next_after(
+                &self,
+                _schedule: &str,
+                after: &chrono::DateTime<chrono::Utc>,
+            ) -> Result<Option<chrono::DateTime<chrono::Utc>>, JobSchedulerError> {
+                Ok(Some(*after + chrono::Duration::weeks(1)))
+            }
+        }
+
+        let start = utc("2030-01-01T00:00:00Z");
+        let scheduler = JobScheduler::new().await.unwrap();
+        scheduler.set_schedule_parser(Box::new(WeeklyParser)).await;
+        scheduler
+            .set_clock(Box::new(tokio_cron_scheduler::ManualClock::new(start)))
+            .await;
+
+        let cron = scheduler
+            .add(Job::new_cron_job("0 0 * * * *", |_, _| {}).unwrap())
+            .await
+            .unwrap();
+        let data = scheduler.job_metadata(cron).await.unwrap().unwrap();
+        assert_eq!(data.next_tick_utc(), Some(utc("2030-01-08T00:00:00Z")));
+
+        let built = tokio_cron_scheduler::JobBuilder::new()
+            .with_cron_schedule("every week")
+            .with_schedule_parser(Box::new(WeeklyParser))
+            .with_run_sync(|_, _| {})
+            .build()
+            .unwrap();
+        let built = scheduler.add(built).await.unwrap();
+        let data = scheduler.job_metadata(built).await.unwrap().unwrap();
+        assert_eq!(data.next_tick_utc(), Some(utc("2030-01-08T00:00:00Z")));
+    }
+
+    #[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+    async fn test_history_cleared() {
+        let scheduler = JobScheduler::new().await.unwrap();
+        scheduler
+            .set_history_store(Box::new(tokio_cron_scheduler::SimpleHistoryStore::new(5)))
+            .await;
+        let (tx, mut rx) = tokio::sync::mpsc::unbounded_channel();
+        let guid = scheduler
+            .add(
+                Job::new("0 0 0 1 1 *", move |_, _| {
+                    tx.send(()).unwrap();
+                })
+                .unwrap(),
+            )
+            .await
+            .unwrap();
+
+        scheduler.run_job_now(guid).await.unwrap();
+        rx.recv().await.unwrap();
+        tokio::time::sleep(core::time::Duration::from_millis(100)).await;
+        assert_eq!(scheduler.run_history(guid).await.unwrap().len(), 1);
+        scheduler.reset_stats(&guid).await.unwrap();
+        assert!(scheduler.run_history(guid).await.unwrap().is_empty());
+
+        scheduler.run_job_now(guid).await.unwrap();
+        rx.recv().await.unwrap();
+        tokio::time::sleep(core::time::Duration::from_millis(100)).await;
+        assert_eq!(scheduler.run_history(guid).await.unwrap().len(), 1);
+        scheduler.remove(&guid).await.unwrap();
+        tokio::time::sleep(core::time::Duration::from_millis(200)).await;
+        assert!(scheduler.run_history(guid).await.unwrap().is_empty());
+    }
+
+    #[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+    async fn test_graceful_shutdown_takes_no_new_runs() {
+        use std::sync::atomic::{AtomicUsize, Ordering};
+        use std::sync::Arc;
+
+        let mut scheduler = JobScheduler::new().await.unwrap();
+        let started = Arc::new(AtomicUsize::new(0));
+        let finished = Arc::new(AtomicUsize::new(0));
+        let job = {
+            let started = started.clone();
+            let finished = finished.clone();
+            Job::new_async("0 0 0 1 1 *", move |_, _| {
+                let started = started.clone();
+                let finished = finished.clone();
+                Box::pin(async move {
+                    started.fetch_add(1, Ordering::SeqCst);
+                    tokio::time::sleep(core::time::Duration::from_millis(300)).await;
+                    finished.fetch_add(1, Ordering::SeqCst);
+                })
+            })
+            .unwrap()
+        };
+        let guid = scheduler.add(job).await.unwrap();
+        scheduler.start().await.unwrap();
+        scheduler.run_job_now(guid).await.unwrap();
+        tokio::time::sleep(core::time::Duration::from_millis(100)).await;
+
+        let during = scheduler.clone();
+        let late_run = tokio::spawn(async move {
+            tokio::time::sleep(core::time::Duration::from_millis(50)).await;
+            during.run_job_now(guid).await
+        });
+        scheduler
+            .shutdown_graceful(core::time::Duration::from_secs(2))
+            .await
+            .unwrap();
+        late_run.await.unwrap().unwrap();
+        tokio::time::sleep(core::time::Duration::from_millis(400)).await;
+        assert_eq!(started.load(Ordering::SeqCst), 1);
+        assert_eq!(finished.load(Ordering::SeqCst), 1);
+    }
+
+    #[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+    async fn test_pause_all_advances_ticks() {
+        use std::sync::atomic::{AtomicUsize, Ordering};
+
+        let clock = tokio_cron_scheduler::ManualClock::new(utc("2030-01-01T00:00:00Z"));
+        let mut scheduler = JobScheduler::new().await.unwrap();
+        scheduler.set_clock(Box::new(clock.clone())).await;
+        scheduler
+            .set_tick_interval(core::time::Duration::from_millis(50))
+            .await;
+        let runs = std::sync::Arc::new(AtomicUsize::new(0));
+        let for_job = runs.clone();
+        let job = Job::new("0 * * * * *", move |_, _| {
+            for_job.fetch_add(1, Ordering::SeqCst);
+        })
+        .unwrap()
+        .with_misfire_policy(tokio_cron_scheduler::MisfirePolicy::FireAll)
+        .unwrap();
+        let guid = scheduler.add(job).await.unwrap();
+        scheduler.start().await.unwrap();
+
+        scheduler.pause_all().await;
+        clock.set(utc("2030-01-01T00:03:00Z"));
+        tokio::time::sleep(core::time::Duration::from_millis(500)).await;
+        assert_eq!(runs.load(Ordering::SeqCst), 0);
+        let data = scheduler.job_metadata(guid).await.unwrap().unwrap();
+        assert_eq!(data.next_tick_utc(), Some(utc("2030-01-01T00:04:00Z")));
+        assert_eq!(data.last_tick_utc(), Some(utc("2030-01-01T00:03:00Z")));
+
+        // Made up for from 00:01, the first tick missed
+        scheduler.resume_all().await;
+        tokio::time::sleep(core::time::Duration::from_millis(500)).await;
+        assert_eq!(runs.load(Ordering::SeqCst), 3);
+        scheduler.shutdown().await.unwrap();
+    }
+
+    #[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+    async fn test_concurrent_add_of_same_job() {
+        let scheduler = JobScheduler::new().await.unwrap();
+        let data = Job::new("0 0 0 1 1 *", |_, _| {})
+            .unwrap()
+            .job_data()
+            .unwrap();
+        let first = Job::from_job_data(data.clone(), |_, _| {}).unwrap();
+        let second = Job::from_job_data(data, |_, _| {}).unwrap();
+
+        let (first, second) = tokio::join!(scheduler.add(first), scheduler.add(second));
+        let added = [&first, &second].iter().filter(|r| r.is_ok()).count();
+        assert_eq!(added, 1);
+        assert!([first, second]
+            .iter()
+            .any(|r| matches!(r, Err(JobSchedulerError::DuplicateId))));
+    }
 }
@@ -0,0 +1,36 @@
+mod lib;
+use crate::lib::run_example;
+use std::sync::Arc;
+use tokio::sync::RwLock;
+use tokio_cron_scheduler::{
+    JobScheduler, RedisMetadataStore, RedisNotificationStore, RedisStore, SimpleJobCode,
+    SimpleNotificationCode,
+};
+use tracing::Level;
+use tracing_subscriber::FmtSubscriber;
+
+#[tokio::main]
+async fn main() {
+    let subscriber = FmtSubscriber::builder()
+        .with_max_level(Level::DEBUG)
+        .finish();
+    tracing::subscriber::set_global_default(subscriber).expect("Setting default subscriber failed");
+
+    let store = Arc::new(RwLock::new(RedisStore::default()));
+    let metadata_storage = Box::new(RedisMetadataStore::new(store.clone()));
+    let notification_storage = Box::new(RedisNotificationStore::new(store));
+
+    let simple_job_code = Box::new(SimpleJobCode::default());
+    let simple_notification_code = Box::new(SimpleNotificationCode::default());
+
+    let sched = JobScheduler::new_with_storage_and_code(
+        metadata_storage,
+        notification_storage,
+        simple_job_code,
+        simple_notification_code,
+    )
+    .await
+    .unwrap();
+
+    run_example(sched).await;
+}
@@ -0,0 +1,154 @@
+use crate::job::JobConfig;
+use crate::job_scheduler::JobsSchedulerLocked;
+use crate::JobSchedulerError;
+use axum::extract::{Path, State};
+use axum::http::StatusCode;
+use axum::response::{IntoResponse, Response};
+use axum::routing::{get, post};
+use axum::{Json, Router};
+use std::net::SocketAddr;
+use tracing::error;
+use uuid::Uuid;
+
+///
+/// The admin API of a scheduler as an axum router, to mount in an application's own router.
+/// `JobScheduler::serve_admin` serves it on an address of its own. The endpoints, with `:id` a
+/// job's hyphenated GUID:
+///
+/// Method | Path                | Response
+/// -------|---------------------|------------------------------------------------
+/// GET    | `/jobs`             | The GUIDs of all jobs, a JSON array of strings
+/// GET    | `/jobs/:id`         | The job's `JobConfig` as JSON
+/// DELETE | `/jobs/:id`         | Removes the job, `204 No Content`
+/// POST   | `/jobs/:id/pause`   | Pauses the job, `204 No Content`
+/// POST   | `/jobs/:id/resume`  | Resumes the job, `204 No Content`
+/// POST   | `/jobs/:id/run_now` | Runs the job right away, `204 No Content`
+///
+/// An invalid GUID is a `400 Bad Request`, an unknown job a `404 Not Found` and any other error a
+/// `500 Internal Server Error`, each with a JSON body like `{"error": "JobNotFound"}`. The API has
+/// no authentication, only expose it to those allowed to manage the jobs.
+///
+/// ```rust,ignore
+/// let app = Router::new().nest("/admin", admin_router(sched.clone()));
+/// ```
+pub fn admin_router(scheduler: JobsSchedulerLocked) -> Router {
+    Router::new()
+        .route("/jobs", get(job_ids))
+        .route("/jobs/:id", get(describe).delete(remove))
+        .route("/jobs/:id/pause", post(pause))
+        .route("/jobs/:id/resume", post(resume))
+        .route("/jobs/:id/run_now", post(run_now))
+        .with_state(scheduler)
+}
+
+pub(crate) async fn serve(
+    scheduler: JobsSchedulerLocked,
+    addr: SocketAddr,
+) -> Result<(), JobSchedulerError> {
+    axum::Server::try_bind(&addr)
+        .map_err(|e| JobSchedulerError::AdminHttp(e.to_string()))?
+        .serve(admin_router(scheduler).into_make_service())
+        .await
+        .map_err(|e| {
+            error!("Admin API stopped {:?}", e);
+            JobSchedulerError::AdminHttp(e.to_string())
+        })
+}
+
+#[derive(serde::Serialize)]
+struct ErrorBody {
+    error: String,
+}
+
+enum AdminError {
+    InvalidId(String),
+    Scheduler(JobSchedulerError),
+}
+
+impl From<JobSchedulerError> for AdminError {
+    fn from(e: JobSchedulerError) -> Self {
+        Self::Scheduler(e)
+    }
+}
+
+impl IntoResponse for AdminError {
+    fn into_response(self) -> Response {
+        let (status, error) = match self {
+            AdminError::InvalidId(id) => {
+                (StatusCode::BAD_REQUEST, format!("invalid job id {}", id))
+            }
+            AdminError::Scheduler(JobSchedulerError::JobNotFound) => (
+                StatusCode::NOT_FOUND,
+                JobSchedulerError::JobNotFound.to_string(),
+            ),
+            AdminError::Scheduler(e) => {
+                error!("Admin API request failed {:?}", e);
+                (StatusCode::INTERNAL_SERVER_ERROR, e.to_string())
+            }
+        };
+        (status, Json(ErrorBody { error })).into_response()
+    }
+}
+
+fn job_id(id: &str) -> Result<Uuid, AdminError> {
+    Uuid::parse_str(id).map_err(|_| AdminError::InvalidId(id.to_string()))
+}
+
+async fn job_ids(
+    State(scheduler): State<JobsSchedulerLocked>,
+) -> Result<Json<Vec<String>>, AdminError> {
+    let jobs = scheduler.list_jobs().await?;
+    Ok(Json(
+        jobs.into_iter()
+            .map(|(job_id, _, _)| job_id.to_string())
+            .collect(),
+    ))
+}
+
+async fn describe(
+    State(scheduler): State<JobsSchedulerLocked>,
+    Path(id): Path<String>,
+) -> Result<Json<JobConfig>, AdminError> {
+    let data = scheduler
+        .job_metadata(job_id(&id)?)
+        .await?
+        .ok_or(JobSchedulerError::JobNotFound)?;
+    Ok(Json(JobConfig::from(data)))
+}
+
+async fn remove(
+    State(scheduler): State<JobsSchedulerLocked>,
+    Path(id): Path<String>,
+) -> Result<StatusCode, AdminError> {
+    let job_id = job_id(&id)?;
+    // `remove` leaves unknown jobs be, an admin asking for one should hear it's not there
+    if scheduler.job_metadata(job_id).await?.is_none() {
+        return Err(JobSchedulerError::JobNotFound.into());
+    }
+    scheduler.remove(&job_id).await?;
+    Ok(StatusCode::NO_CONTENT)
+}
+
+async fn pause(
+    State(scheduler): State<JobsSchedulerLocked>,
+    Path(id): Path<String>,
+) -> Result<StatusCode, AdminError> {
+    scheduler.pause(&job_id(&id)?).await?;
+    Ok(StatusCode::NO_CONTENT)
+}
+
+async fn resume(
+    State(scheduler): State<JobsSchedulerLocked>,
+    Path(id): Path<String>,
+) -> Result<StatusCode, AdminError> {
+    scheduler.resume(&job_id(&id)?).await?;
+    Ok(StatusCode::NO_CONTENT)
+}
+
+async fn run_now(
+    State(scheduler): State<JobsSchedulerLocked>,
+    Path(id): Path<String>,
+) -> Result<StatusCode, AdminError> {
+    scheduler.run_job_now(job_id(&id)?).await?;
+    Ok(StatusCode::NO_CONTENT)
+}
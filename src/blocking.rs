@@ -0,0 +1,108 @@
+use crate::job::JobLocked;
+use crate::job_scheduler::JobsSchedulerLocked;
+use crate::JobSchedulerError;
+use std::future::Future;
+use std::thread::JoinHandle;
+use tokio::runtime::Handle;
+use tokio::sync::oneshot;
+use tracing::error;
+use uuid::Uuid;
+
+///
+/// A scheduler for code without an async main. It owns a current thread Tokio runtime, driven by
+/// a thread of its own so the jobs keep running in between calls, and blocks on the scheduler's
+/// methods. Dropping it stops the runtime, use `shutdown` first to shut the scheduler down
+/// properly.
+///
+/// The methods panic when called from within a Tokio runtime, use `JobScheduler` there.
+///
+/// ```rust,ignore
+/// let mut sched = BlockingScheduler::new()?;
+/// sched.add(Job::new("1/10 * * * * *", |_uuid, _l| {
+///     println!("I get executed every 10 seconds!");
+/// })?)?;
+/// sched.start()?;
+/// std::thread::sleep(Duration::from_secs(60));
+/// sched.shutdown()?;
+/// ```
+pub struct BlockingScheduler {
+    scheduler: JobsSchedulerLocked,
+    handle: Handle,
+    stop_tx: Option<oneshot::Sender<()>>,
+    runtime_thread: Option<JoinHandle<()>>,
+}
+
+impl BlockingScheduler {
+    pub fn new() -> Result<Self, JobSchedulerError> {
+        let runtime = tokio::runtime::Builder::new_current_thread()
+            .enable_all()
+            .build()
+            .map_err(|e| {
+                error!("Could not build runtime {:?}", e);
+                JobSchedulerError::CantInit
+            })?;
+        let handle = runtime.handle().clone();
+        let (stop_tx, stop_rx) = oneshot::channel::<()>();
+        let runtime_thread = std::thread::Builder::new()
+            .name("tokio-cron-scheduler".to_string())
+            .spawn(move || {
+                runtime.block_on(async move {
+                    let _ = stop_rx.await;
+                });
+            })
+            .map_err(|e| {
+                error!("Could not spawn runtime thread {:?}", e);
+                JobSchedulerError::CantInit
+            })?;
+        Ok(Self {
+            scheduler: handle.block_on(JobsSchedulerLocked::new())?,
+            handle,
+            stop_tx: Some(stop_tx),
+            runtime_thread: Some(runtime_thread),
+        })
+    }
+
+    /// See `JobScheduler::add`
+    pub fn add(&self, job: JobLocked) -> Result<Uuid, JobSchedulerError> {
+        self.handle.block_on(self.scheduler.add(job))
+    }
+
+    /// See `JobScheduler::remove`
+    pub fn remove(&self, job_id: &Uuid) -> Result<(), JobSchedulerError> {
+        self.handle.block_on(self.scheduler.remove(job_id))
+    }
+
+    /// See `JobScheduler::start`
+    pub fn start(&self) -> Result<(), JobSchedulerError> {
+        self.handle.block_on(self.scheduler.start())
+    }
+
+    /// See `JobScheduler::shutdown`
+    pub fn shutdown(&mut self) -> Result<(), JobSchedulerError> {
+        self.handle.block_on(self.scheduler.shutdown())
+    }
+
+    ///
+    /// Block on any of the other `JobScheduler` methods, like
+    /// `sched.block_on(|s| s.count_jobs())`
+    pub fn block_on<'a, F, Fut>(&'a self, f: F) -> Fut::Output
+    where
+        F: FnOnce(&'a JobsSchedulerLocked) -> Fut,
+        Fut: Future + 'a,
+    {
+        self.handle.block_on(f(&self.scheduler))
+    }
+}
+
+impl Drop for BlockingScheduler {
+    fn drop(&mut self) {
+        if let Some(stop_tx) = self.stop_tx.take() {
+            let _ = stop_tx.send(());
+        }
+        if let Some(runtime_thread) = self.runtime_thread.take() {
+            if runtime_thread.join().is_err() {
+                error!("Runtime thread panicked");
+            }
+        }
+    }
+}
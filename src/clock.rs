@@ -0,0 +1,77 @@
+use chrono::{DateTime, Utc};
+use std::sync::{Arc, Mutex};
+
+///
+/// Where the scheduler gets the current time from. Defaults to a [SystemClock], set another one
+/// with `JobScheduler::set_clock` to control time in tests.
+pub trait Clock {
+    fn now(&self) -> DateTime<Utc>;
+
+    /// Whether the clock keeps the system's time. Only then the scheduler's loop sleeps until the
+    /// earliest next tick, with other clocks it checks for due jobs every tick interval.
+    fn is_system(&self) -> bool {
+        false
+    }
+}
+
+///
+/// The clock of the system, `Utc::now()`
+#[derive(Default)]
+pub struct SystemClock {}
+
+impl Clock for SystemClock {
+    fn now(&self) -> DateTime<Utc> {
+        Utc::now()
+    }
+
+    fn is_system(&self) -> bool {
+        true
+    }
+}
+
+///
+/// A clock that only moves when told to. Clones share their time, so keep a clone to advance the
+/// clock after handing it to the scheduler.
+/// ```rust,ignore
+/// let clock = ManualClock::new(Utc.ymd(2030, 1, 1).and_hms(0, 0, 0));
+/// sched.set_clock(Box::new(clock.clone())).await;
+/// clock.advance(Duration::from_secs(60));
+/// ```
+#[derive(Clone)]
+pub struct ManualClock {
+    now: Arc<Mutex<DateTime<Utc>>>,
+}
+
+impl ManualClock {
+    pub fn new(now: DateTime<Utc>) -> Self {
+        Self {
+            now: Arc::new(Mutex::new(now)),
+        }
+    }
+
+    pub fn set(&self, now: DateTime<Utc>) {
+        let mut w = match self.now.lock() {
+            Ok(w) => w,
+            Err(poisoned) => poisoned.into_inner(),
+        };
+        *w = now;
+    }
+
+    pub fn advance(&self, by: std::time::Duration) {
+        let by = chrono::Duration::from_std(by).unwrap_or_else(|_| chrono::Duration::max_value());
+        let mut w = match self.now.lock() {
+            Ok(w) => w,
+            Err(poisoned) => poisoned.into_inner(),
+        };
+        *w = w.checked_add_signed(by).unwrap_or(*w);
+    }
+}
+
+impl Clock for ManualClock {
+    fn now(&self) -> DateTime<Utc> {
+        match self.now.lock() {
+            Ok(now) => *now,
+            Err(poisoned) => *poisoned.into_inner(),
+        }
+    }
+}
@@ -3,7 +3,7 @@ use crate::job::job_data::{JobState, NotificationData};
 #[cfg(feature = "has_bytes")]
 use crate::job::job_data_prost::{JobState, NotificationData};
 use crate::job::to_code::{JobCode, NotificationCode};
-use crate::job::{JobToRunAsync, NotificationId};
+use crate::job::{JobEvent, JobToRunAsync, NotificationId, SkipReason};
 use crate::store::{MetaDataStorage, NotificationStore};
 use crate::{JobSchedulerError, JobStoredData, OnJobNotification};
 use std::sync::Arc;
@@ -16,8 +16,16 @@ pub type NotificationDeletedResult =
 
 pub struct Context {
     pub job_activation_tx: Sender<Uuid>,
+    /// Runs asked for with `JobScheduler::run_job_now`, outside of the job's schedule
+    pub job_run_now_tx: Sender<Uuid>,
     pub notify_tx: Sender<(Uuid, JobState)>,
+    pub skipped_tx: Sender<(Uuid, SkipReason)>,
+    pub failed_tx: Sender<(Uuid, String)>,
+    pub tick_error_tx: Sender<JobSchedulerError>,
+    pub event_tx: Sender<JobEvent>,
     pub job_create_tx: Sender<(JobStoredData, Arc<RwLock<Box<JobToRunAsync>>>)>,
+    /// Jobs added together, whose metadata is stored in one go
+    pub jobs_create_tx: Sender<Vec<(JobStoredData, Arc<RwLock<Box<JobToRunAsync>>>)>>,
     pub job_created_tx: Sender<Result<Uuid, (JobSchedulerError, Option<Uuid>)>>,
     pub job_delete_tx: Sender<Uuid>,
     pub job_deleted_tx: Sender<Result<Uuid, (JobSchedulerError, Option<Uuid>)>>,
@@ -25,6 +33,8 @@ pub struct Context {
     pub notify_created_tx: Sender<Result<Uuid, (JobSchedulerError, Option<Uuid>)>>,
     pub notify_delete_tx: Sender<(Uuid, Option<Vec<JobState>>)>,
     pub notify_deleted_tx: Sender<NotificationDeletedResult>,
+    /// Flips to true once the scheduler shuts down, for the waits of jobs to stop early
+    pub shutdown_tx: Arc<tokio::sync::watch::Sender<bool>>,
     // TODO need to add when notification was deleted and there's no more references to it
     pub metadata_storage: Arc<RwLock<Box<dyn MetaDataStorage + Send + Sync>>>,
     pub notification_storage: Arc<RwLock<Box<dyn NotificationStore + Send + Sync>>>,
@@ -40,8 +50,14 @@ impl Context {
         notification_code: Arc<RwLock<Box<dyn NotificationCode + Send + Sync>>>,
     ) -> Self {
         let (job_activation_tx, _job_activation_rx) = tokio::sync::broadcast::channel(200);
+        let (job_run_now_tx, _job_run_now_rx) = tokio::sync::broadcast::channel(200);
         let (notify_tx, _notify_rx) = tokio::sync::broadcast::channel(200);
+        let (skipped_tx, _skipped_rx) = tokio::sync::broadcast::channel(200);
+        let (failed_tx, _failed_rx) = tokio::sync::broadcast::channel(200);
+        let (tick_error_tx, _tick_error_rx) = tokio::sync::broadcast::channel(200);
+        let (event_tx, _event_rx) = tokio::sync::broadcast::channel(200);
         let (job_create_tx, _job_create_rx) = tokio::sync::broadcast::channel(200);
+        let (jobs_create_tx, _jobs_create_rx) = tokio::sync::broadcast::channel(200);
         let (job_created_tx, _job_created_rx) = tokio::sync::broadcast::channel(200);
         let (job_delete_tx, _job_delete_rx) = tokio::sync::broadcast::channel(200);
         let (job_deleted_tx, _job_deleted_rx) = tokio::sync::broadcast::channel(200);
@@ -49,11 +65,18 @@ impl Context {
         let (notify_created_tx, _notify_created_rx) = tokio::sync::broadcast::channel(200);
         let (notify_delete_tx, _notify_delete_rx) = tokio::sync::broadcast::channel(200);
         let (notify_deleted_tx, _notify_deleted_rx) = tokio::sync::broadcast::channel(200);
+        let (shutdown_tx, _shutdown_rx) = tokio::sync::watch::channel(false);
 
         Self {
             job_activation_tx,
+            job_run_now_tx,
             notify_tx,
+            skipped_tx,
+            failed_tx,
+            tick_error_tx,
+            event_tx,
             job_create_tx,
+            jobs_create_tx,
             job_created_tx,
             job_delete_tx,
             job_deleted_tx,
@@ -61,6 +84,7 @@ impl Context {
             notify_created_tx,
             notify_delete_tx,
             notify_deleted_tx,
+            shutdown_tx: Arc::new(shutdown_tx),
             metadata_storage,
             notification_storage,
             job_code,
@@ -73,8 +97,14 @@ impl Clone for Context {
     fn clone(&self) -> Self {
         Self {
             job_activation_tx: self.job_activation_tx.clone(),
+            job_run_now_tx: self.job_run_now_tx.clone(),
             notify_tx: self.notify_tx.clone(),
+            skipped_tx: self.skipped_tx.clone(),
+            failed_tx: self.failed_tx.clone(),
+            tick_error_tx: self.tick_error_tx.clone(),
+            event_tx: self.event_tx.clone(),
             job_create_tx: self.job_create_tx.clone(),
+            jobs_create_tx: self.jobs_create_tx.clone(),
             job_created_tx: self.job_created_tx.clone(),
             job_delete_tx: self.job_delete_tx.clone(),
             job_deleted_tx: self.job_deleted_tx.clone(),
@@ -82,6 +112,7 @@ impl Clone for Context {
             notify_created_tx: self.notify_created_tx.clone(),
             notify_delete_tx: self.notify_delete_tx.clone(),
             notify_deleted_tx: self.notify_deleted_tx.clone(),
+            shutdown_tx: self.shutdown_tx.clone(),
             metadata_storage: self.metadata_storage.clone(),
             notification_storage: self.notification_storage.clone(),
             job_code: self.job_code.clone(),
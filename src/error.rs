@@ -27,8 +27,23 @@ pub enum JobSchedulerError {
     CantListNextTicks,
     NotifyOnStateError,
     ParseSchedule,
-    #[cfg(feature = "nats_storage")]
+    DuplicateId,
+    JobNotFound,
+    NotificationNotFound,
+    MetadataTooLarge,
+    JobTimedOut,
+    JobPanicked(String),
+    JobsCancelledOnShutdown(Vec<uuid::Uuid>),
+    InvalidSchedule(String),
     BuilderNeedsField(String),
+    InvalidMaxRuns,
+    InvalidActiveWindow,
+    InvalidRetryPolicy(String),
+    ScheduleNeverFires,
+    StoreInit(String),
+    Draining,
+    #[cfg(feature = "admin-http")]
+    AdminHttp(String),
     #[cfg(feature = "nats_storage")]
     NatsCouldNotConnect(String),
     #[cfg(feature = "nats_storage")]
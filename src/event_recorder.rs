@@ -0,0 +1,106 @@
+#[cfg(not(feature = "has_bytes"))]
+use crate::job::job_data::JobState;
+#[cfg(feature = "has_bytes")]
+use crate::job::job_data_prost::JobState;
+use crate::job_scheduler::JobsSchedulerLocked;
+use chrono::{DateTime, Utc};
+use std::sync::Arc;
+use tokio::sync::broadcast::error::RecvError;
+use tokio::sync::RwLock;
+use tracing::warn;
+use uuid::Uuid;
+
+///
+/// A job notification as seen by the [EventRecorder]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RecordedEvent {
+    pub job_id: Uuid,
+    pub state: JobState,
+    pub at: DateTime<Utc>,
+}
+
+///
+/// Records the notifications of all jobs of a scheduler, to check in tests what ran and when.
+/// Only notifications sent after the recorder was attached are recorded.
+/// ```rust,ignore
+/// let recorder = EventRecorder::attach(&sched);
+/// let job_id = sched.add(job).await?;
+/// sched.start().await?;
+/// tokio::time::sleep(Duration::from_secs(5)).await;
+/// recorder.assert_fired(&job_id, 5).await;
+/// ```
+#[derive(Clone)]
+pub struct EventRecorder {
+    events: Arc<RwLock<Vec<RecordedEvent>>>,
+}
+
+impl EventRecorder {
+    pub fn attach(scheduler: &JobsSchedulerLocked) -> Self {
+        let events = Arc::new(RwLock::new(vec![]));
+        let mut rx = scheduler.context().notify_tx.subscribe();
+        let recorded = events.clone();
+        tokio::spawn(async move {
+            loop {
+                match rx.recv().await {
+                    Ok((job_id, state)) => {
+                        let mut w = recorded.write().await;
+                        w.push(RecordedEvent {
+                            job_id,
+                            state,
+                            at: Utc::now(),
+                        });
+                    }
+                    Err(RecvError::Lagged(missed)) => {
+                        warn!("Event recorder missed {} events", missed);
+                    }
+                    Err(RecvError::Closed) => break,
+                }
+            }
+        });
+        Self { events }
+    }
+
+    ///
+    /// All recorded events, in the order they were received
+    pub async fn events(&self) -> Vec<RecordedEvent> {
+        let r = self.events.read().await;
+        r.clone()
+    }
+
+    ///
+    /// The recorded events of one job
+    pub async fn events_for(&self, job_id: &Uuid) -> Vec<RecordedEvent> {
+        let r = self.events.read().await;
+        r.iter().filter(|e| e.job_id == *job_id).cloned().collect()
+    }
+
+    ///
+    /// How many events of a state were recorded, over all jobs
+    pub async fn count(&self, state: JobState) -> usize {
+        let r = self.events.read().await;
+        r.iter().filter(|e| e.state == state).count()
+    }
+
+    ///
+    /// Panics unless the job was started exactly `times` times
+    pub async fn assert_fired(&self, job_id: &Uuid, times: usize) {
+        let fired = self
+            .events_for(job_id)
+            .await
+            .iter()
+            .filter(|e| e.state == JobState::Started)
+            .count();
+        assert_eq!(
+            fired, times,
+            "Expected job {:?} to have fired {} times, it fired {} times",
+            job_id, times, fired
+        );
+    }
+
+    ///
+    /// Forget all recorded events
+    pub async fn clear(&self) {
+        let mut w = self.events.write().await;
+        w.clear();
+    }
+}
@@ -0,0 +1,223 @@
+#[cfg(not(feature = "has_bytes"))]
+use crate::job::job_data::{DstGapPolicy, DstOverlapPolicy, MisfirePolicy};
+#[cfg(feature = "has_bytes")]
+use crate::job::job_data_prost::{DstGapPolicy, DstOverlapPolicy, MisfirePolicy};
+use crate::job::{JobLocked, JobToRun, JobToRunAsync, ScheduleParser};
+use crate::job_scheduler::JobsSchedulerLocked;
+use crate::JobSchedulerError;
+use chrono::{DateTime, Utc};
+use chrono_tz::Tz;
+use std::future::Future;
+use std::pin::Pin;
+use std::time::Duration;
+use uuid::Uuid;
+
+enum Run {
+    Sync(Box<JobToRun>),
+    Async(Box<JobToRunAsync>),
+}
+
+///
+/// Builds a cron job one option at a time, instead of picking the `Job::new_*` constructor for
+/// the combination of options. A schedule and the code to run are needed, everything else is
+/// optional and defaults to what the constructors do.
+///
+/// ```rust,ignore
+/// let job = JobBuilder::new()
+///     .with_cron_schedule("0 0 7 * * Mon-Fri")
+///     .with_timezone(chrono_tz::Europe::Amsterdam)
+///     .with_async_run(|_uuid, _l| Box::pin(async move {
+///         send_report().await;
+///     }))
+///     .with_tags(vec!["reports".to_string()])
+///     .with_timeout(Duration::from_secs(60))
+///     .build()?;
+/// sched.add(job).await?;
+/// ```
+#[derive(Default)]
+pub struct JobBuilder {
+    schedule: Option<String>,
+    schedule_parser: Option<Box<dyn ScheduleParser + Send + Sync>>,
+    timezone: Option<Tz>,
+    dst_policy: Option<(DstGapPolicy, DstOverlapPolicy)>,
+    run: Option<Run>,
+    tags: Vec<String>,
+    timeout: Option<Duration>,
+    skip_overlapping: bool,
+    jitter: Option<Duration>,
+    misfire_policy: Option<MisfirePolicy>,
+    expires_at: Option<DateTime<Utc>>,
+    priority: Option<i32>,
+    active_window: Option<(DateTime<Utc>, DateTime<Utc>)>,
+    fallback: Option<Box<JobToRunAsync>>,
+}
+
+impl JobBuilder {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// The cron schedule, with seconds, see `Job::new`. The descriptor shorthands like `@daily`
+    /// and `@every 30s` are accepted too, see `Job::new_with_shorthand`; an `@every` job has no
+    /// timezone.
+    pub fn with_cron_schedule(mut self, schedule: &str) -> Self {
+        self.schedule = Some(schedule.to_string());
+        self
+    }
+
+    /// Read the schedule with `parser`, for a dialect other than the one of the `cron` crate, see
+    /// `Job::new_cron_job_with_parser`. The descriptor shorthands aren't expanded then. Set the
+    /// same parser on the scheduler with `JobScheduler::set_schedule_parser`.
+    pub fn with_schedule_parser(mut self, parser: Box<dyn ScheduleParser + Send + Sync>) -> Self {
+        self.schedule_parser = Some(parser);
+        self
+    }
+
+    /// Evaluate the schedule on the wall clock of `timezone`, see `Job::new_cron_job_tz`
+    pub fn with_timezone(mut self, timezone: Tz) -> Self {
+        self.timezone = Some(timezone);
+        self
+    }
+
+    /// How the schedule fires around daylight saving changes, see `Job::with_dst_policy`
+    pub fn with_dst_policy(mut self, gap: DstGapPolicy, overlap: DstOverlapPolicy) -> Self {
+        self.dst_policy = Some((gap, overlap));
+        self
+    }
+
+    /// The code to run, replacing any code set before
+    pub fn with_run_sync<T>(mut self, run: T) -> Self
+    where
+        T: 'static,
+        T: FnMut(Uuid, JobsSchedulerLocked) + Send + Sync,
+    {
+        self.run = Some(Run::Sync(Box::new(run)));
+        self
+    }
+
+    /// The async code to run, replacing any code set before
+    pub fn with_async_run<T>(mut self, run: T) -> Self
+    where
+        T: 'static,
+        T: FnMut(Uuid, JobsSchedulerLocked) -> Pin<Box<dyn Future<Output = ()> + Send>>
+            + Send
+            + Sync,
+    {
+        self.run = Some(Run::Async(Box::new(run)));
+        self
+    }
+
+    /// See `Job::with_tags`
+    pub fn with_tags(mut self, tags: Vec<String>) -> Self {
+        self.tags = tags;
+        self
+    }
+
+    /// See `Job::set_timeout`
+    pub fn with_timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = Some(timeout);
+        self
+    }
+
+    /// See `Job::set_skip_overlapping`
+    pub fn with_skip_overlapping(mut self, skip: bool) -> Self {
+        self.skip_overlapping = skip;
+        self
+    }
+
+    /// See `Job::with_jitter`
+    pub fn with_jitter(mut self, max: Duration) -> Self {
+        self.jitter = Some(max);
+        self
+    }
+
+    /// See `Job::with_misfire_policy`
+    pub fn with_misfire_policy(mut self, policy: MisfirePolicy) -> Self {
+        self.misfire_policy = Some(policy);
+        self
+    }
+
+    /// See `Job::with_expires_at`
+    pub fn with_expires_at(mut self, expires_at: DateTime<Utc>) -> Self {
+        self.expires_at = Some(expires_at);
+        self
+    }
+
+    /// See `Job::with_priority`
+    pub fn with_priority(mut self, priority: i32) -> Self {
+        self.priority = Some(priority);
+        self
+    }
+
+    /// See `Job::with_active_window`
+    pub fn with_active_window(mut self, start: DateTime<Utc>, end: DateTime<Utc>) -> Self {
+        self.active_window = Some((start, end));
+        self
+    }
+
+    /// See `Job::with_fallback`
+    pub fn with_fallback<T>(mut self, fallback: T) -> Self
+    where
+        T: 'static,
+        T: FnMut(Uuid, JobsSchedulerLocked) -> Pin<Box<dyn Future<Output = ()> + Send>>
+            + Send
+            + Sync,
+    {
+        self.fallback = Some(Box::new(fallback));
+        self
+    }
+
+    ///
+    /// Create the job. Returns `BuilderNeedsField` when the schedule or the code is missing and
+    /// `ParseSchedule` when the schedule doesn't parse.
+    pub fn build(self) -> Result<JobLocked, JobSchedulerError> {
+        let schedule = self
+            .schedule
+            .ok_or_else(|| JobSchedulerError::BuilderNeedsField("schedule".to_string()))?;
+        let mut job = match (self.run, self.schedule_parser) {
+            (Some(Run::Sync(run)), Some(parser)) => {
+                JobLocked::new_cron_job_with_parser(&schedule, &*parser, run)?
+            }
+            (Some(Run::Async(run)), Some(parser)) => {
+                JobLocked::new_cron_job_with_parser_async(&schedule, &*parser, run)?
+            }
+            (Some(Run::Sync(run)), None) => JobLocked::new_with_shorthand(&*schedule, run)?,
+            (Some(Run::Async(run)), None) => JobLocked::new_async_with_shorthand(&*schedule, run)?,
+            (None, _) => return Err(JobSchedulerError::BuilderNeedsField("run".to_string())),
+        };
+        if let Some(timezone) = self.timezone {
+            job = job.in_timezone(timezone)?;
+        }
+        if let Some((gap, overlap)) = self.dst_policy {
+            job = job.with_dst_policy(gap, overlap)?;
+        }
+        if !self.tags.is_empty() {
+            job = job.with_tags(self.tags)?;
+        }
+        if self.timeout.is_some() {
+            job.set_timeout(self.timeout)?;
+        }
+        if self.skip_overlapping {
+            job.set_skip_overlapping(true)?;
+        }
+        if let Some(jitter) = self.jitter {
+            job = job.with_jitter(jitter)?;
+        }
+        if let Some(policy) = self.misfire_policy {
+            job = job.with_misfire_policy(policy)?;
+        }
+        if let Some(expires_at) = self.expires_at {
+            job = job.with_expires_at(expires_at)?;
+        }
+        if let Some(priority) = self.priority {
+            job = job.with_priority(priority)?;
+        }
+        if let Some((start, end)) = self.active_window {
+            job = job.with_active_window(start, end)?;
+        }
+        if let Some(fallback) = self.fallback {
+            job = job.with_fallback(fallback)?;
+        }
+        Ok(job)
+    }
+}
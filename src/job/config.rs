@@ -0,0 +1,79 @@
+#[cfg(not(feature = "has_bytes"))]
+use crate::job::job_data::job_stored_data::Job as JobKind;
+#[cfg(feature = "has_bytes")]
+use crate::job::job_data_prost::job_stored_data::Job as JobKind;
+use crate::job::validate_schedule;
+use crate::{JobSchedulerError, JobStoredData};
+use uuid::Uuid;
+
+///
+/// A job as it is configured, all the scheduler keeps of it but its code. Get them with
+/// `JobScheduler::export_jobs` and re-create the jobs from them with `JobScheduler::import_jobs`,
+/// for a backup or to move the jobs to another environment. With the `serde` feature it can be
+/// serialized, to JSON for example.
+///
+/// The fields next to `metadata` are there to be read and edited, on import they're applied over
+/// what `metadata` has.
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[derive(Clone, PartialEq, Debug)]
+pub struct JobConfig {
+    /// The job's GUID, hyphenated
+    pub id: String,
+    /// The `JobType`, as its number
+    pub job_type: i32,
+    /// The schedule of a cron job
+    pub schedule: Option<String>,
+    /// Seconds between the runs of a repeated job, or before the run of a one shot job
+    pub repeated_every: Option<u64>,
+    pub tags: Vec<String>,
+    /// The job's metadata as stored: its ticks, run count, timezone and settings
+    pub metadata: JobStoredData,
+}
+
+impl JobConfig {
+    ///
+    /// The job's GUID, `InvalidSchedule` when `id` isn't one
+    pub fn guid(&self) -> Result<Uuid, JobSchedulerError> {
+        Uuid::parse_str(&self.id)
+            .map_err(|_| JobSchedulerError::InvalidSchedule(format!("invalid job id {}", self.id)))
+    }
+
+    ///
+    /// The metadata to re-create the job with, `metadata` with the other fields applied
+    pub(crate) fn into_job_data(self) -> Result<JobStoredData, JobSchedulerError> {
+        let guid = self.guid()?;
+        let mut data = self.metadata;
+        data.id = Some(guid.into());
+        data.job_type = self.job_type;
+        data.tags = self.tags;
+        match data.job.as_mut() {
+            Some(JobKind::CronJob(cron_job)) => {
+                if let Some(schedule) = self.schedule {
+                    validate_schedule(&schedule)?;
+                    cron_job.schedule = schedule;
+                }
+            }
+            Some(JobKind::NonCronJob(non_cron_job)) => {
+                if let Some(repeated_every) = self.repeated_every {
+                    non_cron_job.repeated_every = repeated_every;
+                }
+            }
+            None => {}
+        }
+        Ok(data)
+    }
+}
+
+impl From<JobStoredData> for JobConfig {
+    fn from(data: JobStoredData) -> Self {
+        let id = data.id.as_ref().map(Uuid::from).unwrap_or_else(Uuid::nil);
+        Self {
+            id: id.to_string(),
+            job_type: data.job_type,
+            schedule: data.schedule_str().map(String::from),
+            repeated_every: data.repeated_every(),
+            tags: data.tags.clone(),
+            metadata: data,
+        }
+    }
+}
@@ -1,4 +1,5 @@
 use crate::context::Context;
+use crate::job::runner::RUN_JOB;
 use crate::job::{JobLocked, JobToRunAsync};
 use crate::store::MetaDataStorage;
 use crate::{JobSchedulerError, JobStoredData};
@@ -52,15 +53,61 @@ impl JobCreator {
         }
     }
 
+    async fn listen_to_batch_additions(
+        storage: Arc<RwLock<Box<dyn MetaDataStorage + Send + Sync>>>,
+        mut rx: Receiver<Vec<(JobStoredData, Arc<RwLock<Box<JobToRunAsync>>>)>>,
+        tx_created: Sender<Result<Uuid, (JobSchedulerError, Option<Uuid>)>>,
+    ) {
+        loop {
+            let val = rx.recv().await;
+            if let Err(e) = val {
+                error!("Error receiving {:?}", e);
+                break;
+            }
+            let jobs = val.unwrap();
+            // The batch is reported as a whole, under its first job
+            let uuid: Uuid = match jobs.first().and_then(|(data, _)| data.id.as_ref()) {
+                Some(uuid) => uuid.into(),
+                None => {
+                    if let Err(e) = tx_created.send(Err((JobSchedulerError::CantAdd, None))) {
+                        error!("Error sending creation error {:?}", e);
+                    }
+                    continue;
+                }
+            };
+            let saved = {
+                let mut storage = storage.write().await;
+                let jobs = jobs.into_iter().map(|(data, _)| data).collect();
+                storage.add_all(jobs).await
+            };
+            let created = match saved {
+                Ok(()) => Ok(uuid),
+                Err(e) => {
+                    error!("Error saving the metadata of a batch of jobs {:?}", e);
+                    Err((e, Some(uuid)))
+                }
+            };
+            if let Err(e) = tx_created.send(created) {
+                error!("Error sending created jobs {:?}", e);
+            }
+        }
+    }
+
     pub fn init(
         &self,
         context: &Context,
     ) -> Pin<Box<dyn Future<Output = Result<(), JobSchedulerError>> + Send>> {
         let rx = context.job_create_tx.subscribe();
+        let batch_rx = context.jobs_create_tx.subscribe();
         let tx_created = context.job_created_tx.clone();
         let storage = context.metadata_storage.clone();
 
         Box::pin(async move {
+            tokio::spawn(JobCreator::listen_to_batch_additions(
+                storage.clone(),
+                batch_rx,
+                tx_created.clone(),
+            ));
             tokio::spawn(JobCreator::listen_to_additions(storage, rx, tx_created));
             Ok(())
         })
@@ -78,33 +125,7 @@ impl JobCreator {
             return Err(e);
         }
         let data = data.unwrap();
-        let job: Box<JobToRunAsync> = Box::new(move |job_id, job_scheduler| {
-            let job = job.clone();
-            Box::pin(async move {
-                let job_done = {
-                    let w = job.0.write();
-                    if let Err(e) = w {
-                        error!("Error getting job {:?}", e);
-                        return;
-                    }
-                    let mut w = w.unwrap();
-                    w.run(job_scheduler)
-                };
-                let job_done = job_done.await;
-                match job_done {
-                    Err(e) => {
-                        error!("Error running job {:?} {:?}", job_id, e);
-                    }
-                    Ok(val) => {
-                        if !val {
-                            error!("Error running job {:?}", job_id);
-                        }
-                    }
-                }
-            })
-        });
-
-        let job = Arc::new(RwLock::new(job));
+        let job = Arc::new(RwLock::new(JobCreator::code(job)));
         if let Err(_e) = tx.send((data, job)) {
             error!("Error sending new job");
             return Err(JobSchedulerError::CantAdd);
@@ -128,4 +149,86 @@ impl JobCreator {
 
         Err(JobSchedulerError::CantAdd)
     }
+
+    ///
+    /// Add the jobs with a single write of their metadata, see `MetaDataStorage::add_all`
+    pub async fn add_all(
+        context: &Context,
+        jobs: Vec<JobLocked>,
+    ) -> Result<Vec<Uuid>, JobSchedulerError> {
+        let tx = context.jobs_create_tx.clone();
+        let mut rx = context.job_created_tx.subscribe();
+
+        let mut uuids = Vec::with_capacity(jobs.len());
+        let mut batch = Vec::with_capacity(jobs.len());
+        for mut job in jobs {
+            let data = job.job_data().map_err(|e| {
+                error!("Error getting job data {e:?}");
+                e
+            })?;
+            uuids.push(job.guid());
+            batch.push((data, Arc::new(RwLock::new(JobCreator::code(job)))));
+        }
+        if uuids.is_empty() {
+            return Ok(uuids);
+        }
+        if let Err(_e) = tx.send(batch) {
+            error!("Error sending new jobs");
+            return Err(JobSchedulerError::CantAdd);
+        }
+
+        let first = uuids[0];
+        while let Ok(val) = rx.recv().await {
+            match val {
+                Ok(ret_uuid) => {
+                    if ret_uuid == first {
+                        return Ok(uuids);
+                    }
+                }
+                Err((e, Some(ret_uuid))) => {
+                    if ret_uuid == first {
+                        return Err(e);
+                    }
+                }
+                _ => {}
+            }
+        }
+
+        Err(JobSchedulerError::CantAdd)
+    }
+
+    ///
+    /// The code the runner calls to run the job
+    fn code(job: JobLocked) -> Box<JobToRunAsync> {
+        Box::new(move |job_id, job_scheduler| {
+            let job = job.clone();
+            Box::pin(async move {
+                // Handed to the runner, to run the job's fallback if the run fails
+                if let Ok(run_job) = RUN_JOB.try_with(Arc::clone) {
+                    if let Ok(mut w) = run_job.lock() {
+                        *w = Some(job.clone());
+                    }
+                }
+                let job_done = {
+                    // A run that panicked poisoned the lock, the job runs on regardless
+                    let mut w = match job.0.write() {
+                        Ok(w) => w,
+                        Err(poisoned) => poisoned.into_inner(),
+                    };
+                    w.run(job_scheduler)
+                };
+                let job_done = job_done.await;
+                match job_done {
+                    Err(e) => {
+                        error!("Error running job {:?} {:?}", job_id, e);
+                    }
+                    Ok(val) => {
+                        if !val {
+                            error!("Error running job {:?}", job_id);
+                        }
+                    }
+                }
+            })
+        })
+    }
 }
@@ -0,0 +1,92 @@
+use crate::JobSchedulerError;
+use chrono::{Datelike, NaiveDate, Weekday};
+
+///
+/// The days a schedule using the `L` and `#` modifiers fires on, on top of what the `cron` crate
+/// makes of the rest of the schedule
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub(crate) struct DayFilter {
+    /// `L` in the day of month field, the last day of the month
+    last_day_of_month: bool,
+    /// `FriL` or `6L` in the day of week field, the last Friday of the month
+    last_weekday: Option<Weekday>,
+    /// `Mon#3` or `2#3` in the day of week field, the third Monday of the month
+    nth_weekday: Option<(Weekday, u32)>,
+}
+
+impl DayFilter {
+    pub(crate) fn matches(&self, date: NaiveDate) -> bool {
+        let in_next_week = date + chrono::Duration::days(7);
+        let tomorrow = date + chrono::Duration::days(1);
+        if self.last_day_of_month && tomorrow.month() == date.month() {
+            return false;
+        }
+        if let Some(weekday) = self.last_weekday {
+            if date.weekday() != weekday || in_next_week.month() == date.month() {
+                return false;
+            }
+        }
+        if let Some((weekday, nth)) = self.nth_weekday {
+            if date.weekday() != weekday || (date.day() - 1) / 7 + 1 != nth {
+                return false;
+            }
+        }
+        true
+    }
+}
+
+///
+/// Take the `L` and `#` modifiers out of a cron schedule. `L` in the day of month field is the
+/// last day of the month. In the day of week field `FriL` is the last Friday of the month and
+/// `Mon#3` the third Monday; the weekday can be a name or a number, `1` being Sunday like in the
+/// rest of the schedule. Returns the schedule with those fields as `*` for the `cron` crate, and
+/// the days to keep of what it gives. A schedule without the modifiers is passed on as is.
+pub(crate) fn split(schedule: &str) -> Result<(String, Option<DayFilter>), JobSchedulerError> {
+    let mut fields = schedule.split_whitespace().collect::<Vec<_>>();
+    if fields.len() < 6 {
+        return Ok((schedule.to_string(), None));
+    }
+    let mut filter = DayFilter::default();
+    let day_of_month = fields[3];
+    if day_of_month.eq_ignore_ascii_case("L") {
+        filter.last_day_of_month = true;
+        fields[3] = "*";
+    } else if day_of_month.contains(|c: char| c.eq_ignore_ascii_case(&'L')) {
+        return Err(JobSchedulerError::ParseSchedule);
+    }
+    let day_of_week = fields[5];
+    if let Some((weekday, nth)) = day_of_week.split_once('#') {
+        let nth = match nth.parse() {
+            Ok(nth) if (1..=5).contains(&nth) => nth,
+            _ => return Err(JobSchedulerError::ParseSchedule),
+        };
+        filter.nth_weekday = Some((parse_weekday(weekday)?, nth));
+        fields[5] = "*";
+    } else if let Some(weekday) = day_of_week
+        .strip_suffix('L')
+        .or_else(|| day_of_week.strip_suffix('l'))
+    {
+        filter.last_weekday = Some(parse_weekday(weekday)?);
+        fields[5] = "*";
+    }
+    if filter == DayFilter::default() {
+        return Ok((schedule.to_string(), None));
+    }
+    Ok((fields.join(" "), Some(filter)))
+}
+
+///
+/// A single weekday, `1` to `7` from Sunday or its name
+pub(crate) fn parse_weekday(weekday: &str) -> Result<Weekday, JobSchedulerError> {
+    let weekday = match weekday.to_ascii_lowercase().as_str() {
+        "1" | "sun" | "sunday" => Weekday::Sun,
+        "2" | "mon" | "monday" => Weekday::Mon,
+        "3" | "tue" | "tuesday" => Weekday::Tue,
+        "4" | "wed" | "wednesday" => Weekday::Wed,
+        "5" | "thu" | "thursday" => Weekday::Thu,
+        "6" | "fri" | "friday" => Weekday::Fri,
+        "7" | "sat" | "saturday" => Weekday::Sat,
+        _ => return Err(JobSchedulerError::ParseSchedule),
+    };
+    Ok(weekday)
+}
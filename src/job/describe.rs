@@ -0,0 +1,243 @@
+use crate::job::day_filter::parse_weekday;
+use crate::job::shorthand::{self, Shorthand};
+use crate::job::validate_schedule;
+use crate::JobSchedulerError;
+use chrono::Weekday;
+use std::time::Duration;
+
+const MONTHS: [&str; 12] = [
+    "January",
+    "February",
+    "March",
+    "April",
+    "May",
+    "June",
+    "July",
+    "August",
+    "September",
+    "October",
+    "November",
+    "December",
+];
+
+const ORDINALS: [&str; 5] = ["first", "second", "third", "fourth", "fifth"];
+
+///
+/// What a single cron field comes down to
+enum Field {
+    /// `*` or `?`
+    Any,
+    /// A single value
+    At(u32),
+    /// `*/n`
+    Every(u32),
+    /// Lists, ranges and steps from a start, already in words
+    Words(String),
+}
+
+///
+/// Describe a cron schedule in words, for showing it to people, like "every day at 09:00" for
+/// `0 0 9 * * *`. Accepts what `validate_schedule` accepts and the descriptor shorthands of
+/// `Job::new_with_shorthand`; an invalid schedule returns `InvalidSchedule`. Fixed times,
+/// intervals, weekdays, days of the month, months and the `L` and `#` day modifiers are put in
+/// words, anything more unusual is described field by field.
+/// ```rust,ignore
+/// assert_eq!(describe_schedule("0 30 9 * * Mon-Fri")?, "at 09:30 on Monday through Friday");
+/// assert_eq!(describe_schedule("0 */5 * * * *")?, "every 5 minutes");
+/// ```
+pub fn describe_schedule(schedule: &str) -> Result<String, JobSchedulerError> {
+    let schedule = match shorthand::expand(schedule)
+        .map_err(|_| JobSchedulerError::InvalidSchedule("unknown shorthand".to_string()))?
+    {
+        Shorthand::Cron(schedule) => schedule,
+        Shorthand::Every(interval) => return Ok(format!("every {}", duration_words(interval))),
+    };
+    validate_schedule(&schedule)?;
+    let fields = schedule.split_whitespace().collect::<Vec<_>>();
+
+    let second = field(fields[0], &number);
+    let minute = field(fields[1], &number);
+    let hour = field(fields[2], &number);
+    let time = match (&second, &minute, &hour) {
+        (Field::At(s), Field::At(m), Field::At(h)) if *s == 0 => format!("at {:02}:{:02}", h, m),
+        (Field::At(s), Field::At(m), Field::At(h)) => format!("at {:02}:{:02}:{:02}", h, m, s),
+        (Field::Any, Field::Any, Field::Any) => "every second".to_string(),
+        (Field::Every(n), Field::Any, Field::Any) => format!("every {} seconds", n),
+        (Field::At(0), Field::Any, Field::Any) => "every minute".to_string(),
+        (Field::At(0), Field::Every(n), Field::Any) => format!("every {} minutes", n),
+        (Field::At(0), Field::At(0), Field::Any) => "every hour".to_string(),
+        (Field::At(0), Field::At(m), Field::Any) => format!("at minute {} of every hour", m),
+        (Field::At(0), Field::At(0), Field::Every(n)) => format!("every {} hours", n),
+        (Field::At(0), Field::At(m), Field::Every(n)) => {
+            format!("every {} hours at minute {}", n, m)
+        }
+        _ => format!(
+            "at {}, {} and {}",
+            unit_words(&second, "second"),
+            unit_words(&minute, "minute"),
+            unit_words(&hour, "hour")
+        ),
+    };
+
+    let mut days = vec![];
+    let day_of_month = fields[3];
+    if day_of_month.eq_ignore_ascii_case("L") {
+        days.push("on the last day of the month".to_string());
+    } else {
+        match field(day_of_month, &number) {
+            Field::Any => {}
+            Field::At(day) => days.push(format!("on day {} of the month", day)),
+            Field::Every(n) => days.push(format!("every {} days", n)),
+            Field::Words(words) => days.push(format!("on days {} of the month", words)),
+        }
+    }
+    let day_of_week = fields[5];
+    if let Some((weekday, nth)) = day_of_week.split_once('#') {
+        let nth = nth.parse::<usize>().unwrap_or_default();
+        days.push(format!(
+            "on the {} {} of the month",
+            ORDINALS.get(nth.wrapping_sub(1)).unwrap_or(&"nth"),
+            weekday_name(weekday)
+        ));
+    } else if let Some(weekday) = day_of_week
+        .strip_suffix('L')
+        .or_else(|| day_of_week.strip_suffix('l'))
+    {
+        days.push(format!(
+            "on the last {} of the month",
+            weekday_name(weekday)
+        ));
+    } else {
+        match field(day_of_week, &weekday_name) {
+            Field::Any => {}
+            Field::At(weekday) => days.push(format!("on {}", weekday_name(&weekday.to_string()))),
+            Field::Every(n) => days.push(format!("every {} days of the week", n)),
+            Field::Words(words) => days.push(format!("on {}", words)),
+        }
+    }
+    match field(fields[4], &month_name) {
+        Field::Any => {}
+        Field::At(month) => days.push(format!("in {}", month_name(&month.to_string()))),
+        Field::Every(n) => days.push(format!("every {} months", n)),
+        Field::Words(words) => days.push(format!("in {}", words)),
+    }
+    if let Some(year) = fields.get(6) {
+        match field(year, &number) {
+            Field::Any => {}
+            Field::At(year) => days.push(format!("in {}", year)),
+            Field::Every(n) => days.push(format!("every {} years", n)),
+            Field::Words(words) => days.push(format!("in {}", words)),
+        }
+    }
+
+    let fixed_time = matches!(
+        (&second, &minute, &hour),
+        (Field::At(_), Field::At(_), Field::At(_))
+    );
+    if days.is_empty() && fixed_time {
+        return Ok(format!("every day {}", time));
+    }
+    days.insert(0, time);
+    Ok(days.join(" "))
+}
+
+///
+/// A cron field, with its values put in words by `name`
+fn field(field: &str, name: &dyn Fn(&str) -> String) -> Field {
+    if field == "*" || field == "?" {
+        return Field::Any;
+    }
+    if let Ok(value) = field.parse() {
+        return Field::At(value);
+    }
+    if let Some(step) = field.strip_prefix("*/") {
+        if let Ok(step) = step.parse() {
+            return Field::Every(step);
+        }
+    }
+    let parts = field
+        .split(',')
+        .map(|part| match part.split_once('/') {
+            Some((start, step)) => format!("every {} from {}", step, range(start, name)),
+            None => range(part, name),
+        })
+        .collect::<Vec<_>>();
+    Field::Words(list(&parts))
+}
+
+fn range(range: &str, name: &dyn Fn(&str) -> String) -> String {
+    match range.split_once('-') {
+        Some((from, to)) => format!("{} through {}", name(from), name(to)),
+        None => name(range),
+    }
+}
+
+fn list(parts: &[String]) -> String {
+    match parts.split_last() {
+        Some((last, rest)) if !rest.is_empty() => format!("{} and {}", rest.join(", "), last),
+        Some((last, _)) => last.clone(),
+        None => String::new(),
+    }
+}
+
+fn unit_words(field: &Field, unit: &str) -> String {
+    match field {
+        Field::Any => format!("every {}", unit),
+        Field::At(value) => format!("{} {}", unit, value),
+        Field::Every(n) => format!("every {} {}s", n, unit),
+        Field::Words(words) => format!("{}s {}", unit, words),
+    }
+}
+
+fn number(value: &str) -> String {
+    value.to_string()
+}
+
+fn weekday_name(weekday: &str) -> String {
+    let name = match parse_weekday(weekday) {
+        Ok(Weekday::Sun) => "Sunday",
+        Ok(Weekday::Mon) => "Monday",
+        Ok(Weekday::Tue) => "Tuesday",
+        Ok(Weekday::Wed) => "Wednesday",
+        Ok(Weekday::Thu) => "Thursday",
+        Ok(Weekday::Fri) => "Friday",
+        Ok(Weekday::Sat) => "Saturday",
+        Err(_) => return weekday.to_string(),
+    };
+    name.to_string()
+}
+
+fn month_name(month: &str) -> String {
+    let index = match month.parse::<usize>() {
+        Ok(month) => month.checked_sub(1),
+        Err(_) => MONTHS
+            .iter()
+            .position(|name| name[..3].eq_ignore_ascii_case(&month[..month.len().min(3)])),
+    };
+    match index.and_then(|index| MONTHS.get(index)) {
+        Some(name) => name.to_string(),
+        None => month.to_string(),
+    }
+}
+
+///
+/// An `@every` interval in words, like "30 seconds" or "2 hours"
+fn duration_words(interval: Duration) -> String {
+    let millis = interval.as_millis();
+    let (amount, unit) = if millis % 1000 != 0 {
+        (millis, "millisecond")
+    } else if millis % (24 * 60 * 60 * 1000) == 0 {
+        (millis / (24 * 60 * 60 * 1000), "day")
+    } else if millis % (60 * 60 * 1000) == 0 {
+        (millis / (60 * 60 * 1000), "hour")
+    } else if millis % (60 * 1000) == 0 {
+        (millis / (60 * 1000), "minute")
+    } else {
+        (millis / 1000, "second")
+    };
+    if amount == 1 {
+        unit.to_string()
+    } else {
+        format!("{} {}s", amount, unit)
+    }
+}
@@ -0,0 +1,148 @@
+use crate::JobSchedulerError;
+use chrono::{DateTime, Utc};
+use std::collections::{HashMap, VecDeque};
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::sync::RwLock;
+use uuid::Uuid;
+
+///
+/// How a run of a job ended
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Outcome {
+    Succeeded,
+    /// The run returned an error, see `Job::new_async_result`
+    Failed,
+    /// The run was cancelled by the job's timeout
+    TimedOut,
+}
+
+///
+/// A finished run of a job, as kept by a [HistoryStore]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct JobRunRecord {
+    pub started: DateTime<Utc>,
+    pub duration: Duration,
+    pub outcome: Outcome,
+}
+
+///
+/// Keeps the runs of the jobs, so they can be queried with `JobScheduler::run_history`. Unlike a
+/// `RunSink`, the store is read back by the scheduler.
+pub trait HistoryStore {
+    fn record(
+        &self,
+        job_id: Uuid,
+        record: JobRunRecord,
+    ) -> Pin<Box<dyn Future<Output = Result<(), JobSchedulerError>> + Send>>;
+
+    /// The runs of a job, oldest first
+    fn history(
+        &self,
+        job_id: Uuid,
+    ) -> Pin<Box<dyn Future<Output = Result<Vec<JobRunRecord>, JobSchedulerError>> + Send>>;
+
+    /// Forget the runs of a job, when its stats are reset or it's removed
+    fn clear(
+        &self,
+        job_id: Uuid,
+    ) -> Pin<Box<dyn Future<Output = Result<(), JobSchedulerError>> + Send>>;
+}
+
+///
+/// The default store, which keeps nothing
+#[derive(Default)]
+pub struct NoopHistoryStore {}
+
+impl HistoryStore for NoopHistoryStore {
+    fn record(
+        &self,
+        _job_id: Uuid,
+        _record: JobRunRecord,
+    ) -> Pin<Box<dyn Future<Output = Result<(), JobSchedulerError>> + Send>> {
+        Box::pin(async { Ok(()) })
+    }
+
+    fn history(
+        &self,
+        _job_id: Uuid,
+    ) -> Pin<Box<dyn Future<Output = Result<Vec<JobRunRecord>, JobSchedulerError>> + Send>> {
+        Box::pin(async { Ok(vec![]) })
+    }
+
+    fn clear(
+        &self,
+        _job_id: Uuid,
+    ) -> Pin<Box<dyn Future<Output = Result<(), JobSchedulerError>> + Send>> {
+        Box::pin(async { Ok(()) })
+    }
+}
+
+///
+/// An in memory store keeping the last `limit` runs of every job, until the job is removed
+pub struct SimpleHistoryStore {
+    pub limit: usize,
+    pub records: Arc<RwLock<HashMap<Uuid, VecDeque<JobRunRecord>>>>,
+}
+
+impl SimpleHistoryStore {
+    pub fn new(limit: usize) -> Self {
+        Self {
+            limit,
+            records: Arc::new(RwLock::new(HashMap::new())),
+        }
+    }
+}
+
+impl Default for SimpleHistoryStore {
+    fn default() -> Self {
+        Self::new(10)
+    }
+}
+
+impl HistoryStore for SimpleHistoryStore {
+    fn record(
+        &self,
+        job_id: Uuid,
+        record: JobRunRecord,
+    ) -> Pin<Box<dyn Future<Output = Result<(), JobSchedulerError>> + Send>> {
+        let records = self.records.clone();
+        let limit = self.limit;
+        Box::pin(async move {
+            let mut w = records.write().await;
+            let runs = w.entry(job_id).or_insert_with(VecDeque::new);
+            runs.push_back(record);
+            while runs.len() > limit {
+                runs.pop_front();
+            }
+            Ok(())
+        })
+    }
+
+    fn history(
+        &self,
+        job_id: Uuid,
+    ) -> Pin<Box<dyn Future<Output = Result<Vec<JobRunRecord>, JobSchedulerError>> + Send>> {
+        let records = self.records.clone();
+        Box::pin(async move {
+            let r = records.read().await;
+            Ok(r.get(&job_id)
+                .map(|runs| runs.iter().cloned().collect())
+                .unwrap_or_default())
+        })
+    }
+
+    fn clear(
+        &self,
+        job_id: Uuid,
+    ) -> Pin<Box<dyn Future<Output = Result<(), JobSchedulerError>> + Send>> {
+        let records = self.records.clone();
+        Box::pin(async move {
+            let mut w = records.write().await;
+            w.remove(&job_id);
+            Ok(())
+        })
+    }
+}
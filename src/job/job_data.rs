@@ -1,33 +1,68 @@
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 #[derive(Clone, PartialEq, Debug)]
 pub struct CronJob {
     pub schedule: String,
+    pub timezone: String,
+    pub dst_gap: i32,
+    pub dst_overlap: i32,
 }
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 #[derive(Clone, PartialEq, Debug)]
 pub struct NonCronJob {
     pub repeating: bool,
+    /// Seconds in between runs
     pub repeated_every: u64,
+    /// Unix timestamp, in seconds, the runs are aligned to
+    pub anchor: ::core::option::Option<u64>,
 }
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 #[derive(Clone, PartialEq, Debug)]
 pub struct Uuid {
     pub id1: u64,
     pub id2: u64,
 }
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 #[derive(Clone, PartialEq, Debug)]
 pub struct JobStoredData {
     pub id: ::core::option::Option<Uuid>,
+    /// Unix timestamp, in seconds
     pub last_updated: ::core::option::Option<u64>,
+    /// Unix timestamp, in seconds, of the last run
     pub last_tick: ::core::option::Option<u64>,
+    /// Unix timestamp, in seconds, of the next run, 0 when there is none
     pub next_tick: u64,
     pub job_type: i32,
     pub count: u32,
     pub extra: Vec<u8>,
     pub ran: bool,
     pub stopped: bool,
+    /// Milliseconds
+    pub timeout_ms: ::core::option::Option<u64>,
+    pub skip_overlapping: bool,
+    pub retries: u32,
+    /// Milliseconds
+    pub retry_backoff_ms: u64,
+    /// Milliseconds
+    pub jitter_ms: u64,
+    pub tags: Vec<String>,
+    pub misfire_policy: i32,
+    pub max_runs: ::core::option::Option<u32>,
+    /// Unix timestamp, in seconds, after which the job is removed
+    pub expires_at: ::core::option::Option<u64>,
+    /// Due jobs with a higher priority are dispatched first within a tick
+    pub priority: i32,
+    /// Unix timestamp, in seconds, before which the job doesn't fire
+    pub active_from: ::core::option::Option<u64>,
+    /// The priority set by `boost_priority`, in place of `priority` until `boost_until`
+    pub boosted_priority: ::core::option::Option<i32>,
+    /// Unix timestamp, in seconds, at which the boosted priority ends
+    pub boost_until: ::core::option::Option<u64>,
     pub job: ::core::option::Option<job_stored_data::Job>,
 }
 
 /// Nested message and enum types in `JobStoredData`.
 pub mod job_stored_data {
+    #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
     #[derive(Clone, PartialEq, Debug)]
     #[repr(i32)]
     pub enum Job {
@@ -35,42 +70,55 @@ pub mod job_stored_data {
         NonCronJob(super::NonCronJob),
     }
 }
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 #[derive(Clone, PartialEq, Debug)]
 pub struct JobIdAndNotification {
     pub job_id: ::core::option::Option<Uuid>,
     pub notification_id: ::core::option::Option<Uuid>,
 }
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 #[derive(Clone, PartialEq, Debug)]
 pub struct NotificationData {
     pub job_id: ::core::option::Option<JobIdAndNotification>,
     pub job_states: Vec<i32>,
     pub extra: Vec<u8>,
 }
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 #[derive(Clone, PartialEq, Debug)]
 pub struct NotificationIdAndState {
     pub notification_id: ::core::option::Option<Uuid>,
     pub job_state: i32,
 }
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 #[derive(Clone, PartialEq, Debug)]
 pub struct JobAndNextTick {
     pub id: ::core::option::Option<Uuid>,
     pub job_type: i32,
     pub next_tick: u64,
     pub last_tick: ::core::option::Option<u64>,
+    pub stopped: bool,
+    pub expires_at: ::core::option::Option<u64>,
+    pub priority: i32,
+    pub boosted_priority: ::core::option::Option<i32>,
+    pub boost_until: ::core::option::Option<u64>,
 }
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 #[derive(Clone, PartialEq, Debug)]
 pub struct ListOfUuids {
     pub uuids: Vec<Uuid>,
 }
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 #[derive(Clone, PartialEq, Debug)]
 pub struct JobAndNotifications {
     pub job_id: ::core::option::Option<Uuid>,
     pub notification_ids: Vec<Uuid>,
 }
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 #[derive(Clone, PartialEq, Debug)]
 pub struct ListOfJobsAndNotifications {
     pub job_and_notifications: Vec<JobAndNotifications>,
 }
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 #[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord, FromPrimitive, ToPrimitive)]
 #[repr(i32)]
 pub enum JobState {
@@ -79,7 +127,15 @@ pub enum JobState {
     Started = 2,
     Done = 3,
     Removed = 4,
+    Skipped = 5,
+    TimedOut = 6,
+    Failed = 7,
+    /// The job's fallback ran after a failed run, see `Job::with_fallback`
+    FallbackRan = 8,
+    /// The scheduler shut down, only sent on `JobScheduler::subscribe` with a nil job id
+    SchedulerStopped = 9,
 }
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 #[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord, FromPrimitive, ToPrimitive)]
 #[repr(i32)]
 pub enum JobType {
@@ -87,6 +143,34 @@ pub enum JobType {
     Repeated = 1,
     OneShot = 2,
 }
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord, FromPrimitive, ToPrimitive)]
+#[repr(i32)]
+pub enum MisfirePolicy {
+    FireOnce = 0,
+    Skip = 1,
+    FireAll = 2,
+}
+/// How a wall clock time that doesn't exist, when the clocks go forward, fires
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord, FromPrimitive, ToPrimitive)]
+#[repr(i32)]
+pub enum DstGapPolicy {
+    /// Moved forward by the length of the gap
+    ShiftByGap = 0,
+    SkipGap = 1,
+    /// At the end of the gap, the moment the clocks went forward
+    FireAtTransition = 2,
+}
+/// How a wall clock time that happens twice, when the clocks go back, fires
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord, FromPrimitive, ToPrimitive)]
+#[repr(i32)]
+pub enum DstOverlapPolicy {
+    FirstOccurrence = 0,
+    LastOccurrence = 1,
+    BothOccurrences = 2,
+}
 
 impl JobState {
     pub fn from_i32(x: i32) -> Option<Self> {
@@ -96,6 +180,11 @@ impl JobState {
             2 => Some(Self::Started),
             3 => Some(Self::Done),
             4 => Some(Self::Removed),
+            5 => Some(Self::Skipped),
+            6 => Some(Self::TimedOut),
+            7 => Some(Self::Failed),
+            8 => Some(Self::FallbackRan),
+            9 => Some(Self::SchedulerStopped),
             _ => None,
         }
     }
@@ -112,6 +201,17 @@ impl JobType {
     }
 }
 
+impl MisfirePolicy {
+    pub fn from_i32(x: i32) -> Option<Self> {
+        match x {
+            0 => Some(Self::FireOnce),
+            1 => Some(Self::Skip),
+            2 => Some(Self::FireAll),
+            _ => None,
+        }
+    }
+}
+
 impl From<JobState> for i32 {
     fn from(val: JobState) -> Self {
         val as i32
@@ -124,6 +224,46 @@ impl From<JobType> for i32 {
     }
 }
 
+impl From<MisfirePolicy> for i32 {
+    fn from(val: MisfirePolicy) -> Self {
+        val as i32
+    }
+}
+
+impl DstGapPolicy {
+    pub fn from_i32(x: i32) -> Option<Self> {
+        match x {
+            0 => Some(Self::ShiftByGap),
+            1 => Some(Self::SkipGap),
+            2 => Some(Self::FireAtTransition),
+            _ => None,
+        }
+    }
+}
+
+impl DstOverlapPolicy {
+    pub fn from_i32(x: i32) -> Option<Self> {
+        match x {
+            0 => Some(Self::FirstOccurrence),
+            1 => Some(Self::LastOccurrence),
+            2 => Some(Self::BothOccurrences),
+            _ => None,
+        }
+    }
+}
+
+impl From<DstGapPolicy> for i32 {
+    fn from(val: DstGapPolicy) -> Self {
+        val as i32
+    }
+}
+
+impl From<DstOverlapPolicy> for i32 {
+    fn from(val: DstOverlapPolicy) -> Self {
+        val as i32
+    }
+}
+
 impl JobStoredData {
     pub fn job_type(&self) -> JobType {
         JobType::from_i32(self.job_type).unwrap()
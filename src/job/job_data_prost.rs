@@ -1,17 +1,30 @@
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 #[allow(clippy::derive_partial_eq_without_eq)]
 #[derive(Clone, PartialEq, ::prost::Message)]
 pub struct CronJob {
     #[prost(string, tag = "1")]
     pub schedule: ::prost::alloc::string::String,
+    #[prost(string, tag = "2")]
+    pub timezone: ::prost::alloc::string::String,
+    #[prost(enumeration = "DstGapPolicy", tag = "3")]
+    pub dst_gap: i32,
+    #[prost(enumeration = "DstOverlapPolicy", tag = "4")]
+    pub dst_overlap: i32,
 }
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 #[allow(clippy::derive_partial_eq_without_eq)]
 #[derive(Clone, PartialEq, ::prost::Message)]
 pub struct NonCronJob {
     #[prost(bool, tag = "1")]
     pub repeating: bool,
+    /// Seconds in between runs
     #[prost(uint64, tag = "2")]
     pub repeated_every: u64,
+    /// Unix timestamp, in seconds, the runs are aligned to
+    #[prost(uint64, optional, tag = "3")]
+    pub anchor: ::core::option::Option<u64>,
 }
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 #[allow(clippy::derive_partial_eq_without_eq)]
 #[derive(Clone, PartialEq, ::prost::Message)]
 pub struct Uuid {
@@ -20,15 +33,19 @@ pub struct Uuid {
     #[prost(uint64, tag = "2")]
     pub id2: u64,
 }
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 #[allow(clippy::derive_partial_eq_without_eq)]
 #[derive(Clone, PartialEq, ::prost::Message)]
 pub struct JobStoredData {
     #[prost(message, optional, tag = "1")]
     pub id: ::core::option::Option<Uuid>,
+    /// Unix timestamp, in seconds
     #[prost(uint64, optional, tag = "2")]
     pub last_updated: ::core::option::Option<u64>,
+    /// Unix timestamp, in seconds, of the last run
     #[prost(uint64, optional, tag = "3")]
     pub last_tick: ::core::option::Option<u64>,
+    /// Unix timestamp, in seconds, of the next run, 0 when there is none
     #[prost(uint64, tag = "4")]
     pub next_tick: u64,
     #[prost(enumeration = "JobType", tag = "5")]
@@ -41,11 +58,46 @@ pub struct JobStoredData {
     pub ran: bool,
     #[prost(bool, tag = "11")]
     pub stopped: bool,
+    /// Milliseconds
+    #[prost(uint64, optional, tag = "12")]
+    pub timeout_ms: ::core::option::Option<u64>,
+    #[prost(bool, tag = "13")]
+    pub skip_overlapping: bool,
+    #[prost(uint32, tag = "14")]
+    pub retries: u32,
+    /// Milliseconds
+    #[prost(uint64, tag = "15")]
+    pub retry_backoff_ms: u64,
+    /// Milliseconds
+    #[prost(uint64, tag = "16")]
+    pub jitter_ms: u64,
+    #[prost(string, repeated, tag = "17")]
+    pub tags: ::prost::alloc::vec::Vec<::prost::alloc::string::String>,
+    #[prost(enumeration = "MisfirePolicy", tag = "18")]
+    pub misfire_policy: i32,
+    #[prost(uint32, optional, tag = "19")]
+    pub max_runs: ::core::option::Option<u32>,
+    /// Unix timestamp, in seconds, after which the job is removed
+    #[prost(uint64, optional, tag = "20")]
+    pub expires_at: ::core::option::Option<u64>,
+    /// Due jobs with a higher priority are dispatched first within a tick
+    #[prost(int32, tag = "21")]
+    pub priority: i32,
+    /// Unix timestamp, in seconds, before which the job doesn't fire
+    #[prost(uint64, optional, tag = "22")]
+    pub active_from: ::core::option::Option<u64>,
+    /// The priority set by `boost_priority`, in place of `priority` until `boost_until`
+    #[prost(int32, optional, tag = "23")]
+    pub boosted_priority: ::core::option::Option<i32>,
+    /// Unix timestamp, in seconds, at which the boosted priority ends
+    #[prost(uint64, optional, tag = "24")]
+    pub boost_until: ::core::option::Option<u64>,
     #[prost(oneof = "job_stored_data::Job", tags = "6, 7")]
     pub job: ::core::option::Option<job_stored_data::Job>,
 }
 /// Nested message and enum types in `JobStoredData`.
 pub mod job_stored_data {
+    #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
     #[allow(clippy::derive_partial_eq_without_eq)]
     #[derive(Clone, PartialEq, ::prost::Oneof)]
     pub enum Job {
@@ -55,6 +107,7 @@ pub mod job_stored_data {
         NonCronJob(super::NonCronJob),
     }
 }
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 #[allow(clippy::derive_partial_eq_without_eq)]
 #[derive(Clone, PartialEq, ::prost::Message)]
 pub struct JobIdAndNotification {
@@ -63,6 +116,7 @@ pub struct JobIdAndNotification {
     #[prost(message, optional, tag = "2")]
     pub notification_id: ::core::option::Option<Uuid>,
 }
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 #[allow(clippy::derive_partial_eq_without_eq)]
 #[derive(Clone, PartialEq, ::prost::Message)]
 pub struct NotificationData {
@@ -73,6 +127,7 @@ pub struct NotificationData {
     #[prost(bytes = "vec", tag = "3")]
     pub extra: ::prost::alloc::vec::Vec<u8>,
 }
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 #[allow(clippy::derive_partial_eq_without_eq)]
 #[derive(Clone, PartialEq, ::prost::Message)]
 pub struct NotificationIdAndState {
@@ -81,6 +136,7 @@ pub struct NotificationIdAndState {
     #[prost(enumeration = "JobState", tag = "2")]
     pub job_state: i32,
 }
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 #[allow(clippy::derive_partial_eq_without_eq)]
 #[derive(Clone, PartialEq, ::prost::Message)]
 pub struct JobAndNextTick {
@@ -92,13 +148,25 @@ pub struct JobAndNextTick {
     pub next_tick: u64,
     #[prost(uint64, optional, tag = "4")]
     pub last_tick: ::core::option::Option<u64>,
+    #[prost(bool, tag = "5")]
+    pub stopped: bool,
+    #[prost(uint64, optional, tag = "6")]
+    pub expires_at: ::core::option::Option<u64>,
+    #[prost(int32, tag = "7")]
+    pub priority: i32,
+    #[prost(int32, optional, tag = "8")]
+    pub boosted_priority: ::core::option::Option<i32>,
+    #[prost(uint64, optional, tag = "9")]
+    pub boost_until: ::core::option::Option<u64>,
 }
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 #[allow(clippy::derive_partial_eq_without_eq)]
 #[derive(Clone, PartialEq, ::prost::Message)]
 pub struct ListOfUuids {
     #[prost(message, repeated, tag = "1")]
     pub uuids: ::prost::alloc::vec::Vec<Uuid>,
 }
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 #[allow(clippy::derive_partial_eq_without_eq)]
 #[derive(Clone, PartialEq, ::prost::Message)]
 pub struct JobAndNotifications {
@@ -107,12 +175,14 @@ pub struct JobAndNotifications {
     #[prost(message, repeated, tag = "2")]
     pub notification_ids: ::prost::alloc::vec::Vec<Uuid>,
 }
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 #[allow(clippy::derive_partial_eq_without_eq)]
 #[derive(Clone, PartialEq, ::prost::Message)]
 pub struct ListOfJobsAndNotifications {
     #[prost(message, repeated, tag = "1")]
     pub job_and_notifications: ::prost::alloc::vec::Vec<JobAndNotifications>,
 }
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 #[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord, ::prost::Enumeration)]
 #[repr(i32)]
 pub enum JobState {
@@ -121,6 +191,13 @@ pub enum JobState {
     Started = 2,
     Done = 3,
     Removed = 4,
+    Skipped = 5,
+    TimedOut = 6,
+    Failed = 7,
+    /// The job's fallback ran after a failed run, see `Job::with_fallback`
+    FallbackRan = 8,
+    /// The scheduler shut down, only sent on `JobScheduler::subscribe` with a nil job id
+    SchedulerStopped = 9,
 }
 impl JobState {
     /// String value of the enum field names used in the ProtoBuf definition.
@@ -134,6 +211,11 @@ impl JobState {
             JobState::Started => "Started",
             JobState::Done => "Done",
             JobState::Removed => "Removed",
+            JobState::Skipped => "Skipped",
+            JobState::TimedOut => "TimedOut",
+            JobState::Failed => "Failed",
+            JobState::FallbackRan => "FallbackRan",
+            JobState::SchedulerStopped => "SchedulerStopped",
         }
     }
     /// Creates an enum from field names used in the ProtoBuf definition.
@@ -144,10 +226,16 @@ impl JobState {
             "Started" => Some(Self::Started),
             "Done" => Some(Self::Done),
             "Removed" => Some(Self::Removed),
+            "Skipped" => Some(Self::Skipped),
+            "TimedOut" => Some(Self::TimedOut),
+            "Failed" => Some(Self::Failed),
+            "FallbackRan" => Some(Self::FallbackRan),
+            "SchedulerStopped" => Some(Self::SchedulerStopped),
             _ => None,
         }
     }
 }
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 #[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord, ::prost::Enumeration)]
 #[repr(i32)]
 pub enum JobType {
@@ -177,3 +265,97 @@ impl JobType {
         }
     }
 }
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord, ::prost::Enumeration)]
+#[repr(i32)]
+pub enum MisfirePolicy {
+    FireOnce = 0,
+    Skip = 1,
+    FireAll = 2,
+}
+impl MisfirePolicy {
+    /// String value of the enum field names used in the ProtoBuf definition.
+    ///
+    /// The values are not transformed in any way and thus are considered stable
+    /// (if the ProtoBuf definition does not change) and safe for programmatic use.
+    pub fn as_str_name(&self) -> &'static str {
+        match self {
+            MisfirePolicy::FireOnce => "FireOnce",
+            MisfirePolicy::Skip => "Skip",
+            MisfirePolicy::FireAll => "FireAll",
+        }
+    }
+    /// Creates an enum from field names used in the ProtoBuf definition.
+    pub fn from_str_name(value: &str) -> ::core::option::Option<Self> {
+        match value {
+            "FireOnce" => Some(Self::FireOnce),
+            "Skip" => Some(Self::Skip),
+            "FireAll" => Some(Self::FireAll),
+            _ => None,
+        }
+    }
+}
+/// How a wall clock time that doesn't exist, when the clocks go forward, fires
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord, ::prost::Enumeration)]
+#[repr(i32)]
+pub enum DstGapPolicy {
+    /// Moved forward by the length of the gap
+    ShiftByGap = 0,
+    SkipGap = 1,
+    /// At the end of the gap, the moment the clocks went forward
+    FireAtTransition = 2,
+}
+impl DstGapPolicy {
+    /// String value of the enum field names used in the ProtoBuf definition.
+    ///
+    /// The values are not transformed in any way and thus are considered stable
+    /// (if the ProtoBuf definition does not change) and safe for programmatic use.
+    pub fn as_str_name(&self) -> &'static str {
+        match self {
+            DstGapPolicy::ShiftByGap => "ShiftByGap",
+            DstGapPolicy::SkipGap => "SkipGap",
+            DstGapPolicy::FireAtTransition => "FireAtTransition",
+        }
+    }
+    /// Creates an enum from field names used in the ProtoBuf definition.
+    pub fn from_str_name(value: &str) -> ::core::option::Option<Self> {
+        match value {
+            "ShiftByGap" => Some(Self::ShiftByGap),
+            "SkipGap" => Some(Self::SkipGap),
+            "FireAtTransition" => Some(Self::FireAtTransition),
+            _ => None,
+        }
+    }
+}
+/// How a wall clock time that happens twice, when the clocks go back, fires
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord, ::prost::Enumeration)]
+#[repr(i32)]
+pub enum DstOverlapPolicy {
+    FirstOccurrence = 0,
+    LastOccurrence = 1,
+    BothOccurrences = 2,
+}
+impl DstOverlapPolicy {
+    /// String value of the enum field names used in the ProtoBuf definition.
+    ///
+    /// The values are not transformed in any way and thus are considered stable
+    /// (if the ProtoBuf definition does not change) and safe for programmatic use.
+    pub fn as_str_name(&self) -> &'static str {
+        match self {
+            DstOverlapPolicy::FirstOccurrence => "FirstOccurrence",
+            DstOverlapPolicy::LastOccurrence => "LastOccurrence",
+            DstOverlapPolicy::BothOccurrences => "BothOccurrences",
+        }
+    }
+    /// Creates an enum from field names used in the ProtoBuf definition.
+    pub fn from_str_name(value: &str) -> ::core::option::Option<Self> {
+        match value {
+            "FirstOccurrence" => Some(Self::FirstOccurrence),
+            "LastOccurrence" => Some(Self::LastOccurrence),
+            "BothOccurrences" => Some(Self::BothOccurrences),
+            _ => None,
+        }
+    }
+}
@@ -0,0 +1,29 @@
+use crate::JobSchedulerError;
+use chrono::{DateTime, Utc};
+use std::future::Future;
+use std::pin::Pin;
+use uuid::Uuid;
+
+pub type JobRun = Pin<Box<dyn Future<Output = Result<(), JobSchedulerError>> + Send>>;
+
+///
+/// What a [JobMiddleware] gets to know about the run it wraps
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct JobRunContext {
+    /// The GUID of the job that is run
+    pub job_id: Uuid,
+    /// When the runner started the run
+    pub started_at: DateTime<Utc>,
+}
+
+///
+/// Code wrapped around every job run, for behaviour that applies to all jobs such as setting up
+/// a context, timing or mapping errors. `wrap` gets the rest of the chain as `next` and decides
+/// if and when to await it.
+///
+/// Middlewares are run in the order they were added to the scheduler: the first one added is the
+/// outermost, the job itself is the innermost. Errors that come out of the chain are logged by the
+/// runner and notified as `Failed`, with their message on `JobScheduler::subscribe_failed`. A job that runs longer than its timeout fails with `JobSchedulerError::JobTimedOut`.
+pub trait JobMiddleware {
+    fn wrap(&self, context: JobRunContext, next: JobRun) -> JobRun;
+}
@@ -1,44 +1,79 @@
 #[cfg(not(feature = "has_bytes"))]
-use crate::job::job_data::{JobState, JobType};
+use crate::job::job_data::{DstGapPolicy, DstOverlapPolicy, JobState, JobType, MisfirePolicy};
 #[cfg(feature = "has_bytes")]
-use crate::job::job_data_prost::{JobState, JobType};
+use crate::job::job_data_prost::{
+    DstGapPolicy, DstOverlapPolicy, JobState, JobType, MisfirePolicy,
+};
 use crate::job_scheduler::JobsSchedulerLocked;
 use crate::{JobScheduler, JobSchedulerError, JobStoredData};
-use chrono::{DateTime, Utc};
+use chrono::{DateTime, TimeZone, Utc};
+use chrono_tz::Tz;
 use cron::Schedule;
 use cron_job::CronJob;
 use non_cron_job::NonCronJob;
+use std::any::Any;
 use std::convert::TryInto;
 use std::future::Future;
 use std::pin::Pin;
+use std::str::FromStr;
 use std::sync::{Arc, RwLock};
 use std::time::{Duration, Instant, SystemTime};
 use tokio::sync::oneshot::Receiver;
 use tracing::error;
 use uuid::Uuid;
 
+mod builder;
+mod config;
 mod creator;
 mod cron_job;
+mod day_filter;
 mod deleter;
+mod describe;
+mod history_store;
 #[cfg(not(feature = "has_bytes"))]
 pub mod job_data;
 #[cfg(feature = "has_bytes")]
 pub mod job_data_prost;
+mod middleware;
 mod non_cron_job;
+mod notification_builder;
+mod retry_policy;
+mod run_sink;
 mod runner;
+mod schedule_parser;
+mod shorthand;
 pub mod to_code;
 use crate::notification::{NotificationCreator, NotificationDeleter};
+pub use builder::JobBuilder;
+pub use config::JobConfig;
 pub use creator::JobCreator;
 pub use deleter::JobDeleter;
+pub use describe::describe_schedule;
+pub use history_store::{
+    HistoryStore, JobRunRecord, NoopHistoryStore, Outcome, SimpleHistoryStore,
+};
+pub use middleware::{JobMiddleware, JobRun, JobRunContext};
+pub use notification_builder::NotificationBuilder;
+pub use retry_policy::RetryPolicy;
+pub use run_sink::{NoopRunSink, RunRecord, RunSink};
 pub use runner::JobRunner;
+pub(crate) use schedule_parser::next_after_in;
+pub use schedule_parser::{CronScheduleParser, ScheduleParser};
+use shorthand::Shorthand;
 
 pub type JobId = Uuid;
 pub type NotificationId = Uuid;
 
+///
+/// The code of a job. Every job constructor, sync or async, hands its code the job's own GUID and
+/// a clone of the scheduler it runs on, so a job can look itself up, add follow-up jobs or remove
+/// itself without capturing either.
 pub type JobToRun = dyn FnMut(JobId, JobsSchedulerLocked) + Send + Sync;
 pub type JobToRunAsync =
     dyn FnMut(JobId, JobsSchedulerLocked) -> Pin<Box<dyn Future<Output = ()> + Send>> + Send + Sync;
 
+pub type JobError = Box<dyn std::error::Error + Send + Sync>;
+
 pub type OnJobNotification = dyn FnMut(JobId, NotificationId, JobState) -> Pin<Box<dyn Future<Output = ()> + Send>>
     + Send
     + Sync;
@@ -51,6 +86,129 @@ fn nop_async(_uuid: Uuid, _jobs: JobsSchedulerLocked) -> Pin<Box<dyn Future<Outp
     Box::pin(async move {})
 }
 
+///
+/// A lifecycle event of a job, like it being started, done, removed or failing. Sent for every
+/// notification of every job, see `JobScheduler::subscribe`. The scheduler's own
+/// `SchedulerStopped` event has a nil `job_id`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct JobEvent {
+    pub job_id: Uuid,
+    pub kind: JobState,
+    pub at: DateTime<Utc>,
+}
+
+///
+/// Why a due job was not run. Sent alongside the job's `Skipped` notification, see
+/// `JobScheduler::subscribe_skipped`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum SkipReason {
+    /// The scheduler's global gate was closed
+    GlobalGate,
+    /// The job is paused, see `JobLocked::pause`
+    Paused,
+    /// The previous run of the job was still going, see `JobLocked::set_skip_overlapping`
+    Overlap,
+    /// The job's tick was missed while the scheduler was down, see `JobLocked::with_misfire_policy`
+    Misfire,
+}
+
+fn parse_schedule<S, E>(schedule: S) -> Result<Schedule, JobSchedulerError>
+where
+    S: TryInto<Schedule, Error = E>,
+    E: std::error::Error + 'static,
+{
+    schedule
+        .try_into()
+        .map_err(|_| JobSchedulerError::ParseSchedule)
+}
+
+///
+/// Checks that a cron schedule parses, without creating a job or needing a Tokio runtime. Accepts
+/// the schedules `Job::new_with_shorthand` and `JobBuilder` accept, leaving out the descriptor
+/// shorthands: six fields starting with the seconds, optionally followed by the year, and the `L`
+/// and `#` day modifiers of `CronScheduleParser`. `Job::new` and the other constructors taking a
+/// `TryInto<Schedule>` don't take the day modifiers. The error tells what is wrong with the
+/// schedule.
+/// ```rust,ignore
+/// if let Err(JobSchedulerError::InvalidSchedule(reason)) = validate_schedule(&input) {
+///     return Err(BadRequest(reason));
+/// }
+/// ```
+pub fn validate_schedule(schedule: &str) -> Result<(), JobSchedulerError> {
+    let (schedule, _) = day_filter::split(schedule).map_err(|_| {
+        JobSchedulerError::InvalidSchedule("invalid L or # day modifier".to_string())
+    })?;
+    Schedule::from_str(&schedule)
+        .map(|_| ())
+        .map_err(|e| JobSchedulerError::InvalidSchedule(e.to_string()))
+}
+
+///
+/// Project the next `n` times a cron schedule fires after `after`, without creating a job or a
+/// scheduler. The schedule is parsed and evaluated the same way a cron job's is, so this can be
+/// used to check why a job did or didn't fire.
+/// ```rust,ignore
+/// let times = upcoming_for("0 15 6,8,10 * Mar,Jun Fri 2017", Utc::now(), 5)?;
+/// ```
+pub fn upcoming_for(
+    schedule: &str,
+    after: DateTime<Utc>,
+    n: usize,
+) -> Result<Vec<DateTime<Utc>>, JobSchedulerError> {
+    upcoming_for_tz(schedule, Utc, after, n)
+}
+
+///
+/// Like [upcoming_for], but evaluates the schedule on the wall clock of the given timezone, the
+/// way a job created with `Job::new_cron_job_tz` is. The fire times are returned in UTC. A time
+/// that happens twice when the clocks go back fires once; a time skipped when the clocks go
+/// forward fires the length of the gap later.
+pub fn upcoming_for_tz<TZ: TimeZone>(
+    schedule: &str,
+    tz: TZ,
+    after: DateTime<Utc>,
+    n: usize,
+) -> Result<Vec<DateTime<Utc>>, JobSchedulerError> {
+    let parser = CronScheduleParser::default();
+    let mut upcoming = Vec::with_capacity(n);
+    let mut after = after;
+    while upcoming.len() < n {
+        match next_after_in(
+            &parser,
+            schedule,
+            &tz,
+            &after,
+            DstGapPolicy::ShiftByGap,
+            DstOverlapPolicy::FirstOccurrence,
+        )? {
+            Some(next) => {
+                upcoming.push(next);
+                after = next;
+            }
+            None => break,
+        }
+    }
+    Ok(upcoming)
+}
+
+///
+/// The smallest `anchor + k * every` (in seconds) that lies after `after`
+pub(crate) fn next_anchored_tick(
+    anchor: DateTime<Utc>,
+    every: u64,
+    after: DateTime<Utc>,
+) -> Option<DateTime<Utc>> {
+    if after < anchor {
+        return Some(anchor);
+    }
+    if every == 0 {
+        return None;
+    }
+    let since_anchor = (after - anchor).num_seconds() as u64;
+    let periods = since_anchor / every + 1;
+    anchor.checked_add_signed(chrono::Duration::seconds((periods * every) as i64))
+}
+
 ///
 /// A schedulable Job
 #[derive(Clone)]
@@ -76,11 +234,16 @@ pub trait Job {
     fn set_started(&mut self);
     fn job_data_from_job(&mut self) -> Result<Option<JobStoredData>, JobSchedulerError>;
     fn set_job_data(&mut self, job_data: JobStoredData) -> Result<(), JobSchedulerError>;
+    fn set_fallback(&mut self, fallback: Box<JobToRunAsync>);
+    /// The run of the job's fallback, `None` when it has none
+    fn fallback(&mut self, jobs: JobScheduler) -> Option<Pin<Box<dyn Future<Output = ()> + Send>>>;
     fn run(&mut self, jobs: JobScheduler) -> Receiver<bool>;
 }
 
 impl JobLocked {
-    /// Create a new cron job.
+    /// Create a new cron job. The schedule is parsed by the `cron` crate, which doesn't know the
+    /// `L` and `#` day modifiers of `CronScheduleParser`; create jobs using them with
+    /// `new_with_shorthand` or `JobBuilder`.
     ///
     /// ```rust,ignore
     /// let mut sched = JobScheduler::new();
@@ -99,9 +262,7 @@ impl JobLocked {
         S: TryInto<Schedule, Error = E>,
         E: std::error::Error + 'static,
     {
-        let schedule: Schedule = schedule
-            .try_into()
-            .map_err(|_| JobSchedulerError::ParseSchedule)?;
+        let schedule = parse_schedule(schedule)?;
         let job_id = Uuid::new_v4();
         Ok(Self(Arc::new(RwLock::new(Box::new(CronJob {
             data: JobStoredData {
@@ -118,24 +279,45 @@ impl JobLocked {
                 extra: vec![],
                 ran: false,
                 stopped: false,
+                timeout_ms: None,
+                skip_overlapping: false,
+                retries: 0,
+                retry_backoff_ms: 0,
+                jitter_ms: 0,
+                tags: vec![],
+                misfire_policy: MisfirePolicy::FireOnce.into(),
+                max_runs: None,
+                expires_at: None,
+                priority: 0,
+                active_from: None,
+                boosted_priority: None,
+                boost_until: None,
                 #[cfg(feature = "has_bytes")]
                 job: Some(job_data_prost::job_stored_data::Job::CronJob(
                     job_data_prost::CronJob {
                         schedule: schedule.to_string(),
+                        timezone: String::new(),
+                        dst_gap: DstGapPolicy::ShiftByGap.into(),
+                        dst_overlap: DstOverlapPolicy::FirstOccurrence.into(),
                     },
                 )),
                 #[cfg(not(feature = "has_bytes"))]
                 job: Some(job_data::job_stored_data::Job::CronJob(job_data::CronJob {
                     schedule: schedule.to_string(),
+                    timezone: String::new(),
+                    dst_gap: DstGapPolicy::ShiftByGap.into(),
+                    dst_overlap: DstOverlapPolicy::FirstOccurrence.into(),
                 })),
             },
             run: Box::new(run),
             run_async: Box::new(nop_async),
             async_job: false,
+            fallback: None,
         })))))
     }
 
-    /// Create a new async cron job.
+    /// Create a new async cron job. Like `new`, the schedule can't use the `L` and `#` day
+    /// modifiers, see `new_async_with_shorthand` for those.
     ///
     /// ```rust,ignore
     /// let mut sched = JobScheduler::new();
@@ -156,9 +338,7 @@ impl JobLocked {
         S: TryInto<Schedule, Error = E>,
         E: std::error::Error + 'static,
     {
-        let schedule: Schedule = schedule
-            .try_into()
-            .map_err(|_| JobSchedulerError::ParseSchedule)?;
+        let schedule = parse_schedule(schedule)?;
         let job_id = Uuid::new_v4();
         Ok(Self(Arc::new(RwLock::new(Box::new(CronJob {
             data: JobStoredData {
@@ -175,24 +355,258 @@ impl JobLocked {
                 extra: vec![],
                 ran: false,
                 stopped: false,
+                timeout_ms: None,
+                skip_overlapping: false,
+                retries: 0,
+                retry_backoff_ms: 0,
+                jitter_ms: 0,
+                tags: vec![],
+                misfire_policy: MisfirePolicy::FireOnce.into(),
+                max_runs: None,
+                expires_at: None,
+                priority: 0,
+                active_from: None,
+                boosted_priority: None,
+                boost_until: None,
                 #[cfg(feature = "has_bytes")]
                 job: Some(job_data_prost::job_stored_data::Job::CronJob(
                     job_data_prost::CronJob {
                         schedule: schedule.to_string(),
+                        timezone: String::new(),
+                        dst_gap: DstGapPolicy::ShiftByGap.into(),
+                        dst_overlap: DstOverlapPolicy::FirstOccurrence.into(),
                     },
                 )),
                 #[cfg(not(feature = "has_bytes"))]
                 job: Some(job_data::job_stored_data::Job::CronJob(job_data::CronJob {
                     schedule: schedule.to_string(),
+                    timezone: String::new(),
+                    dst_gap: DstGapPolicy::ShiftByGap.into(),
+                    dst_overlap: DstOverlapPolicy::FirstOccurrence.into(),
                 })),
             },
             run: Box::new(nop),
             run_async: Box::new(run),
             async_job: true,
+            fallback: None,
         })))))
     }
 
-    /// Create a new cron job.
+    /// Create a new async cron job that can fail. When the returned future resolves to an error
+    /// the run is notified as `Failed`, before its `Done`, and the error's message is sent on the
+    /// scheduler's `subscribe_failed` channel. A successful run is notified the same as one of
+    /// `new_async`. Failed runs can be retried, see `set_retries`.
+    ///
+    /// ```rust,ignore
+    /// let mut sched = JobScheduler::new();
+    /// let job = Job::new_async_result("0 * * * * *", |_uuid, _lock| Box::pin(async move {
+    ///             call_flaky_service().await?;
+    ///             Ok(())
+    ///         }));
+    /// sched.add(job)
+    /// tokio::spawn(sched.start());
+    /// ```
+    pub fn new_async_result<S, T, E>(schedule: S, run: T) -> Result<Self, JobSchedulerError>
+    where
+        T: 'static,
+        T: FnMut(
+                Uuid,
+                JobsSchedulerLocked,
+            ) -> Pin<Box<dyn Future<Output = Result<(), JobError>> + Send>>
+            + Send
+            + Sync,
+        S: TryInto<Schedule, Error = E>,
+        E: std::error::Error + 'static,
+    {
+        let run = Arc::new(tokio::sync::Mutex::new(run));
+        JobLocked::new_async(schedule, move |job_id, jobs| {
+            let run = run.clone();
+            Box::pin(async move {
+                let context = jobs.context.clone();
+                let (retries, backoff) = {
+                    let mut w = context.metadata_storage.write().await;
+                    match w.get(job_id).await {
+                        Ok(Some(data)) => {
+                            (data.retries, Duration::from_millis(data.retry_backoff_ms))
+                        }
+                        _ => (0, Duration::default()),
+                    }
+                };
+                let mut shutdown_rx = context.shutdown_tx.subscribe();
+                let mut attempt = 0;
+                let ran = loop {
+                    let ran = {
+                        let mut run = run.lock().await;
+                        run(job_id, jobs.clone())
+                    }
+                    .await;
+                    match ran {
+                        Err(e) if attempt < retries => {
+                            attempt += 1;
+                            error!(
+                                "Job {:?} failed, retry {} of {} {:?}",
+                                job_id, attempt, retries, e
+                            );
+                            // A shutdown during the backoff cancels the retry right away
+                            let shut_down = *shutdown_rx.borrow()
+                                || tokio::select! {
+                                    _ = tokio::time::sleep(backoff) => false,
+                                    _ = shutdown_rx.changed() => true,
+                                };
+                            if shut_down || jobs.is_shutdown().await {
+                                tracing::info!(
+                                    "Scheduler shut down, cancelled retry of {:?}",
+                                    job_id
+                                );
+                                return;
+                            }
+                        }
+                        ran => break ran,
+                    }
+                };
+                if let Err(e) = ran {
+                    error!("Job {:?} failed {:?}", job_id, e);
+                    // Not set when the job's future is polled outside of the runner
+                    let _ = runner::RUN_FAILED.try_with(|failed| {
+                        if let Ok(mut w) = failed.lock() {
+                            *w = Some(e.to_string());
+                        }
+                    });
+                    if let Err(e) = context.notify_tx.send((job_id, JobState::Failed)) {
+                        error!("Error sending notification failed {:?}", e);
+                    }
+                    if let Err(e) = context.failed_tx.send((job_id, e.to_string())) {
+                        // Only fails when nobody subscribed to the errors
+                        tracing::trace!("No listener for job errors {:?}", e);
+                    }
+                }
+            })
+        })
+    }
+
+    /// Create a new cron job for blocking or CPU bound code. Instead of running on one of the
+    /// runtime's workers, where it would hold up other tasks and the scheduler's own ticks, the code
+    /// is run on tokio's blocking thread pool using `tokio::task::spawn_blocking`. The job's done
+    /// notification is sent once the code returns, and `shutdown_graceful` waits for the code like
+    /// it waits for async jobs.
+    ///
+    /// The blocking pool is sized by the runtime, see `max_blocking_threads` on tokio's runtime
+    /// `Builder`, 512 threads by default, and is shared with everything else in the application
+    /// that calls `spawn_blocking`, tokio's file system functions included. Once all of its
+    /// threads are busy, runs queue until one frees up and so start late. Runs of the same job are
+    /// serialised, so one job takes up at most one blocking thread, though runs that pile up each
+    /// wait on a thread of their own. A run that exceeds its timeout, see `set_timeout`, is
+    /// notified as `TimedOut` but its code can't be interrupted: it keeps its thread until it
+    /// returns.
+    ///
+    /// ```rust,ignore
+    /// let mut sched = JobScheduler::new();
+    /// let job = Job::new_blocking("0 0 * * * *", |_uuid, _lock| {
+    ///             std::thread::sleep(std::time::Duration::from_secs(10));
+    ///             println!("{:?} Hi I blocked for a while", chrono::Utc::now());
+    ///         });
+    /// sched.add(job)
+    /// tokio::spawn(sched.start());
+    /// ```
+    pub fn new_blocking<S, T, E>(schedule: S, run: T) -> Result<Self, JobSchedulerError>
+    where
+        T: 'static,
+        T: FnMut(Uuid, JobsSchedulerLocked) + Send + Sync,
+        S: TryInto<Schedule, Error = E>,
+        E: std::error::Error + 'static,
+    {
+        let run = Arc::new(std::sync::Mutex::new(run));
+        JobLocked::new_async(schedule, move |job_id, jobs| {
+            let run = run.clone();
+            Box::pin(async move {
+                let ran = tokio::task::spawn_blocking(move || match run.lock() {
+                    Ok(mut run) => (run)(job_id, jobs),
+                    Err(e) => error!("Error getting blocking job {:?} {:?}", job_id, e),
+                })
+                .await;
+                if let Err(e) = ran {
+                    error!("Error running blocking job {:?} {:?}", job_id, e);
+                }
+            })
+        })
+    }
+
+    /// Create a new cron job that carries `state`, for jobs made from the same template with
+    /// different parameters. The state is boxed as a `dyn Any` and a reference to it is passed to
+    /// every run; get the typed value back with `downcast_ref`, which returns `None` when asked
+    /// for another type than the one the job was created with.
+    ///
+    /// The state lives as long as the job and is shared by all of its runs, so it has to be
+    /// `Send + Sync`. The reference is a shared one: state that changes from run to run needs
+    /// interior mutability, like a `Mutex` or an atomic. The state isn't persisted, a job loaded
+    /// from a store after a restart has to be created with its state again.
+    ///
+    /// ```rust,ignore
+    /// struct Report { name: String }
+    /// let job = Job::new_with_state("0 0 * * * *", Report { name: "daily".into() },
+    ///     |_uuid, _lock, state| {
+    ///         let report = state.downcast_ref::<Report>().unwrap();
+    ///         println!("Running report {}", report.name);
+    ///     });
+    /// sched.add(job)
+    /// ```
+    pub fn new_with_state<S, U, T, E>(
+        schedule: S,
+        state: U,
+        mut run: T,
+    ) -> Result<Self, JobSchedulerError>
+    where
+        U: Any + Send + Sync,
+        T: 'static,
+        T: FnMut(Uuid, JobsSchedulerLocked, &(dyn Any + Send + Sync)) + Send + Sync,
+        S: TryInto<Schedule, Error = E>,
+        E: std::error::Error + 'static,
+    {
+        let state: Box<dyn Any + Send + Sync> = Box::new(state);
+        JobLocked::new(schedule, move |job_id, jobs| run(job_id, jobs, &*state))
+    }
+
+    /// Create a new async cron job that carries `state`, see `new_with_state`. The returned
+    /// future can't borrow the state, so take what the run needs out of it, a clone or an `Arc`,
+    /// before building the future.
+    ///
+    /// ```rust,ignore
+    /// let job = Job::new_async_with_state("0 0 * * * *", Arc::new(config),
+    ///     |_uuid, _lock, state| {
+    ///         let config = state.downcast_ref::<Arc<Config>>().cloned();
+    ///         Box::pin(async move {
+    ///             if let Some(config) = config {
+    ///                 call_service(&config).await;
+    ///             }
+    ///         })
+    ///     });
+    /// ```
+    pub fn new_async_with_state<S, U, T, E>(
+        schedule: S,
+        state: U,
+        mut run: T,
+    ) -> Result<Self, JobSchedulerError>
+    where
+        U: Any + Send + Sync,
+        T: 'static,
+        T: FnMut(
+                Uuid,
+                JobsSchedulerLocked,
+                &(dyn Any + Send + Sync),
+            ) -> Pin<Box<dyn Future<Output = ()> + Send>>
+            + Send
+            + Sync,
+        S: TryInto<Schedule, Error = E>,
+        E: std::error::Error + 'static,
+    {
+        let state: Box<dyn Any + Send + Sync> = Box::new(state);
+        JobLocked::new_async(schedule, move |job_id, jobs| run(job_id, jobs, &*state))
+    }
+
+    /// Create a new cron job. The schedule has to parse with the `cron` crate, so it can't use the
+    /// `L` and `#` day modifiers, see `new`. Its ticks, the first one included, are worked out by
+    /// the parser set on the scheduler with `set_schedule_parser` once the job is added. Use
+    /// `new_cron_job_with_parser` for schedules in another dialect.
     ///
     /// ```rust,ignore
     /// let mut sched = JobScheduler::new();
@@ -214,6 +628,110 @@ impl JobLocked {
         JobLocked::new(schedule, run)
     }
 
+    fn make_cron_job_with_parser(
+        schedule: &str,
+        parser: &dyn ScheduleParser,
+        run: Box<JobToRun>,
+        run_async: Box<JobToRunAsync>,
+        async_job: bool,
+    ) -> Result<Self, JobSchedulerError> {
+        let next_tick = parser.next_after(schedule, &Utc::now())?;
+        let job_id = Uuid::new_v4();
+        Ok(Self(Arc::new(RwLock::new(Box::new(CronJob {
+            data: JobStoredData {
+                id: Some(job_id.into()),
+                last_updated: None,
+                last_tick: None,
+                next_tick: next_tick.map(|t| t.timestamp() as u64).unwrap_or(0),
+                job_type: JobType::Cron.into(),
+                count: 0,
+                extra: vec![],
+                ran: false,
+                stopped: false,
+                timeout_ms: None,
+                skip_overlapping: false,
+                retries: 0,
+                retry_backoff_ms: 0,
+                jitter_ms: 0,
+                tags: vec![],
+                misfire_policy: MisfirePolicy::FireOnce.into(),
+                max_runs: None,
+                expires_at: None,
+                priority: 0,
+                active_from: None,
+                boosted_priority: None,
+                boost_until: None,
+                #[cfg(feature = "has_bytes")]
+                job: Some(job_data_prost::job_stored_data::Job::CronJob(
+                    job_data_prost::CronJob {
+                        schedule: schedule.to_string(),
+                        timezone: String::new(),
+                        dst_gap: DstGapPolicy::ShiftByGap.into(),
+                        dst_overlap: DstOverlapPolicy::FirstOccurrence.into(),
+                    },
+                )),
+                #[cfg(not(feature = "has_bytes"))]
+                job: Some(job_data::job_stored_data::Job::CronJob(job_data::CronJob {
+                    schedule: schedule.to_string(),
+                    timezone: String::new(),
+                    dst_gap: DstGapPolicy::ShiftByGap.into(),
+                    dst_overlap: DstOverlapPolicy::FirstOccurrence.into(),
+                })),
+            },
+            run,
+            run_async,
+            async_job,
+            fallback: None,
+        })))))
+    }
+
+    /// Create a new cron job with a schedule in a dialect of the given parser, which checks the
+    /// schedule. Set the same parser on the scheduler using `set_schedule_parser`, it's used to
+    /// calculate the job's ticks once it's added.
+    ///
+    /// ```rust,ignore
+    /// let mut sched = JobScheduler::new();
+    /// sched.set_schedule_parser(Box::new(QuartzParser::default())).await;
+    /// let job = Job::new_cron_job_with_parser("0 15 10 ? * MON-FRI", &QuartzParser::default(), |_uuid, _lock| {
+    ///             println!("{:?} Hi I ran", chrono::Utc::now());
+    ///         });
+    /// sched.add(job)
+    /// tokio::spawn(sched.start());
+    /// ```
+    pub fn new_cron_job_with_parser<T>(
+        schedule: &str,
+        parser: &dyn ScheduleParser,
+        run: T,
+    ) -> Result<Self, JobSchedulerError>
+    where
+        T: 'static,
+        T: FnMut(Uuid, JobsSchedulerLocked) + Send + Sync,
+    {
+        JobLocked::make_cron_job_with_parser(
+            schedule,
+            parser,
+            Box::new(run),
+            Box::new(nop_async),
+            false,
+        )
+    }
+
+    /// Create a new async cron job with a schedule in a dialect of the given parser.
+    /// See `new_cron_job_with_parser`.
+    pub fn new_cron_job_with_parser_async<T>(
+        schedule: &str,
+        parser: &dyn ScheduleParser,
+        run: T,
+    ) -> Result<Self, JobSchedulerError>
+    where
+        T: 'static,
+        T: FnMut(Uuid, JobsSchedulerLocked) -> Pin<Box<dyn Future<Output = ()> + Send>>
+            + Send
+            + Sync,
+    {
+        JobLocked::make_cron_job_with_parser(schedule, parser, Box::new(nop), Box::new(run), true)
+    }
+
     /// Create a new async cron job.
     ///
     /// ```rust,ignore
@@ -238,6 +756,133 @@ impl JobLocked {
         JobLocked::new_async(schedule, run)
     }
 
+    /// Create a new cron job whose schedule is evaluated on the wall clock of `timezone` instead
+    /// of UTC. The timezone is stored with the job. Around daylight saving changes a wall clock
+    /// time that happens twice fires once, at its first occurrence, and a time that is skipped
+    /// fires the length of the skip later.
+    ///
+    /// ```rust,ignore
+    /// // Run at 9am in Johannesburg and at 9am in New York
+    /// let jhb = Job::new_cron_job_tz("0 0 9 * * *", chrono_tz::Africa::Johannesburg, |_uuid, _lock| {
+    ///             println!("Good morning Johannesburg");
+    ///         })?;
+    /// let nyc = Job::new_cron_job_tz("0 0 9 * * *", chrono_tz::America::New_York, |_uuid, _lock| {
+    ///             println!("Good morning New York");
+    ///         })?;
+    /// ```
+    pub fn new_cron_job_tz<S, T, E>(
+        schedule: S,
+        timezone: Tz,
+        run: T,
+    ) -> Result<Self, JobSchedulerError>
+    where
+        T: 'static,
+        T: FnMut(Uuid, JobsSchedulerLocked) + Send + Sync,
+        S: TryInto<Schedule, Error = E>,
+        E: std::error::Error + 'static,
+    {
+        JobLocked::new(schedule, run)?.in_timezone(timezone)
+    }
+
+    /// Create a new async cron job whose schedule is evaluated on the wall clock of `timezone`.
+    /// See `new_cron_job_tz`.
+    pub fn new_cron_job_async_tz<S, T, E>(
+        schedule: S,
+        timezone: Tz,
+        run: T,
+    ) -> Result<Self, JobSchedulerError>
+    where
+        T: 'static,
+        T: FnMut(Uuid, JobsSchedulerLocked) -> Pin<Box<dyn Future<Output = ()> + Send>>
+            + Send
+            + Sync,
+        S: TryInto<Schedule, Error = E>,
+        E: std::error::Error + 'static,
+    {
+        JobLocked::new_async(schedule, run)?.in_timezone(timezone)
+    }
+
+    /// Create a new job from a schedule that may use a descriptor shorthand instead of cron
+    /// fields. `@yearly` (or `@annually`), `@monthly`, `@weekly`, `@daily` (or `@midnight`) and
+    /// `@hourly` create a cron job firing at the start of the period, in UTC. `@every <duration>`
+    /// creates a repeated job, see `new_repeated`, with the duration written as `30s`, `5m`,
+    /// `1h30m` or `500ms`. Any other schedule is parsed as a cron schedule, like `new` does, with
+    /// the `L` and `#` day modifiers of `CronScheduleParser` on top.
+    ///
+    /// ```rust,ignore
+    /// let daily = Job::new_with_shorthand("@daily", |_uuid, _lock| {
+    ///             println!("A new day");
+    ///         })?;
+    /// let often = Job::new_with_shorthand("@every 30s", |_uuid, _lock| {
+    ///             println!("Another 30 seconds");
+    ///         })?;
+    /// ```
+    pub fn new_with_shorthand<T>(schedule: &str, run: T) -> Result<Self, JobSchedulerError>
+    where
+        T: 'static,
+        T: FnMut(Uuid, JobsSchedulerLocked) + Send + Sync,
+    {
+        match shorthand::expand(schedule)? {
+            Shorthand::Cron(schedule) if day_filter::split(&schedule)?.1.is_some() => {
+                JobLocked::new_cron_job_with_parser(&schedule, &CronScheduleParser::default(), run)
+            }
+            Shorthand::Cron(schedule) => JobLocked::new(&*schedule, run),
+            Shorthand::Every(interval) => JobLocked::new_repeated(interval, run),
+        }
+    }
+
+    /// Create a new async job from a schedule that may use a descriptor shorthand. See
+    /// `new_with_shorthand`.
+    pub fn new_async_with_shorthand<T>(schedule: &str, run: T) -> Result<Self, JobSchedulerError>
+    where
+        T: 'static,
+        T: FnMut(Uuid, JobsSchedulerLocked) -> Pin<Box<dyn Future<Output = ()> + Send>>
+            + Send
+            + Sync,
+    {
+        match shorthand::expand(schedule)? {
+            Shorthand::Cron(schedule) if day_filter::split(&schedule)?.1.is_some() => {
+                JobLocked::new_cron_job_with_parser_async(
+                    &schedule,
+                    &CronScheduleParser::default(),
+                    run,
+                )
+            }
+            Shorthand::Cron(schedule) => JobLocked::new_async(&*schedule, run),
+            Shorthand::Every(interval) => JobLocked::new_repeated_async(interval, run),
+        }
+    }
+
+    fn in_timezone(self, timezone: Tz) -> Result<Self, JobSchedulerError> {
+        {
+            let mut w = self
+                .0
+                .write()
+                .map_err(|_| JobSchedulerError::UpdateJobData)?;
+            let mut job_data = w
+                .job_data_from_job()?
+                .ok_or(JobSchedulerError::UpdateJobData)?;
+            match job_data.job.as_mut() {
+                #[cfg(feature = "has_bytes")]
+                Some(job_data_prost::job_stored_data::Job::CronJob(cron_job)) => {
+                    cron_job.timezone = timezone.name().to_string();
+                }
+                #[cfg(not(feature = "has_bytes"))]
+                Some(job_data::job_stored_data::Job::CronJob(cron_job)) => {
+                    cron_job.timezone = timezone.name().to_string();
+                }
+                _ => return Err(JobSchedulerError::UpdateJobData),
+            }
+            let next_tick = job_data
+                .next_schedule_tick(&CronScheduleParser::default(), &Utc::now())
+                .transpose()?
+                .flatten();
+            job_data.set_next_tick(next_tick);
+            w.set_job_data(job_data)?;
+        }
+        Ok(self)
+    }
+
     fn make_one_shot_job(
         duration: Duration,
         run: Box<JobToRun>,
@@ -249,6 +894,7 @@ impl JobLocked {
             run,
             run_async,
             async_job,
+            fallback: None,
             data: JobStoredData {
                 id: Some(id.into()),
                 last_updated: None,
@@ -263,11 +909,25 @@ impl JobLocked {
                 extra: vec![],
                 ran: false,
                 stopped: false,
+                timeout_ms: None,
+                skip_overlapping: false,
+                retries: 0,
+                retry_backoff_ms: 0,
+                jitter_ms: 0,
+                tags: vec![],
+                misfire_policy: MisfirePolicy::FireOnce.into(),
+                max_runs: None,
+                expires_at: None,
+                priority: 0,
+                active_from: None,
+                boosted_priority: None,
+                boost_until: None,
                 #[cfg(feature = "has_bytes")]
                 job: Some(job_data_prost::job_stored_data::Job::NonCronJob(
                     job_data_prost::NonCronJob {
                         repeating: false,
                         repeated_every: duration.as_secs(),
+                        anchor: None,
                     },
                 )),
                 #[cfg(not(feature = "has_bytes"))]
@@ -275,6 +935,7 @@ impl JobLocked {
                     job_data::NonCronJob {
                         repeating: false,
                         repeated_every: duration.as_secs(),
+                        anchor: None,
                     },
                 )),
             },
@@ -288,7 +949,7 @@ impl JobLocked {
 
     /// Create a new one shot job.
     ///
-    /// This is checked if it is running only after 500ms in 500ms intervals.
+    /// The scheduler wakes up to run it at the whole second it is due.
     /// ```rust,ignore
     /// let mut sched = JobScheduler::new();
     /// let job = Job::new_one_shot(Duration::from_secs(18), |_uuid, _l| {
@@ -307,7 +968,7 @@ impl JobLocked {
 
     /// Create a new async one shot job.
     ///
-    /// This is checked if it is running only after 500ms in 500ms intervals.
+    /// The scheduler wakes up to run it at the whole second it is due.
     /// ```rust,ignore
     /// let mut sched = JobScheduler::new();
     /// let job = Job::new_one_shot(Duration::from_secs(18), |_uuid, _l| Box::pin(async move {
@@ -331,40 +992,69 @@ impl JobLocked {
         run: Box<JobToRun>,
         run_async: Box<JobToRunAsync>,
         async_job: bool,
+    ) -> Result<Self, JobSchedulerError> {
+        let when = chrono::Utc::now()
+            .checked_add_signed(chrono::Duration::seconds(
+                instant.duration_since(Instant::now()).as_secs() as i64,
+            ))
+            .ok_or(JobSchedulerError::NoNextTick)?;
+        JobLocked::make_new_one_shot_at(when, run, run_async, async_job)
+    }
+
+    fn make_new_one_shot_at(
+        when: DateTime<Utc>,
+        run: Box<JobToRun>,
+        run_async: Box<JobToRunAsync>,
+        async_job: bool,
     ) -> Result<Self, JobSchedulerError> {
         let id = Uuid::new_v4();
+        let now = chrono::Utc::now();
+        // A time in the past is due right away
+        let when = when.max(now);
+        let in_seconds = (when - now).num_seconds().max(0) as u64;
 
         let job = NonCronJob {
             run,
             run_async,
             async_job,
+            fallback: None,
             data: JobStoredData {
                 id: Some(id.into()),
                 last_updated: None,
                 last_tick: None,
-                next_tick: chrono::Utc::now()
-                    .checked_add_signed(chrono::Duration::seconds(
-                        instant.duration_since(Instant::now()).as_secs() as i64,
-                    ))
-                    .map(|t| t.timestamp() as u64)
-                    .unwrap_or(0),
+                next_tick: when.timestamp() as u64,
                 job_type: JobType::OneShot.into(),
                 count: 0,
                 extra: vec![],
                 ran: false,
                 stopped: false,
+                timeout_ms: None,
+                skip_overlapping: false,
+                retries: 0,
+                retry_backoff_ms: 0,
+                jitter_ms: 0,
+                tags: vec![],
+                misfire_policy: MisfirePolicy::FireOnce.into(),
+                max_runs: None,
+                expires_at: None,
+                priority: 0,
+                active_from: None,
+                boosted_priority: None,
+                boost_until: None,
                 #[cfg(feature = "has_bytes")]
                 job: Some(job_data_prost::job_stored_data::Job::NonCronJob(
                     job_data_prost::NonCronJob {
                         repeating: false,
-                        repeated_every: instant.duration_since(Instant::now()).as_secs(),
+                        repeated_every: in_seconds,
+                        anchor: None,
                     },
                 )),
                 #[cfg(not(feature = "has_bytes"))]
                 job: Some(job_data::job_stored_data::Job::NonCronJob(
                     job_data::NonCronJob {
                         repeating: false,
-                        repeated_every: instant.duration_since(Instant::now()).as_secs(),
+                        repeated_every: in_seconds,
+                        anchor: None,
                     },
                 )),
             },
@@ -425,36 +1115,89 @@ impl JobLocked {
         JobLocked::make_new_one_shot_at_an_instant(instant, Box::new(nop), Box::new(run), true)
     }
 
+    /// Create a new one shot job that runs at a date and time. A time that already passed when the
+    /// job is created runs it right away, on the scheduler's next tick. Once it ran the job removes
+    /// itself.
+    ///
+    /// ```rust,ignore
+    /// let when = "2025-07-01T00:00:00Z".parse::<DateTime<Utc>>()?;
+    /// let job = Job::new_one_shot_at(when, |_uuid, _lock| println!("Happy new half year"))?;
+    /// sched.add(job).await?;
+    /// ```
+    pub fn new_one_shot_at<T>(when: DateTime<Utc>, run: T) -> Result<Self, JobSchedulerError>
+    where
+        T: 'static,
+        T: FnMut(Uuid, JobsSchedulerLocked) + Send + Sync,
+    {
+        JobLocked::make_new_one_shot_at(when, Box::new(run), Box::new(nop_async), false)
+    }
+
+    /// Create a new async one shot job that runs at a date and time, see `new_one_shot_at`.
+    ///
+    /// ```rust,ignore
+    /// let job = Job::new_one_shot_at_async(when, |_uuid, _lock| Box::pin(async move {
+    ///     println!("Happy new half year");
+    /// }))?;
+    /// sched.add(job).await?;
+    /// ```
+    pub fn new_one_shot_at_async<T>(when: DateTime<Utc>, run: T) -> Result<Self, JobSchedulerError>
+    where
+        T: 'static,
+        T: FnMut(Uuid, JobsSchedulerLocked) -> Pin<Box<dyn Future<Output = ()> + Send>>
+            + Send
+            + Sync,
+    {
+        JobLocked::make_new_one_shot_at(when, Box::new(nop), Box::new(run), true)
+    }
+
     fn make_new_repeated(
+        anchor: Option<DateTime<Utc>>,
         duration: Duration,
         run: Box<JobToRun>,
         run_async: Box<JobToRunAsync>,
         async_job: bool,
     ) -> Result<Self, JobSchedulerError> {
         let id = Uuid::new_v4();
+        let now = chrono::Utc::now();
+        let next_tick = match anchor {
+            Some(anchor) => next_anchored_tick(anchor, duration.as_secs(), now),
+            None => now.checked_add_signed(chrono::Duration::seconds(duration.as_secs() as i64)),
+        };
         let job = NonCronJob {
             run,
             run_async,
             async_job,
+            fallback: None,
             data: JobStoredData {
                 id: Some(id.into()),
                 last_updated: None,
                 last_tick: None,
-                next_tick: chrono::Utc::now()
-                    .checked_add_signed(chrono::Duration::seconds(duration.as_secs() as i64))
-                    .map(|t| t.timestamp() as u64)
-                    .unwrap_or(0),
+                next_tick: next_tick.map(|t| t.timestamp() as u64).unwrap_or(0),
                 job_type: JobType::Repeated.into(),
                 count: 0,
                 extra: vec![],
                 ran: false,
                 stopped: false,
+                timeout_ms: None,
+                skip_overlapping: false,
+                retries: 0,
+                retry_backoff_ms: 0,
+                jitter_ms: 0,
+                tags: vec![],
+                misfire_policy: MisfirePolicy::FireOnce.into(),
+                max_runs: None,
+                expires_at: None,
+                priority: 0,
+                active_from: None,
+                boosted_priority: None,
+                boost_until: None,
 
                 #[cfg(feature = "has_bytes")]
                 job: Some(job_data_prost::job_stored_data::Job::NonCronJob(
                     job_data_prost::NonCronJob {
                         repeating: true,
                         repeated_every: duration.as_secs(),
+                        anchor: anchor.map(|a| a.timestamp() as u64),
                     },
                 )),
                 #[cfg(not(feature = "has_bytes"))]
@@ -462,6 +1205,7 @@ impl JobLocked {
                     job_data::NonCronJob {
                         repeating: true,
                         repeated_every: duration.as_secs(),
+                        anchor: anchor.map(|a| a.timestamp() as u64),
                     },
                 )),
             },
@@ -475,7 +1219,7 @@ impl JobLocked {
 
     /// Create a new repeated job.
     ///
-    /// This is checked if it is running only after 500ms in 500ms intervals.
+    /// The scheduler wakes up to run it at the whole second it is due.
     /// ```rust,ignore
     /// let mut sched = JobScheduler::new();
     /// let job = Job::new_repeated(Duration::from_secs(8), |_uuid, _lock| {
@@ -489,12 +1233,12 @@ impl JobLocked {
         T: 'static,
         T: FnMut(Uuid, JobsSchedulerLocked) + Send + Sync,
     {
-        JobLocked::make_new_repeated(duration, Box::new(run), Box::new(nop_async), false)
+        JobLocked::make_new_repeated(None, duration, Box::new(run), Box::new(nop_async), false)
     }
 
     /// Create a new async repeated job.
     ///
-    /// This is checked if it is running only after 500ms in 500ms intervals.
+    /// The scheduler wakes up to run it at the whole second it is due.
     /// ```rust,ignore
     /// let mut sched = JobScheduler::new();
     /// let job = Job::new_repeated(Duration::from_secs(8), |_uuid, _lock| Box::pin(async move {
@@ -510,31 +1254,326 @@ impl JobLocked {
             + Send
             + Sync,
     {
-        JobLocked::make_new_repeated(duration, Box::new(nop), Box::new(run), true)
+        JobLocked::make_new_repeated(None, duration, Box::new(nop), Box::new(run), true)
     }
 
+    /// Create a new repeated job whose ticks are aligned to an anchor. The job runs at the
+    /// smallest `anchor + k * duration` that lies after now, which gives control over the phase
+    /// of intervals that don't divide an hour or a day. The anchor is stored with the job's
+    /// metadata, so the alignment survives restarts.
     ///
-    /// The `tick` method returns a true if there was an invocation needed after it was last called
-    /// This method will also change the last tick on itself
-    pub fn tick(&mut self) -> Result<bool, JobSchedulerError> {
-        let now = Utc::now();
-        let (job_type, last_tick, next_tick, schedule, repeated_every, ran, count) = {
-            let r = self.0.read().map_err(|_| JobSchedulerError::TickError)?;
-            (
-                r.job_type(),
-                r.last_tick(),
-                r.next_tick(),
-                r.schedule(),
-                r.repeated_every(),
-                r.ran(),
-                r.count(),
-            )
-        };
-
-        // Don't bother processing a cancelled job
-        if next_tick.is_none() {
-            return Err(JobSchedulerError::NoNextTick);
-        }
+    /// The scheduler wakes up to run it at the whole second it is due.
+    /// ```rust,ignore
+    /// // Every 90 minutes, starting from 00:15
+    /// let mut sched = JobScheduler::new();
+    /// let anchor = Utc.ymd(2022, 1, 1).and_hms(0, 15, 0);
+    /// let job = Job::new_repeated_from(anchor, Duration::from_secs(90 * 60), |_uuid, _lock| {
+    ///     println!("{:?} I'm repeated every 90 minutes", chrono::Utc::now());
+    /// }
+    /// sched.add(job)
+    /// tokio::spawn(sched.start());
+    /// ```
+    pub fn new_repeated_from<T>(
+        anchor: DateTime<Utc>,
+        duration: Duration,
+        run: T,
+    ) -> Result<Self, JobSchedulerError>
+    where
+        T: 'static,
+        T: FnMut(Uuid, JobsSchedulerLocked) + Send + Sync,
+    {
+        JobLocked::make_new_repeated(
+            Some(anchor),
+            duration,
+            Box::new(run),
+            Box::new(nop_async),
+            false,
+        )
+    }
+
+    /// Create a new async repeated job whose ticks are aligned to an anchor.
+    ///
+    /// ```rust,ignore
+    /// let mut sched = JobScheduler::new();
+    /// let anchor = Utc.ymd(2022, 1, 1).and_hms(0, 15, 0);
+    /// let job = Job::new_repeated_from_async(anchor, Duration::from_secs(90 * 60), |_uuid, _lock| Box::pin(async move {
+    ///     println!("{:?} I'm repeated async every 90 minutes", chrono::Utc::now());
+    /// }));
+    /// sched.add(job)
+    /// tokio::spawn(sched.start());
+    /// ```
+    pub fn new_repeated_from_async<T>(
+        anchor: DateTime<Utc>,
+        duration: Duration,
+        run: T,
+    ) -> Result<Self, JobSchedulerError>
+    where
+        T: 'static,
+        T: FnMut(Uuid, JobsSchedulerLocked) -> Pin<Box<dyn Future<Output = ()> + Send>>
+            + Send
+            + Sync,
+    {
+        JobLocked::make_new_repeated(Some(anchor), duration, Box::new(nop), Box::new(run), true)
+    }
+
+    fn limit_runs(self, max_runs: u32) -> Result<Self, JobSchedulerError> {
+        if max_runs == 0 {
+            error!("A job needs to be allowed at least one run");
+            return Err(JobSchedulerError::InvalidMaxRuns);
+        }
+        {
+            let mut w = self
+                .0
+                .write()
+                .map_err(|_| JobSchedulerError::UpdateJobData)?;
+            let mut job_data = w
+                .job_data_from_job()?
+                .ok_or(JobSchedulerError::UpdateJobData)?;
+            job_data.max_runs = Some(max_runs);
+            w.set_job_data(job_data)?;
+        }
+        Ok(self)
+    }
+
+    /// Create a new repeated job that removes itself after it ran `max_runs` times. The job is
+    /// removed like `JobScheduler::remove` does once its last run finished, so the `Removed`
+    /// notifications are sent. The runs are counted in the metadata storage, so the limit holds
+    /// over restarts with a persistent store. A `max_runs` of zero is rejected with
+    /// `InvalidMaxRuns`.
+    /// ```rust,ignore
+    /// let mut sched = JobScheduler::new();
+    /// let job = Job::new_repeated_limited(Duration::from_secs(60), 5, |_uuid, _lock| {
+    ///     println!("{:?} I'm run 5 times, a minute apart", chrono::Utc::now());
+    /// })?;
+    /// sched.add(job).await?;
+    /// ```
+    pub fn new_repeated_limited<T>(
+        duration: Duration,
+        max_runs: u32,
+        run: T,
+    ) -> Result<Self, JobSchedulerError>
+    where
+        T: 'static,
+        T: FnMut(Uuid, JobsSchedulerLocked) + Send + Sync,
+    {
+        JobLocked::make_new_repeated(None, duration, Box::new(run), Box::new(nop_async), false)?
+            .limit_runs(max_runs)
+    }
+
+    /// Create a new async repeated job that removes itself after it ran `max_runs` times, see
+    /// `new_repeated_limited`.
+    pub fn new_repeated_limited_async<T>(
+        duration: Duration,
+        max_runs: u32,
+        run: T,
+    ) -> Result<Self, JobSchedulerError>
+    where
+        T: 'static,
+        T: FnMut(Uuid, JobsSchedulerLocked) -> Pin<Box<dyn Future<Output = ()> + Send>>
+            + Send
+            + Sync,
+    {
+        JobLocked::make_new_repeated(None, duration, Box::new(nop), Box::new(run), true)?
+            .limit_runs(max_runs)
+    }
+
+    fn make_new_repeated_with_delay(
+        initial: Duration,
+        interval: Duration,
+        run: Box<JobToRun>,
+        run_async: Box<JobToRunAsync>,
+        async_job: bool,
+    ) -> Result<Self, JobSchedulerError> {
+        let first_tick = chrono::Utc::now()
+            .checked_add_signed(chrono::Duration::seconds(initial.as_secs() as i64))
+            .ok_or(JobSchedulerError::CantAdd)?;
+        let job =
+            JobLocked::make_new_repeated(Some(first_tick), interval, run, run_async, async_job)?;
+        {
+            let mut w = job
+                .0
+                .write()
+                .map_err(|_| JobSchedulerError::UpdateJobData)?;
+            let mut job_data = w
+                .job_data_from_job()?
+                .ok_or(JobSchedulerError::UpdateJobData)?;
+            // The first tick is the anchor itself, even when it is now
+            job_data.set_next_tick(Some(first_tick));
+            w.set_job_data(job_data)?;
+        }
+        Ok(job)
+    }
+
+    /// Create a new repeated job that first runs `initial` from now and then every `interval`.
+    /// The first tick is stored as the job's anchor, see `new_repeated_from`, so after a restart
+    /// the ticks stay `interval` apart from the original first tick instead of starting over from
+    /// the restart. An `initial` of zero makes the job run on the scheduler's first tick.
+    /// ```rust,ignore
+    /// let mut sched = JobScheduler::new();
+    /// let job = Job::new_repeated_with_delay(
+    ///     Duration::from_secs(30),
+    ///     Duration::from_secs(5 * 60),
+    ///     |_uuid, _lock| {
+    ///         println!("{:?} I run after 30 seconds, then every 5 minutes", chrono::Utc::now());
+    ///     },
+    /// )?;
+    /// sched.add(job).await?;
+    /// ```
+    pub fn new_repeated_with_delay<T>(
+        initial: Duration,
+        interval: Duration,
+        run: T,
+    ) -> Result<Self, JobSchedulerError>
+    where
+        T: 'static,
+        T: FnMut(Uuid, JobsSchedulerLocked) + Send + Sync,
+    {
+        JobLocked::make_new_repeated_with_delay(
+            initial,
+            interval,
+            Box::new(run),
+            Box::new(nop_async),
+            false,
+        )
+    }
+
+    /// Create a new async repeated job that first runs `initial` from now and then every
+    /// `interval`, see `new_repeated_with_delay`.
+    pub fn new_repeated_with_delay_async<T>(
+        initial: Duration,
+        interval: Duration,
+        run: T,
+    ) -> Result<Self, JobSchedulerError>
+    where
+        T: 'static,
+        T: FnMut(Uuid, JobsSchedulerLocked) -> Pin<Box<dyn Future<Output = ()> + Send>>
+            + Send
+            + Sync,
+    {
+        JobLocked::make_new_repeated_with_delay(
+            initial,
+            interval,
+            Box::new(nop),
+            Box::new(run),
+            true,
+        )
+    }
+
+    fn make_from_job_data(
+        data: JobStoredData,
+        run: Box<JobToRun>,
+        run_async: Box<JobToRunAsync>,
+        async_job: bool,
+    ) -> Result<Self, JobSchedulerError> {
+        if data.id.is_none() {
+            return Err(JobSchedulerError::GetJobData);
+        }
+        let job_type = JobType::from_i32(data.job_type).ok_or(JobSchedulerError::GetJobData)?;
+        let job: Box<dyn Job + Send + Sync + 'static> = match job_type {
+            JobType::Cron => {
+                if data.schedule_str().is_none() {
+                    return Err(JobSchedulerError::ParseSchedule);
+                }
+                Box::new(CronJob {
+                    data,
+                    run,
+                    run_async,
+                    async_job,
+                    fallback: None,
+                })
+            }
+            JobType::Repeated | JobType::OneShot => {
+                if data.repeated_every().is_none() {
+                    return Err(JobSchedulerError::GetJobData);
+                }
+                Box::new(NonCronJob {
+                    run,
+                    run_async,
+                    data,
+                    async_job,
+                    fallback: None,
+                })
+            }
+        };
+
+        Ok(Self(Arc::new(RwLock::new(job))))
+    }
+
+    /// Recreate a job from metadata that was previously saved in a `MetaDataStorage`, for example
+    /// when rehydrating a scheduler from a persistent store after a restart. The job keeps the
+    /// stored GUID, ticks, count and flags, only the code to run is new. As the GUID may already be
+    /// known to the scheduler, add it using `add_or_update`.
+    /// ```rust,ignore
+    /// let data = metadata_storage.get(job_id).await?.unwrap();
+    /// let job = Job::from_job_data(data, |uuid, _lock| {
+    ///     println!("{:?} I'm back", uuid);
+    /// })?;
+    /// sched.add_or_update(job).await?;
+    /// ```
+    pub fn from_job_data<T>(data: JobStoredData, run: T) -> Result<Self, JobSchedulerError>
+    where
+        T: 'static,
+        T: FnMut(Uuid, JobsSchedulerLocked) + Send + Sync,
+    {
+        JobLocked::make_from_job_data(data, Box::new(run), Box::new(nop_async), false)
+    }
+
+    /// Recreate an async job from metadata that was previously saved in a `MetaDataStorage`.
+    /// See `from_job_data`.
+    /// ```rust,ignore
+    /// let data = metadata_storage.get(job_id).await?.unwrap();
+    /// let job = Job::from_job_data_async(data, |uuid, _lock| Box::pin(async move {
+    ///     println!("{:?} I'm back", uuid);
+    /// }))?;
+    /// sched.add_or_update(job).await?;
+    /// ```
+    pub fn from_job_data_async<T>(data: JobStoredData, run: T) -> Result<Self, JobSchedulerError>
+    where
+        T: 'static,
+        T: FnMut(Uuid, JobsSchedulerLocked) -> Pin<Box<dyn Future<Output = ()> + Send>>
+            + Send
+            + Sync,
+    {
+        JobLocked::make_from_job_data(data, Box::new(nop), Box::new(run), true)
+    }
+
+    ///
+    /// The `tick` method returns a true if there was an invocation needed after it was last called
+    /// This method will also change the last tick on itself
+    pub fn tick(&mut self) -> Result<bool, JobSchedulerError> {
+        self.tick_at(Utc::now())
+    }
+
+    ///
+    /// `tick` with `now` as the current time, for instance the time of a scheduler's `Clock`
+    pub fn tick_at(&mut self, now: DateTime<Utc>) -> Result<bool, JobSchedulerError> {
+        let (job_type, last_tick, next_tick, schedule, repeated_every, ran, count) = {
+            let r = self.0.read().map_err(|_| JobSchedulerError::TickError)?;
+            (
+                r.job_type(),
+                r.last_tick(),
+                r.next_tick(),
+                r.schedule(),
+                r.repeated_every(),
+                r.ran(),
+                r.count(),
+            )
+        };
+
+        // Don't bother processing a cancelled job
+        if next_tick.is_none() {
+            return Err(JobSchedulerError::NoNextTick);
+        }
+
+        // An expired job doesn't fire anymore, the scheduler removes it
+        let expires_at = self.job_data().ok().and_then(|d| d.expires_at_utc());
+        if matches!(expires_at, Some(expires_at) if expires_at <= now) {
+            return Ok(false);
+        }
+        let active_from = self.job_data().ok().and_then(|d| d.active_from_utc());
+        if matches!(active_from, Some(active_from) if now < active_from) {
+            return Ok(false);
+        }
 
         let must_run = match (last_tick.as_ref(), next_tick.as_ref(), job_type) {
             (None, Some(next_tick), JobType::OneShot) => {
@@ -561,7 +1600,19 @@ impl JobLocked {
 
         let next_tick = if must_run {
             match job_type {
-                JobType::Cron => schedule.and_then(|s| s.after(&now).next()),
+                // Zoned schedules and the ones the `cron` crate can't parse, with the `L` and `#`
+                // day modifiers, go through the parser
+                JobType::Cron => match self
+                    .job_data()
+                    .ok()
+                    .filter(|d| d.timezone().is_some() || schedule.is_none())
+                {
+                    Some(data) => data
+                        .next_schedule_tick(&CronScheduleParser::default(), &now)
+                        .and_then(|next| next.ok())
+                        .flatten(),
+                    None => schedule.and_then(|s| s.after(&now).next()),
+                },
                 JobType::OneShot => None,
                 JobType::Repeated => repeated_every.and_then(|r| {
                     next_tick
@@ -608,7 +1659,9 @@ impl JobLocked {
     }
 
     ///
-    /// Add a notification to run on a list of state notifications
+    /// Add a notification to run on a list of state notifications. It's added next to the
+    /// notifications the job already has for those states, all of them run. See
+    /// `NotificationBuilder` to add several at once.
     pub async fn on_notifications_add(
         &self,
         job_scheduler: &JobsSchedulerLocked,
@@ -723,6 +1776,94 @@ impl JobLocked {
         .map(|(_, deleted)| deleted)
     }
 
+    ///
+    /// Run something when the task was due but skipped, because the scheduler's global gate was
+    /// closed or the job is paused. Returns a UUID as handle for this notification. This UUID needs to be used when you
+    /// want to remove the notification handle using `on_skipped_notification_remove`.
+    pub async fn on_skipped_notification_add(
+        &mut self,
+        job_scheduler: &JobsSchedulerLocked,
+        on_skipped: Box<OnJobNotification>,
+    ) -> Result<Uuid, JobSchedulerError> {
+        self.on_notifications_add(job_scheduler, on_skipped, vec![JobState::Skipped])
+            .await
+    }
+
+    ///
+    /// Remove the notification when the task was skipped. Uses the same UUID that was returned by
+    /// `on_skipped_notification_add`
+    pub async fn on_skipped_notification_remove(
+        &mut self,
+        job_scheduler: &JobsSchedulerLocked,
+        notification_id: &Uuid,
+    ) -> Result<bool, JobSchedulerError> {
+        self.on_notification_removal(
+            job_scheduler,
+            notification_id,
+            Some(vec![JobState::Skipped]),
+        )
+        .await
+        .map(|(_, deleted)| deleted)
+    }
+
+    ///
+    /// Run something when a run of the task failed, see `new_async_result`, or a middleware
+    /// returned an error. The error's message is sent on `JobScheduler::subscribe_failed`, keyed
+    /// by the job's GUID like the notification. Returns a UUID as
+    /// handle for this notification. This UUID needs to be used when you want to remove the
+    /// notification handle using `on_failed_notification_remove`.
+    pub async fn on_failed_notification_add(
+        &mut self,
+        job_scheduler: &JobsSchedulerLocked,
+        on_failed: Box<OnJobNotification>,
+    ) -> Result<Uuid, JobSchedulerError> {
+        self.on_notifications_add(job_scheduler, on_failed, vec![JobState::Failed])
+            .await
+    }
+
+    ///
+    /// Remove the notification when the task failed. Uses the same UUID that was returned by
+    /// `on_failed_notification_add`
+    pub async fn on_failed_notification_remove(
+        &mut self,
+        job_scheduler: &JobsSchedulerLocked,
+        notification_id: &Uuid,
+    ) -> Result<bool, JobSchedulerError> {
+        self.on_notification_removal(job_scheduler, notification_id, Some(vec![JobState::Failed]))
+            .await
+            .map(|(_, deleted)| deleted)
+    }
+
+    ///
+    /// Run something when a run of the task took longer than its timeout and was cut short. Returns
+    /// a UUID as handle for this notification. This UUID needs to be used when you want to remove
+    /// the notification handle using `on_timed_out_notification_remove`.
+    pub async fn on_timed_out_notification_add(
+        &mut self,
+        job_scheduler: &JobsSchedulerLocked,
+        on_timed_out: Box<OnJobNotification>,
+    ) -> Result<Uuid, JobSchedulerError> {
+        self.on_notifications_add(job_scheduler, on_timed_out, vec![JobState::TimedOut])
+            .await
+    }
+
+    ///
+    /// Remove the notification when the task timed out. Uses the same UUID that was returned by
+    /// `on_timed_out_notification_add`
+    pub async fn on_timed_out_notification_remove(
+        &mut self,
+        job_scheduler: &JobsSchedulerLocked,
+        notification_id: &Uuid,
+    ) -> Result<bool, JobSchedulerError> {
+        self.on_notification_removal(
+            job_scheduler,
+            notification_id,
+            Some(vec![JobState::TimedOut]),
+        )
+        .await
+        .map(|(_, deleted)| deleted)
+    }
+
     ///
     /// Run something when the task was removed. Returns a UUID as handle for this notification. This
     /// UUID needs to be used when you want to remove the notification handle using `on_removed_notification_remove`.
@@ -773,6 +1914,370 @@ impl JobLocked {
         Ok(())
     }
 
+    ///
+    /// Set how long a run of this job may take. A run that takes longer is aborted and notified as
+    /// `TimedOut` instead of `Done`, the job's future isn't polled anymore. Only async jobs can be
+    /// cut short, a blocking job runs to its end regardless. The timeout is stored with the job and doesn't affect its schedule.
+    ///
+    /// ```rust,ignore
+    /// let mut job = Job::new_async("0 * * * * *", |_uuid, _l| Box::pin(async move {
+    ///     call_flaky_service().await;
+    /// }))?;
+    /// job.set_timeout(Some(Duration::from_secs(30)))?;
+    /// sched.add(job).await?;
+    /// ```
+    pub fn set_timeout(&mut self, timeout: Option<Duration>) -> Result<(), JobSchedulerError> {
+        let mut w = self
+            .0
+            .write()
+            .map_err(|_| JobSchedulerError::UpdateJobData)?;
+        let mut job_data = w
+            .job_data_from_job()?
+            .ok_or(JobSchedulerError::UpdateJobData)?;
+        job_data.timeout_ms = timeout.map(|t| t.as_millis() as u64);
+        w.set_job_data(job_data)
+    }
+
+    ///
+    /// Set whether a tick of this job is skipped while its previous run is still going, instead of
+    /// starting a second run next to it. Skipped ticks are notified as `Skipped`, with
+    /// `SkipReason::Overlap`, and aren't made up for later.
+    ///
+    /// Removing the job doesn't stop a run that is going. If the job is added again under the same
+    /// GUID before that run finishes, its ticks are skipped until it does.
+    pub fn set_skip_overlapping(&mut self, skip: bool) -> Result<(), JobSchedulerError> {
+        let mut w = self
+            .0
+            .write()
+            .map_err(|_| JobSchedulerError::UpdateJobData)?;
+        let mut job_data = w
+            .job_data_from_job()?
+            .ok_or(JobSchedulerError::UpdateJobData)?;
+        job_data.skip_overlapping = skip;
+        w.set_job_data(job_data)
+    }
+
+    ///
+    /// Set how often a failed run of a job created with `new_async_result` is retried, waiting
+    /// `backoff` before each retry. The run is only notified as `Failed` once the last retry
+    /// failed too. Retries count per run, a later tick starts over. Retries that are still waiting
+    /// when the scheduler shuts down are cancelled. A job's timeout covers the run including its
+    /// retries.
+    ///
+    /// ```rust,ignore
+    /// job.set_retries(3, Duration::from_secs(5))?;
+    /// ```
+    pub fn set_retries(
+        &mut self,
+        retries: u32,
+        backoff: Duration,
+    ) -> Result<(), JobSchedulerError> {
+        let mut w = self
+            .0
+            .write()
+            .map_err(|_| JobSchedulerError::UpdateJobData)?;
+        let mut job_data = w
+            .job_data_from_job()?
+            .ok_or(JobSchedulerError::UpdateJobData)?;
+        job_data.retries = retries;
+        job_data.retry_backoff_ms = backoff.as_millis() as u64;
+        w.set_job_data(job_data)
+    }
+
+    ///
+    /// Move every tick of a cron job a random number of milliseconds later, up to `max`, so jobs
+    /// with the same schedule don't all fire at once. The offset is drawn anew for every tick,
+    /// when the job is added and after each run, and stored as the job's next tick. Ticks are
+    /// kept in whole seconds, so the job fires at the start of the second its offset falls in,
+    /// never later than `max`; a `max` under a second leaves the ticks where they are. Seed the
+    /// scheduler's generator with `JobScheduler::set_jitter_seed` to get the same offsets every
+    /// time. Returns `UpdateJobData` for jobs that aren't cron jobs.
+    ///
+    /// ```rust,ignore
+    /// let job = Job::new_async("0 0 * * * *", |_uuid, _l| Box::pin(async move {
+    ///     call_rate_limited_api().await;
+    /// }))?
+    /// .with_jitter(Duration::from_secs(5 * 60))?;
+    /// sched.add(job).await?;
+    /// ```
+    pub fn with_jitter(self, max: Duration) -> Result<Self, JobSchedulerError> {
+        {
+            let mut w = self
+                .0
+                .write()
+                .map_err(|_| JobSchedulerError::UpdateJobData)?;
+            if !w.is_cron_job() {
+                error!("Only cron jobs can have jitter");
+                return Err(JobSchedulerError::UpdateJobData);
+            }
+            let mut job_data = w
+                .job_data_from_job()?
+                .ok_or(JobSchedulerError::UpdateJobData)?;
+            job_data.jitter_ms = max.as_millis() as u64;
+            w.set_job_data(job_data)?;
+        }
+        Ok(self)
+    }
+
+    ///
+    /// What the scheduler does when it finds the job's tick was missed, because the scheduler was
+    /// down or busy. By default a missed job runs once to catch up (`MisfirePolicy::FireOnce`).
+    /// `Skip` notifies the run as `Skipped` instead and `FireAll` runs the job once for every
+    /// tick that was missed, up to ten runs, so a frequent job doesn't stampede after a long
+    /// downtime. Either way the next tick is taken from the time the missed tick was found.
+    ///
+    /// ```rust,ignore
+    /// let job = Job::new("0 0 * * * *", |_uuid, _l| roll_up_hour())?
+    ///     .with_misfire_policy(MisfirePolicy::FireAll)?;
+    /// sched.add(job).await?;
+    /// ```
+    pub fn with_misfire_policy(self, policy: MisfirePolicy) -> Result<Self, JobSchedulerError> {
+        {
+            let mut w = self
+                .0
+                .write()
+                .map_err(|_| JobSchedulerError::UpdateJobData)?;
+            let mut job_data = w
+                .job_data_from_job()?
+                .ok_or(JobSchedulerError::UpdateJobData)?;
+            job_data.misfire_policy = policy.into();
+            w.set_job_data(job_data)?;
+        }
+        Ok(self)
+    }
+
+    ///
+    /// Remove the job once `expires_at` passed. The scheduler removes it on its first tick at or
+    /// after the deadline, whether the job is due or not, and sends the `Removed` notifications.
+    /// It doesn't fire from then on, so a job added when it already expired is removed without
+    /// running. The deadline is kept in whole seconds.
+    ///
+    /// ```rust,ignore
+    /// // Poll for the next hour
+    /// let job = Job::new_repeated(Duration::from_secs(60), |_uuid, _l| poll())?
+    ///     .with_expires_at(Utc::now() + chrono::Duration::hours(1))?;
+    /// sched.add(job).await?;
+    /// ```
+    pub fn with_expires_at(self, expires_at: DateTime<Utc>) -> Result<Self, JobSchedulerError> {
+        {
+            let mut w = self
+                .0
+                .write()
+                .map_err(|_| JobSchedulerError::UpdateJobData)?;
+            let mut job_data = w
+                .job_data_from_job()?
+                .ok_or(JobSchedulerError::UpdateJobData)?;
+            job_data.expires_at = Some(expires_at.timestamp().max(0) as u64);
+            w.set_job_data(job_data)?;
+        }
+        Ok(self)
+    }
+
+    ///
+    /// Dispatch the job before the jobs with a lower priority when they're due in the same tick.
+    /// The default is 0, jobs with the same priority are dispatched in no particular order. This
+    /// only orders dispatch: the runs are started one after the other but run concurrently, so a
+    /// job with a higher priority isn't guaranteed to finish, or even get far, before the next one
+    /// starts. Have the first job trigger the second when it has to be done first.
+    ///
+    /// ```rust,ignore
+    /// let prepare = Job::new("0 0 * * * *", |_uuid, _l| prepare())?.with_priority(10)?;
+    /// let consume = Job::new("0 0 * * * *", |_uuid, _l| consume())?;
+    /// ```
+    pub fn with_priority(self, priority: i32) -> Result<Self, JobSchedulerError> {
+        {
+            let mut w = self
+                .0
+                .write()
+                .map_err(|_| JobSchedulerError::UpdateJobData)?;
+            let mut job_data = w
+                .job_data_from_job()?
+                .ok_or(JobSchedulerError::UpdateJobData)?;
+            job_data.priority = priority;
+            w.set_job_data(job_data)?;
+        }
+        Ok(self)
+    }
+
+    ///
+    /// Only fire the job from `start` until `end`, like every day at noon but only in December.
+    /// Until `start` the job waits for its first tick at or after it, and at `end` the job is
+    /// removed like with `with_expires_at`. The job stays added the whole time, so there's no need
+    /// to add it when the window opens. A window that doesn't end after it starts is rejected with
+    /// `InvalidActiveWindow`. Both ends are kept in whole seconds.
+    ///
+    /// ```rust,ignore
+    /// let december = Job::new("0 0 12 * * *", |_uuid, _l| println!("Noon in December"))?
+    ///     .with_active_window(
+    ///         Utc.ymd(2030, 12, 1).and_hms(0, 0, 0),
+    ///         Utc.ymd(2031, 1, 1).and_hms(0, 0, 0),
+    ///     )?;
+    /// sched.add(december).await?;
+    /// ```
+    pub fn with_active_window(
+        self,
+        start: DateTime<Utc>,
+        end: DateTime<Utc>,
+    ) -> Result<Self, JobSchedulerError> {
+        if end <= start {
+            error!("An active window needs to end after it starts");
+            return Err(JobSchedulerError::InvalidActiveWindow);
+        }
+        {
+            let mut w = self
+                .0
+                .write()
+                .map_err(|_| JobSchedulerError::UpdateJobData)?;
+            let mut job_data = w
+                .job_data_from_job()?
+                .ok_or(JobSchedulerError::UpdateJobData)?;
+            job_data.active_from = Some(start.timestamp().max(0) as u64);
+            job_data.expires_at = Some(end.timestamp().max(0) as u64);
+            let next_tick =
+                match job_data.next_schedule_tick(&CronScheduleParser::default(), &Utc::now()) {
+                    Some(next_tick) => next_tick?,
+                    None => match job_data.next_tick_utc() {
+                        Some(next_tick) if next_tick < start => Some(start),
+                        next_tick => next_tick,
+                    },
+                };
+            job_data.set_next_tick(next_tick);
+            w.set_job_data(job_data)?;
+        }
+        Ok(self)
+    }
+
+    ///
+    /// Run `fallback` when a run of the job fails: its code returned an error, see
+    /// `new_async_result`, panicked or took longer than its timeout. A failing job is retried
+    /// first, see `set_retries`; only the run that failed after the retries runs the fallback. The
+    /// fallback gets the job's GUID and is run by the runner right after the failed run, followed
+    /// by a `FallbackRan` notification of the job once it finished. A fallback that panics is
+    /// reported on `JobScheduler::subscribe_failed`, it isn't retried. Like the job's code, the
+    /// fallback isn't stored and has to be given again after a restart.
+    ///
+    /// ```rust,ignore
+    /// let job = Job::new_async_result("0 0 * * * *", |_uuid, _l| Box::pin(sync_orders()))?
+    ///     .with_fallback(|job_id, _l| Box::pin(async move {
+    ///         alert(format!("Order sync {} failed", job_id)).await;
+    ///     }))?;
+    /// ```
+    pub fn with_fallback<T>(self, fallback: T) -> Result<Self, JobSchedulerError>
+    where
+        T: 'static,
+        T: FnMut(Uuid, JobsSchedulerLocked) -> Pin<Box<dyn Future<Output = ()> + Send>>
+            + Send
+            + Sync,
+    {
+        {
+            let mut w = self
+                .0
+                .write()
+                .map_err(|_| JobSchedulerError::UpdateJobData)?;
+            w.set_fallback(Box::new(fallback));
+        }
+        Ok(self)
+    }
+
+    ///
+    /// How the job fires around daylight saving changes in its timezone, see `new_cron_job_tz`.
+    /// `gap` is for a wall clock time that doesn't exist when the clocks go forward: moved forward
+    /// by the length of the gap (the default), skipped, or fired at the transition. `overlap` is for
+    /// a wall clock time that happens twice when the clocks go back: fired at its first occurrence
+    /// (the default), its last, or both. Only cron jobs have a timezone; other jobs return
+    /// `UpdateJobData`.
+    ///
+    /// ```rust,ignore
+    /// // Run at 02:30 Amsterdam time, not at all when the clocks skip it, twice when they repeat it
+    /// let job = Job::new_cron_job_tz("0 30 2 * * *", chrono_tz::Europe::Amsterdam, |_uuid, _l| {
+    ///             println!("It's 02:30");
+    ///         })?
+    ///     .with_dst_policy(DstGapPolicy::SkipGap, DstOverlapPolicy::BothOccurrences)?;
+    /// ```
+    pub fn with_dst_policy(
+        self,
+        gap: DstGapPolicy,
+        overlap: DstOverlapPolicy,
+    ) -> Result<Self, JobSchedulerError> {
+        {
+            let mut w = self
+                .0
+                .write()
+                .map_err(|_| JobSchedulerError::UpdateJobData)?;
+            let mut job_data = w
+                .job_data_from_job()?
+                .ok_or(JobSchedulerError::UpdateJobData)?;
+            match job_data.job.as_mut() {
+                #[cfg(feature = "has_bytes")]
+                Some(job_data_prost::job_stored_data::Job::CronJob(cron_job)) => {
+                    cron_job.dst_gap = gap.into();
+                    cron_job.dst_overlap = overlap.into();
+                }
+                #[cfg(not(feature = "has_bytes"))]
+                Some(job_data::job_stored_data::Job::CronJob(cron_job)) => {
+                    cron_job.dst_gap = gap.into();
+                    cron_job.dst_overlap = overlap.into();
+                }
+                _ => return Err(JobSchedulerError::UpdateJobData),
+            }
+            let next_tick = job_data
+                .next_schedule_tick(&CronScheduleParser::default(), &Utc::now())
+                .transpose()?
+                .flatten();
+            job_data.set_next_tick(next_tick);
+            w.set_job_data(job_data)?;
+        }
+        Ok(self)
+    }
+
+    ///
+    /// Tags the job, to find or remove it together with the other jobs of a group with
+    /// `JobScheduler::jobs_by_tag` and `JobScheduler::remove_by_tag`. The tags are stored with the
+    /// job, so they survive a restart with a persistent storage.
+    ///
+    /// ```rust,ignore
+    /// let job = Job::new("0 0 1 * * *", |_uuid, _l| send_invoices())?
+    ///     .with_tags(vec!["billing".to_string()])?;
+    /// sched.add(job).await?;
+    /// ```
+    pub fn with_tags(self, mut tags: Vec<String>) -> Result<Self, JobSchedulerError> {
+        tags.sort();
+        tags.dedup();
+        {
+            let mut w = self
+                .0
+                .write()
+                .map_err(|_| JobSchedulerError::UpdateJobData)?;
+            let mut job_data = w
+                .job_data_from_job()?
+                .ok_or(JobSchedulerError::UpdateJobData)?;
+            job_data.tags = tags;
+            w.set_job_data(job_data)?;
+        }
+        Ok(self)
+    }
+
+    ///
+    /// Pause this job on the scheduler, see `JobScheduler::pause`. The job keeps its GUID and
+    /// notifications while paused.
+    pub async fn pause(
+        &mut self,
+        job_scheduler: &JobsSchedulerLocked,
+    ) -> Result<(), JobSchedulerError> {
+        job_scheduler.pause(&self.guid()).await?;
+        self.set_stop(true)
+    }
+
+    ///
+    /// Resume this job after it was paused, see `JobScheduler::resume`
+    pub async fn resume(
+        &mut self,
+        job_scheduler: &JobsSchedulerLocked,
+    ) -> Result<(), JobSchedulerError> {
+        job_scheduler.resume(&self.guid()).await?;
+        self.set_stop(false)
+    }
+
     ///
     /// Get the job data
     pub fn job_data(&mut self) -> Result<JobStoredData, JobSchedulerError> {
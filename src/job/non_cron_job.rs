@@ -2,10 +2,13 @@
 use crate::job::job_data::{JobStoredData, JobType};
 #[cfg(feature = "has_bytes")]
 use crate::job::job_data_prost::{JobStoredData, JobType};
+use crate::job::runner::spawn_in_run;
 use crate::job::{Job, JobToRunAsync};
 use crate::{JobScheduler, JobSchedulerError, JobToRun};
 use chrono::{DateTime, Utc};
 use cron::Schedule;
+use std::future::Future;
+use std::pin::Pin;
 use tokio::sync::oneshot::Receiver;
 use tracing::error;
 use uuid::Uuid;
@@ -15,6 +18,8 @@ pub struct NonCronJob {
     pub run_async: Box<JobToRunAsync>,
     pub data: JobStoredData,
     pub async_job: bool,
+    /// Run after a run failed, see `JobLocked::with_fallback`
+    pub fallback: Option<Box<JobToRunAsync>>,
 }
 
 impl Job for NonCronJob {
@@ -112,6 +117,17 @@ impl Job for NonCronJob {
         Ok(())
     }
 
+    fn set_fallback(&mut self, fallback: Box<JobToRunAsync>) {
+        self.fallback = Some(fallback);
+    }
+
+    fn fallback(&mut self, jobs: JobScheduler) -> Option<Pin<Box<dyn Future<Output = ()> + Send>>> {
+        let job_id = self.job_id();
+        self.fallback
+            .as_mut()
+            .map(|fallback| fallback(job_id, jobs))
+    }
+
     fn run(&mut self, jobs: JobScheduler) -> Receiver<bool> {
         let (tx, rx) = tokio::sync::oneshot::channel();
         let job_id = self.job_id();
@@ -123,12 +139,7 @@ impl Job for NonCronJob {
             }
         } else {
             let future = (self.run_async)(job_id, jobs);
-            tokio::task::spawn(async move {
-                future.await;
-                if let Err(e) = tx.send(true) {
-                    error!("Error notifying done {:?}", e);
-                }
-            });
+            spawn_in_run(future, tx);
         }
         rx
     }
@@ -0,0 +1,76 @@
+#[cfg(not(feature = "has_bytes"))]
+use crate::job::job_data::JobState;
+#[cfg(feature = "has_bytes")]
+use crate::job::job_data_prost::JobState;
+use crate::job::{JobLocked, OnJobNotification};
+use crate::job_scheduler::JobsSchedulerLocked;
+use crate::JobSchedulerError;
+use uuid::Uuid;
+
+///
+/// Collects the notification handlers of a job, to add them in one go. Every handler is added as
+/// a notification of its own, so several handlers for the same state all run, in no particular
+/// order, like when calling `Job::on_notifications_add` for each of them.
+///
+/// ```rust,ignore
+/// let notification_ids = NotificationBuilder::new()
+///     .on_done(Box::new(|job_id, _, _| Box::pin(async move { info!("{:?} done", job_id) })))
+///     .on_done(Box::new(|job_id, _, _| Box::pin(async move { save_done(job_id).await })))
+///     .on(
+///         vec![JobState::Failed, JobState::TimedOut],
+///         Box::new(|job_id, _, _| Box::pin(async move { page(job_id).await })),
+///     )
+///     .add_to(&job, &sched)
+///     .await?;
+/// ```
+#[derive(Default)]
+pub struct NotificationBuilder {
+    handlers: Vec<(Vec<JobState>, Box<OnJobNotification>)>,
+}
+
+impl NotificationBuilder {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Run `run` on any of the states
+    pub fn on(mut self, states: Vec<JobState>, run: Box<OnJobNotification>) -> Self {
+        self.handlers.push((states, run));
+        self
+    }
+
+    /// See `Job::on_start_notification_add`
+    pub fn on_start(self, run: Box<OnJobNotification>) -> Self {
+        self.on(vec![JobState::Started], run)
+    }
+
+    /// See `Job::on_done_notification_add`
+    pub fn on_done(self, run: Box<OnJobNotification>) -> Self {
+        self.on(vec![JobState::Done], run)
+    }
+
+    /// See `Job::on_failed_notification_add`
+    pub fn on_failed(self, run: Box<OnJobNotification>) -> Self {
+        self.on(vec![JobState::Failed], run)
+    }
+
+    /// See `Job::on_removed_notification_add`
+    pub fn on_removed(self, run: Box<OnJobNotification>) -> Self {
+        self.on(vec![JobState::Removed], run)
+    }
+
+    ///
+    /// Add the handlers to the job. Returns the ids of the notifications, in the order the
+    /// handlers were given, to remove them again with `Job::on_notification_removal`.
+    pub async fn add_to(
+        self,
+        job: &JobLocked,
+        job_scheduler: &JobsSchedulerLocked,
+    ) -> Result<Vec<Uuid>, JobSchedulerError> {
+        let mut ids = Vec::with_capacity(self.handlers.len());
+        for (states, run) in self.handlers {
+            ids.push(job.on_notifications_add(job_scheduler, run, states).await?);
+        }
+        Ok(ids)
+    }
+}
@@ -0,0 +1,45 @@
+use crate::JobSchedulerError;
+use std::time::Duration;
+
+///
+/// How a failed run of a job made with `Job::new_async_result` is retried, see
+/// `JobScheduler::update_retry_policy` and `Job::set_retries`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RetryPolicy {
+    /// How often a run is attempted, the first attempt included, so 1 doesn't retry
+    pub max_attempts: u32,
+    /// How long to wait before each retry, kept in whole milliseconds
+    pub backoff: Duration,
+}
+
+impl RetryPolicy {
+    pub fn new(max_attempts: u32, backoff: Duration) -> Self {
+        Self {
+            max_attempts,
+            backoff,
+        }
+    }
+
+    ///
+    /// `InvalidRetryPolicy` when there are no attempts at all, or the backoff doesn't fit in
+    /// milliseconds
+    pub(crate) fn validate(&self) -> Result<(), JobSchedulerError> {
+        if self.max_attempts == 0 {
+            return Err(JobSchedulerError::InvalidRetryPolicy(
+                "max_attempts needs to be at least 1".to_string(),
+            ));
+        }
+        if self.backoff.as_millis() > u64::MAX as u128 {
+            return Err(JobSchedulerError::InvalidRetryPolicy(
+                "backoff is too long".to_string(),
+            ));
+        }
+        Ok(())
+    }
+
+    ///
+    /// The retries after the first attempt, as stored
+    pub(crate) fn retries(&self) -> u32 {
+        self.max_attempts.saturating_sub(1)
+    }
+}
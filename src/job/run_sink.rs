@@ -0,0 +1,45 @@
+use crate::job::Outcome;
+use crate::JobSchedulerError;
+use chrono::{DateTime, Utc};
+use std::future::Future;
+use std::pin::Pin;
+use uuid::Uuid;
+
+///
+/// The outcome of a single run of a job, handed to the [RunSink] after the run finished.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RunRecord {
+    pub job_id: Uuid,
+    pub started_at: DateTime<Utc>,
+    pub finished_at: DateTime<Utc>,
+    /// How the run ended
+    pub outcome: Outcome,
+    /// The message of the error, panic or timeout the run failed with
+    pub error: Option<String>,
+}
+
+///
+/// Receives a [RunRecord] for each job run, so runs can be forwarded to a durable external
+/// system (a file, a message queue, an analytics store). The sink is called on a task of its own
+/// once the run finished, so a slow sink doesn't hold up the job, its removal or a shutdown
+/// waiting on it. Errors returned by the sink are logged and don't affect the job.
+pub trait RunSink {
+    fn record(
+        &self,
+        record: RunRecord,
+    ) -> Pin<Box<dyn Future<Output = Result<(), JobSchedulerError>> + Send>>;
+}
+
+///
+/// The default sink, which drops all records
+#[derive(Default)]
+pub struct NoopRunSink {}
+
+impl RunSink for NoopRunSink {
+    fn record(
+        &self,
+        _record: RunRecord,
+    ) -> Pin<Box<dyn Future<Output = Result<(), JobSchedulerError>> + Send>> {
+        Box::pin(async { Ok(()) })
+    }
+}
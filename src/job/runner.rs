@@ -1,36 +1,342 @@
 use crate::context::Context;
 #[cfg(not(feature = "has_bytes"))]
-use crate::job::job_data::JobState;
+use crate::job::job_data::{JobState, JobType};
 #[cfg(feature = "has_bytes")]
-use crate::job::job_data_prost::JobState;
-use crate::job::to_code::JobCode;
-use crate::job_scheduler::JobsSchedulerLocked;
+use crate::job::job_data_prost::{JobState, JobType};
+use crate::job::{
+    HistoryStore, JobLocked, JobMiddleware, JobRun, JobRunContext, JobRunRecord, NoopHistoryStore,
+    NoopRunSink, Outcome, RunRecord, RunSink, SkipReason,
+};
+use crate::job_scheduler::{JobPanicHandler, JobsSchedulerLocked};
 use crate::JobSchedulerError;
+use chrono::Utc;
+use std::any::Any;
+use std::collections::{HashMap, HashSet};
 use std::future::Future;
+use std::panic::AssertUnwindSafe;
 use std::pin::Pin;
-use std::sync::Arc;
+use std::sync::{Arc, Mutex};
+use std::task::{Context as TaskContext, Poll};
+use std::time::Duration;
+use tokio::runtime::Handle;
 use tokio::sync::broadcast::{Receiver, Sender};
-use tokio::sync::RwLock;
-use tracing::error;
+use tokio::sync::{oneshot, RwLock, Semaphore};
+use tokio::task::JoinHandle;
+use tracing::{error, Instrument};
 use uuid::Uuid;
 
-#[derive(Default)]
-pub struct JobRunner {}
+tokio::task_local! {
+    /// Set to the error by a job made with `Job::new_async_result` when its run failed
+    pub(crate) static RUN_FAILED: Arc<Mutex<Option<String>>>;
+    /// The message of the panic of an async job's spawned future
+    static RUN_PANICKED: Arc<Mutex<Option<String>>>;
+    /// The task an async job's future was spawned on, aborted when the run times out
+    static RUN_SPAWNED: Arc<Mutex<Option<JoinHandle<()>>>>;
+    /// The job that is run, set by its code, to run its fallback when the run fails
+    pub(crate) static RUN_JOB: Arc<Mutex<Option<JobLocked>>>;
+}
+
+/// Spawns an async job's future, keeping the span, the failure flag and a panic of its run.
+/// `done` is told once the future finished, a panic is kept before it is. The task is kept too,
+/// so a run that times out can abort it.
+pub(crate) fn spawn_in_run<F>(future: F, done: oneshot::Sender<bool>)
+where
+    F: Future<Output = ()> + Send + 'static,
+{
+    let panicked = RUN_PANICKED.try_with(Arc::clone).ok();
+    let run = async move {
+        if let Err(message) = CatchPanic(Box::pin(future)).await {
+            if let Some(panicked) = panicked {
+                if let Ok(mut w) = panicked.lock() {
+                    *w = Some(message);
+                }
+            }
+        }
+        if let Err(e) = done.send(true) {
+            error!("Error notifying done {:?}", e);
+        }
+    };
+    let task = match RUN_FAILED.try_with(Arc::clone) {
+        Ok(failed) => tokio::spawn(RUN_FAILED.scope(failed, run).in_current_span()),
+        Err(_) => tokio::spawn(run.in_current_span()),
+    };
+    if let Ok(spawned) = RUN_SPAWNED.try_with(Arc::clone) {
+        if let Ok(mut w) = spawned.lock() {
+            *w = Some(task);
+        }
+    }
+}
+
+/// Aborts the task `spawn_in_run` spawned an async job's future on. Dropping the run's future
+/// only stops waiting on it, the job's own future would run on.
+fn abort_spawned(spawned: &Mutex<Option<JoinHandle<()>>>) {
+    if let Some(task) = spawned.lock().ok().and_then(|mut w| w.take()) {
+        task.abort();
+    }
+}
+
+/// Runs the fallback of a job whose run failed, if it has one, and notifies `FallbackRan` once it
+/// finished
+async fn run_fallback(
+    job_id: Uuid,
+    job: Option<JobLocked>,
+    job_scheduler: JobsSchedulerLocked,
+    tx_notify: &Sender<(Uuid, JobState)>,
+    tx_failed: &Sender<(Uuid, String)>,
+) {
+    let fallback = job.and_then(|job| {
+        // A run that panicked poisoned the lock, the fallback runs regardless
+        let mut w = match job.0.write() {
+            Ok(w) => w,
+            Err(poisoned) => poisoned.into_inner(),
+        };
+        w.fallback(job_scheduler)
+    });
+    let fallback = match fallback {
+        Some(fallback) => fallback,
+        None => return,
+    };
+    if let Err(message) = CatchPanic(fallback).await {
+        error!("Fallback of {:?} panicked {}", job_id, message);
+        if let Err(e) = tx_failed.send((job_id, message)) {
+            // Only fails when nobody subscribed to the errors
+            tracing::trace!("No listener for job errors {:?}", e);
+        }
+    }
+    if let Err(e) = tx_notify.send((job_id, JobState::FallbackRan)) {
+        error!("Error sending notification fallback ran {:?}", e);
+    }
+}
+
+/// Polls a future, turning a panic while polling into the panic's message
+struct CatchPanic<F>(F);
+
+impl<F: Future + Unpin> Future for CatchPanic<F> {
+    type Output = Result<F::Output, String>;
+
+    fn poll(mut self: Pin<&mut Self>, cx: &mut TaskContext<'_>) -> Poll<Self::Output> {
+        let inner = &mut self.0;
+        match std::panic::catch_unwind(AssertUnwindSafe(|| Pin::new(inner).poll(cx))) {
+            Ok(Poll::Ready(output)) => Poll::Ready(Ok(output)),
+            Ok(Poll::Pending) => Poll::Pending,
+            Err(panic) => Poll::Ready(Err(panic_message(panic.as_ref()))),
+        }
+    }
+}
+
+fn panic_message(panic: &(dyn Any + Send)) -> String {
+    match panic.downcast_ref::<&str>() {
+        Some(message) => message.to_string(),
+        None => match panic.downcast_ref::<String>() {
+            Some(message) => message.clone(),
+            None => "Box<dyn Any>".to_string(),
+        },
+    }
+}
+
+/// Counts a job as executing for as long as it lives, so a run that panics or is aborted is
+/// uncounted too
+struct Executing {
+    job_id: Uuid,
+    executing: Arc<Mutex<HashMap<Uuid, usize>>>,
+}
+
+impl Executing {
+    fn start(job_id: Uuid, executing: Arc<Mutex<HashMap<Uuid, usize>>>) -> Self {
+        if let Ok(mut w) = executing.lock() {
+            *w.entry(job_id).or_default() += 1;
+        }
+        Self { job_id, executing }
+    }
+}
+
+impl Drop for Executing {
+    fn drop(&mut self) {
+        if let Ok(mut w) = self.executing.lock() {
+            if let Some(runs) = w.get_mut(&self.job_id) {
+                *runs -= 1;
+                if *runs == 0 {
+                    w.remove(&self.job_id);
+                }
+            }
+        }
+    }
+}
+
+/// The handles are all shared, a clone is the same runner
+#[derive(Clone)]
+pub struct JobRunner {
+    pub run_sink: Arc<RwLock<Box<dyn RunSink + Send + Sync>>>,
+    pub history_store: Arc<RwLock<Box<dyn HistoryStore + Send + Sync>>>,
+    pub middlewares: Arc<RwLock<Vec<Arc<dyn JobMiddleware + Send + Sync>>>>,
+    /// Jobs that skip overlapping runs and have a run going
+    pub in_flight: Arc<RwLock<HashSet<Uuid>>>,
+    /// The runs that haven't finished yet, by run id, with the id of their job
+    pub running: Arc<RwLock<HashMap<Uuid, (Uuid, JoinHandle<()>)>>>,
+    /// Limits how many runs go on at once, unlimited when `None`
+    pub max_concurrent: Arc<RwLock<Option<Arc<Semaphore>>>>,
+    /// The jobs whose code is running, with the number of their runs going on. Queued runs
+    /// aren't in it.
+    pub executing: Arc<Mutex<HashMap<Uuid, usize>>>,
+    /// The runtime the runs are spawned on, the scheduler's own when `None`
+    pub runtime: Arc<RwLock<Option<Handle>>>,
+    /// Called with the job's id and the message when a run panics
+    pub panic_handler: Arc<RwLock<Option<Box<JobPanicHandler>>>>,
+}
+
+impl Default for JobRunner {
+    fn default() -> Self {
+        Self {
+            run_sink: Arc::new(RwLock::new(Box::new(NoopRunSink::default()))),
+            history_store: Arc::new(RwLock::new(Box::new(NoopHistoryStore::default()))),
+            middlewares: Arc::new(RwLock::new(vec![])),
+            in_flight: Arc::new(RwLock::new(HashSet::new())),
+            running: Arc::new(RwLock::new(HashMap::new())),
+            max_concurrent: Arc::new(RwLock::new(None)),
+            executing: Arc::new(Mutex::new(HashMap::new())),
+            runtime: Arc::new(RwLock::new(None)),
+            panic_handler: Arc::new(RwLock::new(None)),
+        }
+    }
+}
 
 impl JobRunner {
+    ///
+    /// Forget the runs of the jobs that are removed
+    async fn listen_to_job_removals(
+        history_store: Arc<RwLock<Box<dyn HistoryStore + Send + Sync>>>,
+        mut rx_job_deleted: Receiver<Result<Uuid, (JobSchedulerError, Option<Uuid>)>>,
+    ) {
+        loop {
+            let val = rx_job_deleted.recv().await;
+            if let Err(e) = val {
+                error!("Error receiving deleted jobs {:?}", e);
+                break;
+            }
+            let job_id = match val.unwrap() {
+                Ok(job_id) => job_id,
+                // The job is still stored, so is its history
+                Err(_) => continue,
+            };
+            let cleared = {
+                let r = history_store.read().await;
+                r.clear(job_id)
+            };
+            if let Err(e) = cleared.await {
+                error!("Error clearing history of {:?} {:?}", job_id, e);
+            }
+        }
+    }
+
     async fn listen_for_activations(
-        job_code: Arc<RwLock<Box<dyn JobCode + Send + Sync>>>,
+        context: Context,
         mut rx: Receiver<Uuid>,
-        tx_notify: Sender<(Uuid, JobState)>,
+        mut rx_run_now: Receiver<Uuid>,
         job_scheduler: JobsSchedulerLocked,
+        runner: JobRunner,
     ) {
+        let JobRunner {
+            run_sink,
+            history_store,
+            middlewares,
+            in_flight,
+            running,
+            max_concurrent,
+            executing,
+            runtime,
+            panic_handler,
+        } = runner;
+        let job_code = context.job_code;
+        let metadata_storage = context.metadata_storage;
+        let tx_notify = context.notify_tx;
+        let tx_skipped = context.skipped_tx;
+        let tx_failed = context.failed_tx;
+        let shutdown_tx = context.shutdown_tx;
         loop {
-            let val = rx.recv().await;
+            // Runs asked for now are outside of the schedule, they don't count towards `max_runs`
+            let (val, run_now) = tokio::select! {
+                val = rx.recv() => (val, false),
+                val = rx_run_now.recv() => (val, true),
+            };
             if let Err(e) = val {
                 error!("Error receiving {:?}", e);
                 break;
             }
             let uuid = val.unwrap();
+            if *shutdown_tx.borrow() {
+                // A graceful shutdown only waits for the runs started before it
+                tracing::debug!("Scheduler shut down, dropped activation of {:?}", uuid);
+                continue;
+            }
+            let (timeout, skip_overlapping, job_type) = {
+                let mut w = metadata_storage.write().await;
+                match w.get(uuid).await {
+                    Ok(Some(data)) => (
+                        data.timeout_ms.map(Duration::from_millis),
+                        data.skip_overlapping,
+                        JobType::from_i32(data.job_type),
+                    ),
+                    Ok(None) => {
+                        // Removed after it was activated, its code may not be dropped yet
+                        tracing::debug!("Dropped activation of removed job {:?}", uuid);
+                        continue;
+                    }
+                    Err(e) => {
+                        error!("Error getting {:?} to run it {:?}", uuid, e);
+                        (None, false, None)
+                    }
+                }
+            };
+            if skip_overlapping {
+                let mut w = in_flight.write().await;
+                if !w.insert(uuid) {
+                    let tx = tx_notify.clone();
+                    let tx_skipped = tx_skipped.clone();
+                    tokio::spawn(async move {
+                        if let Err(e) = tx_skipped.send((uuid, SkipReason::Overlap)) {
+                            // Only fails when nobody subscribed to the skip reasons
+                            tracing::trace!("No listener for skip reason {:?}", e);
+                        }
+                        if let Err(e) = tx.send((uuid, JobState::Skipped)) {
+                            error!("Error sending notification skipped {:?}", e);
+                        }
+                    });
+                    continue;
+                }
+            }
+            let last_run = if run_now {
+                false
+            } else {
+                // Counted in the store, so the count survives restarts
+                let mut w = metadata_storage.write().await;
+                match w.get(uuid).await {
+                    Ok(Some(data)) if matches!(data.max_runs, Some(max) if data.count >= max) => {
+                        // The last run is going on, the job is removed once it finished
+                        if skip_overlapping {
+                            let mut w = in_flight.write().await;
+                            w.remove(&uuid);
+                        }
+                        continue;
+                    }
+                    Ok(Some(mut data)) => {
+                        data.count = if data.count == u32::MAX {
+                            0
+                        } else {
+                            data.count + 1
+                        };
+                        let last_run = matches!(data.max_runs, Some(max) if data.count >= max);
+                        if let Err(e) = w.add_or_update(data).await {
+                            error!("Error saving the run count of {:?} {:?}", uuid, e);
+                        }
+                        last_run
+                    }
+                    Ok(None) => false,
+                    Err(e) => {
+                        error!("Error getting {:?} to count its run {:?}", uuid, e);
+                        false
+                    }
+                }
+            };
             {
                 let tx = tx_notify.clone();
                 tokio::spawn(async move {
@@ -43,18 +349,224 @@ impl JobRunner {
             let code = w.get(uuid).await;
             match code {
                 Ok(Some(job)) => {
+                    #[cfg(feature = "metrics")]
+                    metrics::increment_counter!("tcs_jobs_started_total");
                     let mut job = job.write().await;
+                    let span = tracing::info_span!(
+                        "job_run",
+                        job_id = %uuid,
+                        job_type = ?job_type,
+                        duration_ms = tracing::field::Empty,
+                    );
                     let v = (job)(uuid, job_scheduler.clone());
                     let tx = tx_notify.clone();
-                    tokio::spawn(async move {
-                        v.await;
-                        if let Err(e) = tx.send((uuid, JobState::Done)) {
+                    let tx_failed = tx_failed.clone();
+                    let run_sink = run_sink.clone();
+                    let history_store = history_store.clone();
+                    let panic_handler = panic_handler.clone();
+                    let middlewares = middlewares.clone();
+                    let in_flight = in_flight.clone();
+                    let limit = max_concurrent.read().await.clone();
+                    let executing = executing.clone();
+                    let job_scheduler = job_scheduler.clone();
+                    let run_id = Uuid::new_v4();
+                    // Held until the handle is in, so a quick run can't remove itself before
+                    let mut runs = running.write().await;
+                    let for_run = running.clone();
+                    let run = async move {
+                        // Queues behind the other runs, without holding up the next activations
+                        let _permit = match limit {
+                            Some(limit) => {
+                                let permit = limit.acquire_owned().await.ok();
+                                if job_scheduler.is_shutdown().await {
+                                    tracing::info!(
+                                        "Scheduler shut down, dropped queued run of {:?}",
+                                        uuid
+                                    );
+                                    if skip_overlapping {
+                                        let mut w = in_flight.write().await;
+                                        w.remove(&uuid);
+                                    }
+                                    let mut w = for_run.write().await;
+                                    w.remove(&run_id);
+                                    return;
+                                }
+                                permit
+                            }
+                            None => None,
+                        };
+                        let started_at = Utc::now();
+                        let executing = Executing::start(uuid, executing);
+                        #[cfg(feature = "metrics")]
+                        metrics::increment_gauge!("tcs_jobs_running", 1.0);
+                        // A panic of the job's code fails the run, instead of taking the runner down
+                        let v = CatchPanic(v);
+                        let spawned: Arc<Mutex<Option<JoinHandle<()>>>> =
+                            Arc::new(Mutex::new(None));
+                        let mut v: JobRun =
+                            match timeout {
+                                Some(timeout) => {
+                                    let spawned = spawned.clone();
+                                    Box::pin(async move {
+                                        match tokio::time::timeout(timeout, v).await {
+                                            Ok(ran) => ran.map_err(JobSchedulerError::JobPanicked),
+                                            Err(_) => {
+                                                abort_spawned(&spawned);
+                                                Err(JobSchedulerError::JobTimedOut)
+                                            }
+                                        }
+                                    })
+                                }
+                                None => Box::pin(async move {
+                                    v.await.map_err(JobSchedulerError::JobPanicked)
+                                }),
+                            };
+                        {
+                            let middlewares = middlewares.read().await;
+                            for middleware in middlewares.iter().rev() {
+                                let context = JobRunContext {
+                                    job_id: uuid,
+                                    started_at,
+                                };
+                                v = middleware.wrap(context, v);
+                            }
+                        }
+                        let failed = Arc::new(Mutex::new(None));
+                        let panicked = Arc::new(Mutex::new(None));
+                        let run_job = Arc::new(Mutex::new(None));
+                        let ran = RUN_JOB
+                            .scope(
+                                run_job.clone(),
+                                RUN_SPAWNED.scope(
+                                    spawned,
+                                    RUN_PANICKED.scope(
+                                        panicked.clone(),
+                                        RUN_FAILED.scope(failed.clone(), v),
+                                    ),
+                                ),
+                            )
+                            .await;
+                        drop(executing);
+                        let failed = failed.lock().ok().and_then(|mut f| f.take());
+                        let panicked = panicked.lock().ok().and_then(|mut p| p.take());
+                        let ran = match (ran, panicked) {
+                            (Ok(()), Some(message)) => Err(JobSchedulerError::JobPanicked(message)),
+                            (ran, _) => ran,
+                        };
+                        let (state, outcome, run_error) = match ran {
+                            Ok(()) => match failed {
+                                Some(message) => (JobState::Done, Outcome::Failed, Some(message)),
+                                None => (JobState::Done, Outcome::Succeeded, None),
+                            },
+                            Err(JobSchedulerError::JobTimedOut) => {
+                                error!("Run of {:?} timed out", uuid);
+                                (
+                                    JobState::TimedOut,
+                                    Outcome::TimedOut,
+                                    Some(JobSchedulerError::JobTimedOut.to_string()),
+                                )
+                            }
+                            Err(JobSchedulerError::JobPanicked(message)) => {
+                                error!("Run of {:?} panicked {}", uuid, message);
+                                if let Err(e) = tx.send((uuid, JobState::Failed)) {
+                                    error!("Error sending notification failed {:?}", e);
+                                }
+                                if let Err(e) = tx_failed.send((uuid, message.clone())) {
+                                    // Only fails when nobody subscribed to the errors
+                                    tracing::trace!("No listener for job errors {:?}", e);
+                                }
+                                let mut w = panic_handler.write().await;
+                                if let Some(handler) = w.as_mut() {
+                                    handler(uuid, message.clone(), job_scheduler.clone()).await;
+                                }
+                                (JobState::Done, Outcome::Failed, Some(message))
+                            }
+                            Err(e) => {
+                                error!("Error running {:?} {:?}", uuid, e);
+                                let message = e.to_string();
+                                if let Err(e) = tx.send((uuid, JobState::Failed)) {
+                                    error!("Error sending notification failed {:?}", e);
+                                }
+                                if let Err(e) = tx_failed.send((uuid, message.clone())) {
+                                    // Only fails when nobody subscribed to the errors
+                                    tracing::trace!("No listener for job errors {:?}", e);
+                                }
+                                (JobState::Done, Outcome::Failed, Some(message))
+                            }
+                        };
+                        if outcome != Outcome::Succeeded {
+                            let job = run_job.lock().ok().and_then(|mut w| w.take());
+                            run_fallback(uuid, job, job_scheduler.clone(), &tx, &tx_failed).await;
+                        }
+                        let finished_at = Utc::now();
+                        #[cfg(feature = "metrics")]
+                        {
+                            metrics::decrement_gauge!("tcs_jobs_running", 1.0);
+                            metrics::increment_counter!("tcs_jobs_completed_total");
+                            if outcome != Outcome::Succeeded {
+                                metrics::increment_counter!("tcs_jobs_failed_total");
+                            }
+                        }
+                        tracing::Span::current().record(
+                            "duration_ms",
+                            &((finished_at - started_at).num_milliseconds()),
+                        );
+                        if skip_overlapping {
+                            let mut w = in_flight.write().await;
+                            w.remove(&uuid);
+                        }
+                        if let Err(e) = tx.send((uuid, state)) {
                             error!("Error sending spawned task {:?}", e);
                         }
-                    });
+                        let record = RunRecord {
+                            job_id: uuid,
+                            started_at,
+                            finished_at,
+                            outcome,
+                            error: run_error,
+                        };
+                        // A slow sink doesn't hold the run up, nor a shutdown waiting on it
+                        tokio::spawn(async move {
+                            let record = {
+                                let r = run_sink.read().await;
+                                r.record(record)
+                            };
+                            if let Err(e) = record.await {
+                                error!("Error recording run of {:?} {:?}", uuid, e);
+                            }
+                        });
+                        let history = JobRunRecord {
+                            started: started_at,
+                            duration: (finished_at - started_at).to_std().unwrap_or_default(),
+                            outcome,
+                        };
+                        let history = {
+                            let r = history_store.read().await;
+                            r.record(uuid, history)
+                        };
+                        if let Err(e) = history.await {
+                            error!("Error keeping history of {:?} {:?}", uuid, e);
+                        }
+                        if last_run {
+                            if let Err(e) = job_scheduler.remove(&uuid).await {
+                                error!("Error removing {:?} after its last run {:?}", uuid, e);
+                            }
+                        }
+                        let mut w = for_run.write().await;
+                        w.remove(&run_id);
+                    };
+                    let handle = match runtime.read().await.as_ref() {
+                        Some(runtime) => runtime.spawn(run.instrument(span)),
+                        None => tokio::spawn(run.instrument(span)),
+                    };
+                    runs.insert(run_id, (uuid, handle));
                 }
                 _ => {
                     error!("Error getting {:?} from job code", uuid);
+                    if skip_overlapping {
+                        let mut w = in_flight.write().await;
+                        w.remove(&uuid);
+                    }
                     continue;
                 }
             }
@@ -66,18 +578,54 @@ impl JobRunner {
         context: &Context,
         job_scheduler: JobsSchedulerLocked,
     ) -> Pin<Box<dyn Future<Output = Result<(), JobSchedulerError>> + Send>> {
-        let job_code = context.job_code.clone();
-        let notify_tx = context.notify_tx.clone();
+        let context = context.clone();
         let job_activation_rx = context.job_activation_tx.subscribe();
+        let job_run_now_rx = context.job_run_now_tx.subscribe();
+        let job_deleted_rx = context.job_deleted_tx.subscribe();
+        let runner = self.clone();
 
         Box::pin(async move {
+            tokio::spawn(JobRunner::listen_to_job_removals(
+                runner.history_store.clone(),
+                job_deleted_rx,
+            ));
             tokio::spawn(JobRunner::listen_for_activations(
-                job_code,
+                context,
                 job_activation_rx,
-                notify_tx,
+                job_run_now_rx,
                 job_scheduler,
+                runner,
             ));
             Ok(())
         })
     }
+
+    ///
+    /// Wait up to `timeout` for the runs going on to finish. Runs still going after that are
+    /// aborted; the ids of their jobs are returned.
+    pub async fn wait_for_runs(&self, timeout: Duration) -> Vec<Uuid> {
+        let deadline = tokio::time::Instant::now() + timeout;
+        let mut cancelled = vec![];
+        // Until none are left, a run of an activation taken just before the shutdown can still
+        // start while waiting
+        loop {
+            let runs = {
+                let mut w = self.running.write().await;
+                w.drain().map(|(_, run)| run).collect::<Vec<_>>()
+            };
+            if runs.is_empty() {
+                break;
+            }
+            for (job_id, mut handle) in runs {
+                if tokio::time::timeout_at(deadline, &mut handle)
+                    .await
+                    .is_err()
+                {
+                    handle.abort();
+                    cancelled.push(job_id);
+                }
+            }
+        }
+        cancelled
+    }
 }
@@ -0,0 +1,207 @@
+use crate::job::day_filter;
+#[cfg(not(feature = "has_bytes"))]
+use crate::job::job_data::{DstGapPolicy, DstOverlapPolicy};
+#[cfg(feature = "has_bytes")]
+use crate::job::job_data_prost::{DstGapPolicy, DstOverlapPolicy};
+use crate::JobSchedulerError;
+use chrono::{DateTime, LocalResult, NaiveDateTime, Offset, TimeZone, Utc};
+use cron::{Schedule, TimeUnitSpec};
+use std::str::FromStr;
+use std::time::Duration;
+
+///
+/// Parses a cron job's schedule and projects when it fires. Implement it to use a cron dialect
+/// other than the one of the `cron` crate, and set it on the scheduler with
+/// `JobScheduler::set_schedule_parser`. Jobs using the dialect are created with
+/// `Job::new_cron_job_with_parser` or `JobBuilder::with_schedule_parser`. The scheduler's parser
+/// works out the ticks of every cron job, the first one when the job is added.
+///
+/// The schedule is stored as the string it was created with, so the parser has to be able to
+/// evaluate it again after a restart. `next_after` must return the first occurrence strictly after
+/// `after` in UTC, or `None` when the schedule doesn't fire anymore.
+pub trait ScheduleParser {
+    fn next_after(
+        &self,
+        schedule: &str,
+        after: &DateTime<Utc>,
+    ) -> Result<Option<DateTime<Utc>>, JobSchedulerError>;
+}
+
+/// How far ahead the default parser looks for the next tick. Long enough for `0 0 0 29 2 *`, which
+/// skips the year 2100 and so can be eight years away.
+const DEFAULT_HORIZON: Duration = Duration::from_secs(10 * 366 * 24 * 60 * 60);
+
+///
+/// The default parser, using the `cron` crate. A schedule whose next tick lies further ahead than
+/// the horizon, ten years by default, fails with `JobSchedulerError::ScheduleNeverFires`, so an
+/// impossible or pathological expression doesn't leave a job waiting for a tick that won't come.
+/// A schedule that names its years, like `0 0 12 1 Jan * 2099`, is taken at its word and isn't
+/// held to the horizon.
+///
+/// On top of the `cron` crate's syntax it understands the `L` and `#` modifiers for the days:
+/// `L` in the day of month field fires on the last day of the month, `FriL` (or `6L`) in the day
+/// of week field on the last Friday of the month and `Mon#3` (or `2#3`) on the third Monday.
+/// Jobs using them are created with `Job::new_with_shorthand`, `JobBuilder` or
+/// `Job::new_cron_job_with_parser`; `Job::new` and the other constructors taking a
+/// `TryInto<Schedule>` parse with the `cron` crate alone and reject them with `ParseSchedule`.
+/// ```rust,ignore
+/// // Payroll at noon on the last Friday of the month
+/// let job = Job::new_cron_job_with_parser("0 0 12 * * FriL", &CronScheduleParser::default(), run)?;
+/// ```
+pub struct CronScheduleParser {
+    horizon: Duration,
+}
+
+impl Default for CronScheduleParser {
+    fn default() -> Self {
+        Self {
+            horizon: DEFAULT_HORIZON,
+        }
+    }
+}
+
+impl CronScheduleParser {
+    ///
+    /// A parser that looks `horizon` ahead for the next tick, set it with
+    /// `JobScheduler::set_schedule_parser`
+    pub fn with_horizon(horizon: Duration) -> Self {
+        Self { horizon }
+    }
+}
+
+impl ScheduleParser for CronScheduleParser {
+    fn next_after(
+        &self,
+        schedule: &str,
+        after: &DateTime<Utc>,
+    ) -> Result<Option<DateTime<Utc>>, JobSchedulerError> {
+        let (schedule, filter) = day_filter::split(schedule)?;
+        let schedule =
+            Schedule::from_str(&schedule).map_err(|_| JobSchedulerError::ParseSchedule)?;
+        let horizon = chrono::Duration::from_std(self.horizon)
+            .ok()
+            .and_then(|horizon| after.checked_add_signed(horizon))
+            .filter(|_| schedule.years().is_all());
+        let mut after = *after;
+        loop {
+            let next = match (schedule.after(&after).next(), horizon) {
+                (Some(next), Some(horizon)) if next > horizon => {
+                    return Err(JobSchedulerError::ScheduleNeverFires)
+                }
+                (next, _) => next,
+            };
+            match (next, filter) {
+                (Some(next), Some(filter)) if !filter.matches(next.naive_utc().date()) => {
+                    // None of the day's ticks fire, go on with the next day
+                    after = Utc.from_utc_datetime(&next.naive_utc().date().and_hms(23, 59, 59));
+                }
+                (next, _) => return Ok(next),
+            }
+        }
+    }
+}
+
+///
+/// The first time `schedule` fires after `after`, with the schedule evaluated on the wall clock of
+/// `timezone`. A wall clock time that doesn't happen, when the clocks go forward, fires as `gap`
+/// says: moved forward by the length of the gap, so 02:30 in a gap from 02:00 to 03:00 fires at
+/// 03:30, skipped, or at the end of the gap, 03:00. A wall clock time that happens twice, when the
+/// clocks go back, fires as `overlap` says: at its first occurrence, its last or both.
+pub(crate) fn next_after_in<TZ: TimeZone>(
+    parser: &dyn ScheduleParser,
+    schedule: &str,
+    timezone: &TZ,
+    after: &DateTime<Utc>,
+    gap: DstGapPolicy,
+    overlap: DstOverlapPolicy,
+) -> Result<Option<DateTime<Utc>>, JobSchedulerError> {
+    // The parser walks the wall clock times of the schedule as if they were UTC
+    let mut local = after.with_timezone(timezone).naive_local();
+    if overlap != DstOverlapPolicy::FirstOccurrence {
+        // The last occurrence of a wall clock time before `after`'s can still come after it, when
+        // the clocks go back in the next day
+        let offset_now = timezone.offset_from_utc_datetime(&after.naive_utc()).fix();
+        let offset_later = timezone
+            .offset_from_utc_datetime(&(after.naive_utc() + chrono::Duration::days(1)))
+            .fix();
+        if offset_later.local_minus_utc() < offset_now.local_minus_utc() {
+            local -= chrono::Duration::seconds(
+                (offset_now.local_minus_utc() - offset_later.local_minus_utc()) as i64,
+            ) + chrono::Duration::nanoseconds(1);
+        }
+    }
+    let mut first: Option<DateTime<Utc>> = None;
+    loop {
+        let next = match parser.next_after(schedule, &Utc.from_utc_datetime(&local))? {
+            Some(next) => next.naive_utc(),
+            None => return Ok(first),
+        };
+        let occurrences = match timezone.from_local_datetime(&next) {
+            LocalResult::Single(t) => vec![t.with_timezone(&Utc)],
+            LocalResult::Ambiguous(earliest, latest) => match overlap {
+                DstOverlapPolicy::FirstOccurrence => vec![earliest.with_timezone(&Utc)],
+                DstOverlapPolicy::LastOccurrence => vec![latest.with_timezone(&Utc)],
+                DstOverlapPolicy::BothOccurrences => {
+                    vec![earliest.with_timezone(&Utc), latest.with_timezone(&Utc)]
+                }
+            },
+            LocalResult::None => match gap {
+                DstGapPolicy::ShiftByGap => vec![shifted_by_gap(timezone, &next)],
+                DstGapPolicy::SkipGap => vec![],
+                DstGapPolicy::FireAtTransition => vec![end_of_gap(timezone, &next)],
+            },
+        };
+        // Later wall clock times don't occur earlier, except in the repeated hour
+        if let (Some(first), Some(earliest)) = (first, occurrences.first()) {
+            if *earliest > first {
+                return Ok(Some(first));
+            }
+        }
+        // Skips the occurrences that already fired
+        for occurrence in occurrences.into_iter().filter(|o| o > after) {
+            if first.map_or(true, |first| occurrence < first) {
+                first = Some(occurrence);
+            }
+        }
+        local = next;
+    }
+}
+
+///
+/// A wall clock time in a gap, at the offset from before the gap
+fn shifted_by_gap<TZ: TimeZone>(timezone: &TZ, local: &NaiveDateTime) -> DateTime<Utc> {
+    let before_gap = timezone
+        .offset_from_utc_datetime(&(*local - chrono::Duration::days(1)))
+        .fix();
+    Utc.from_utc_datetime(
+        &(*local - chrono::Duration::seconds(before_gap.local_minus_utc() as i64)),
+    )
+}
+
+///
+/// The moment the clocks went forward, skipping the wall clock time `local`
+fn end_of_gap<TZ: TimeZone>(timezone: &TZ, local: &NaiveDateTime) -> DateTime<Utc> {
+    let before_gap = timezone
+        .offset_from_utc_datetime(&(*local - chrono::Duration::days(1)))
+        .fix();
+    let after_gap = timezone
+        .offset_from_utc_datetime(&(*local + chrono::Duration::days(1)))
+        .fix();
+    // Before the gap at `before`, after it at `after`, in seconds
+    let mut before =
+        (*local - chrono::Duration::seconds(after_gap.local_minus_utc() as i64)).timestamp();
+    let mut after =
+        (*local - chrono::Duration::seconds(before_gap.local_minus_utc() as i64)).timestamp();
+    while after - before > 1 {
+        let middle = before + (after - before) / 2;
+        let offset = timezone
+            .offset_from_utc_datetime(&Utc.timestamp(middle, 0).naive_utc())
+            .fix();
+        if offset == before_gap {
+            before = middle;
+        } else {
+            after = middle;
+        }
+    }
+    Utc.timestamp(after, 0)
+}
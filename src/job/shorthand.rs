@@ -0,0 +1,79 @@
+use crate::JobSchedulerError;
+use std::time::Duration;
+
+///
+/// What a schedule with the shorthands expanded comes down to
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) enum Shorthand {
+    /// A cron schedule, with seconds, for the `cron` crate
+    Cron(String),
+    /// An `@every <duration>` schedule, a repeated job
+    Every(Duration),
+}
+
+///
+/// Expand the descriptor shorthands `@yearly`, `@annually`, `@monthly`, `@weekly`, `@daily`,
+/// `@midnight`, `@hourly` and `@every <duration>`. Any other schedule is passed on as is, to be
+/// parsed as a cron schedule.
+pub(crate) fn expand(schedule: &str) -> Result<Shorthand, JobSchedulerError> {
+    let trimmed = schedule.trim();
+    if !trimmed.starts_with('@') {
+        return Ok(Shorthand::Cron(schedule.to_string()));
+    }
+    let cron = match trimmed {
+        "@yearly" | "@annually" => "0 0 0 1 1 *",
+        "@monthly" => "0 0 0 1 * *",
+        "@weekly" => "0 0 0 * * Sun",
+        "@daily" | "@midnight" => "0 0 0 * * *",
+        "@hourly" => "0 0 * * * *",
+        _ => match trimmed.strip_prefix("@every") {
+            Some(duration) if duration.starts_with(char::is_whitespace) => {
+                return parse_duration(duration.trim()).map(Shorthand::Every);
+            }
+            _ => return Err(JobSchedulerError::ParseSchedule),
+        },
+    };
+    Ok(Shorthand::Cron(cron.to_string()))
+}
+
+///
+/// Parse a duration like `30s`, `1h30m` or `500ms`. The units are `ms`, `s`, `m`, `h` and `d`.
+/// A zero duration is refused, a job can't repeat that often.
+fn parse_duration(duration: &str) -> Result<Duration, JobSchedulerError> {
+    let mut total = Duration::ZERO;
+    let mut rest = duration;
+    if rest.is_empty() {
+        return Err(JobSchedulerError::ParseSchedule);
+    }
+    while !rest.is_empty() {
+        let digits = rest
+            .find(|c: char| !c.is_ascii_digit())
+            .unwrap_or(rest.len());
+        let amount: u64 = rest[..digits]
+            .parse()
+            .map_err(|_| JobSchedulerError::ParseSchedule)?;
+        rest = &rest[digits..];
+        let unit = rest
+            .find(|c: char| c.is_ascii_digit())
+            .unwrap_or(rest.len());
+        let millis = match &rest[..unit] {
+            "ms" => 1,
+            "s" => 1000,
+            "m" => 60 * 1000,
+            "h" => 60 * 60 * 1000,
+            "d" => 24 * 60 * 60 * 1000,
+            _ => return Err(JobSchedulerError::ParseSchedule),
+        };
+        rest = &rest[unit..];
+        let part = amount
+            .checked_mul(millis)
+            .ok_or(JobSchedulerError::ParseSchedule)?;
+        total = total
+            .checked_add(Duration::from_millis(part))
+            .ok_or(JobSchedulerError::ParseSchedule)?;
+    }
+    if total.is_zero() {
+        return Err(JobSchedulerError::ParseSchedule);
+    }
+    Ok(total)
+}
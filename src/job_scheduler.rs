@@ -1,7 +1,18 @@
+use crate::clock::Clock;
 use crate::context::Context;
 use crate::error::JobSchedulerError;
+#[cfg(not(feature = "has_bytes"))]
+use crate::job::job_data::{job_stored_data::Job as JobKind, JobState, JobStoredData, JobType};
+#[cfg(feature = "has_bytes")]
+use crate::job::job_data_prost::{
+    job_stored_data::Job as JobKind, JobState, JobStoredData, JobType,
+};
 use crate::job::to_code::{JobCode, NotificationCode};
-use crate::job::{JobCreator, JobDeleter, JobLocked, JobRunner};
+use crate::job::{
+    next_after_in, next_anchored_tick, HistoryStore, JobConfig, JobCreator, JobDeleter, JobEvent,
+    JobLocked, JobMiddleware, JobRunRecord, JobRunner, JobToRunAsync, OnJobNotification,
+    RetryPolicy, RunSink, ScheduleParser, SkipReason,
+};
 use crate::notification::{NotificationCreator, NotificationDeleter, NotificationRunner};
 use crate::scheduler::Scheduler;
 use crate::simple::{
@@ -9,18 +20,35 @@ use crate::simple::{
 };
 use crate::store::{MetaDataStorage, NotificationStore};
 use chrono::{DateTime, NaiveDateTime, Utc};
+use rand_core::RngCore;
 use std::future::Future;
 use std::pin::Pin;
 use std::sync::Arc;
-#[cfg(feature = "signal")]
+#[cfg(all(feature = "signal", unix))]
 use tokio::signal::unix::SignalKind;
 use tokio::sync::RwLock;
 use tracing::{error, info};
 use uuid::Uuid;
 
+/// How often `wait_drained` looks whether the runs finished
+const DRAIN_POLL_INTERVAL: std::time::Duration = std::time::Duration::from_millis(100);
+
 pub type ShutdownNotification =
     dyn FnMut() -> Pin<Box<dyn Future<Output = ()> + Send>> + Send + Sync;
 
+pub type FallibleShutdownNotification = dyn FnMut() -> Pin<Box<dyn Future<Output = Result<(), JobSchedulerError>> + Send>>
+    + Send
+    + Sync;
+
+pub type GlobalGate = dyn FnMut() -> Pin<Box<dyn Future<Output = bool> + Send>> + Send + Sync;
+
+pub type JobPanicHandler = dyn FnMut(Uuid, String, JobsSchedulerLocked) -> Pin<Box<dyn Future<Output = ()> + Send>>
+    + Send
+    + Sync;
+
+pub type HeartbeatNotification =
+    dyn FnMut(DateTime<Utc>) -> Pin<Box<dyn Future<Output = ()> + Send>> + Send + Sync;
+
 /// The JobScheduler contains and executes the scheduled jobs.
 pub struct JobsSchedulerLocked {
     pub context: Arc<Context>,
@@ -33,6 +61,7 @@ pub struct JobsSchedulerLocked {
     pub notification_runner: Arc<RwLock<NotificationRunner>>,
     pub scheduler: Arc<RwLock<Scheduler>>,
     pub shutdown_notifier: Option<Arc<RwLock<Box<ShutdownNotification>>>>,
+    pub fallible_shutdown_notifier: Option<Arc<RwLock<Box<FallibleShutdownNotification>>>>,
 }
 
 impl Clone for JobsSchedulerLocked {
@@ -48,6 +77,7 @@ impl Clone for JobsSchedulerLocked {
             notification_runner: self.notification_runner.clone(),
             scheduler: self.scheduler.clone(),
             shutdown_notifier: self.shutdown_notifier.clone(),
+            fallible_shutdown_notifier: self.fallible_shutdown_notifier.clone(),
         }
     }
 }
@@ -59,28 +89,38 @@ impl JobsSchedulerLocked {
         job_code: Arc<RwLock<Box<dyn JobCode + Send + Sync>>>,
         notify_code: Arc<RwLock<Box<dyn NotificationCode + Send + Sync>>>,
     ) -> Result<Arc<Context>, JobSchedulerError> {
-        {
+        // The metadata and notification stores don't depend on each other, so their (possibly
+        // remote) initialisation can run concurrently.
+        let metadata_init = {
             let mut metadata_storage = metadata_storage.write().await;
-            metadata_storage.init().await?;
-        }
-        {
+            metadata_storage.init()
+        };
+        let notification_init = {
             let mut notification_storage = notification_storage.write().await;
-            notification_storage.init().await?;
-        }
+            notification_storage.init()
+        };
+        let (metadata_init, notification_init) = tokio::join!(metadata_init, notification_init);
+        metadata_init?;
+        notification_init?;
+
         let context = Context::new(
             metadata_storage,
             notification_storage,
             job_code.clone(),
             notify_code.clone(),
         );
-        {
+        let job_code_init = {
             let mut job_code = job_code.write().await;
-            job_code.init(&context).await?;
-        }
-        {
+            job_code.init(&context)
+        };
+        let notification_code_init = {
             let mut notification_code = notify_code.write().await;
-            notification_code.init(&context).await?;
-        }
+            notification_code.init(&context)
+        };
+        let (job_code_init, notification_code_init) =
+            tokio::join!(job_code_init, notification_code_init);
+        job_code_init?;
+        notification_code_init?;
         Ok(Arc::new(context))
     }
 
@@ -153,10 +193,7 @@ impl JobsSchedulerLocked {
             let mut w = self.inited.write().await;
             *w = true;
         }
-        self.clone()
-            .init_actors()
-            .await
-            .map_err(|_| JobSchedulerError::CantInit)
+        self.clone().init_actors().await
     }
 
     ///
@@ -185,8 +222,7 @@ impl JobsSchedulerLocked {
             job_code,
             notify_code,
         )
-        .await
-        .map_err(|_| JobSchedulerError::CantInit)?;
+        .await?;
 
         let val = JobsSchedulerLocked {
             context,
@@ -199,6 +235,7 @@ impl JobsSchedulerLocked {
             notification_runner: Arc::new(Default::default()),
             scheduler: Arc::new(Default::default()),
             shutdown_notifier: None,
+            fallible_shutdown_notifier: None,
         };
 
         Ok(val)
@@ -237,6 +274,7 @@ impl JobsSchedulerLocked {
             notification_runner: Arc::new(Default::default()),
             scheduler: Arc::new(Default::default()),
             shutdown_notifier: None,
+            fallible_shutdown_notifier: None,
         };
 
         Ok(val)
@@ -251,21 +289,137 @@ impl JobsSchedulerLocked {
     ///     println!("I get executed every 10 seconds!");
     /// })).await;
     /// ```
-    pub async fn add(&self, job: JobLocked) -> Result<Uuid, JobSchedulerError> {
+    pub async fn add(&self, mut job: JobLocked) -> Result<Uuid, JobSchedulerError> {
+        let guid = job.guid();
+        if !self.inited().await {
+            info!("Uninited");
+            let mut s = self.clone();
+            s.init().await?;
+        }
+        if self.is_draining().await {
+            error!("Scheduler is draining, not adding jobs");
+            return Err(JobSchedulerError::Draining);
+        }
+
+        self.first_tick(&mut job).await?;
+        {
+            // Checked and stored under one guard, so two adds of the same job can't both pass
+            let mut metadata_storage = self.context.metadata_storage.write().await;
+            if metadata_storage.get(guid).await?.is_some() {
+                error!("Job {:?} has already been added", guid);
+                return Err(JobSchedulerError::DuplicateId);
+            }
+            metadata_storage.add_or_update(job.job_data()?).await?;
+        }
+
+        // Hands the code to the runner, the metadata is written again as it is
+        let context = self.context.clone();
+        JobCreator::add(&context, job).await?;
+        info!("Job creator created");
+        self.wake_scheduler().await;
+
+        Ok(guid)
+    }
+
+    /// Add a job to the `JobScheduler`, replacing the job with the same GUID if there is one.
+    /// Use this instead of `add` when re-adding jobs that have user supplied or stored GUIDs.
+    ///
+    /// Replacing a job is a single write of its metadata and swaps in the new code, there's no
+    /// moment the job is absent. Prefer it over a `remove` followed by an `add`, where a tick in
+    /// between would miss the job if it was due. The replacement's ticks and count are the ones
+    /// stored, use `Job::from_job_data` with the existing metadata to keep them.
+    ///
+    /// A stored next tick of a cron job that isn't one of its schedule's ticks, say one carried
+    /// over from before a restart, is moved to the schedule's first tick at or after it. A
+    /// `0 * * * * *` job re-added at :37 fires at the next :00, and every tick after that is
+    /// taken from the schedule too, never from the last tick plus an interval, so they don't
+    /// drift. Jobs with jitter keep their stored tick.
+    ///
+    /// ```rust,ignore
+    /// use tokio_cron_scheduler::{Job, JobScheduler, JobToRun};
+    /// let mut sched = JobScheduler::new();
+    /// let job = Job::from_job_data(stored_data, |_uuid, _lock| {
+    ///     println!("I get executed every 10 seconds!");
+    /// })?;
+    /// sched.add_or_update(job).await?;
+    /// ```
+    pub async fn add_or_update(&self, mut job: JobLocked) -> Result<Uuid, JobSchedulerError> {
         let guid = job.guid();
         if !self.inited().await {
             info!("Uninited");
             let mut s = self.clone();
             s.init().await?;
         }
+        if self.is_draining().await {
+            error!("Scheduler is draining, not adding jobs");
+            return Err(JobSchedulerError::Draining);
+        }
+        self.align_next_tick(&mut job).await?;
 
         let context = self.context.clone();
         JobCreator::add(&context, job).await?;
         info!("Job creator created");
+        self.lift_quarantine(&guid).await;
+        self.wake_scheduler().await;
 
         Ok(guid)
     }
 
+    /// Add many jobs to the `JobScheduler` in one call, returning their GUIDs in the order of the
+    /// jobs. Their metadata is stored in a single write, which saves a round trip per job when
+    /// bootstrapping hundreds of jobs against a database.
+    ///
+    /// The SQL stores write the jobs in one transaction: either all of them are added, or, on an
+    /// error, none are. The other stores write the jobs one by one and stop at the first error,
+    /// leaving the jobs before it added. A job whose GUID is stored already, or that is in the
+    /// batch twice, fails the whole call with `DuplicateId` before anything is written.
+    ///
+    /// ```rust,ignore
+    /// let jobs = reports
+    ///     .iter()
+    ///     .map(|report| Job::new_async(report.schedule(), report.run()))
+    ///     .collect::<Result<Vec<_>, _>>()?;
+    /// let job_ids = sched.add_all(jobs).await?;
+    /// ```
+    pub async fn add_all(&self, mut jobs: Vec<JobLocked>) -> Result<Vec<Uuid>, JobSchedulerError> {
+        if !self.inited().await {
+            info!("Uninited");
+            let mut s = self.clone();
+            s.init().await?;
+        }
+        if self.is_draining().await {
+            error!("Scheduler is draining, not adding jobs");
+            return Err(JobSchedulerError::Draining);
+        }
+
+        let mut guids = {
+            let mut metadata_storage = self.context.metadata_storage.write().await;
+            metadata_storage
+                .list_jobs()
+                .await?
+                .iter()
+                .filter_map(|n| n.id.as_ref().map(|id| id.into()))
+                .collect::<std::collections::HashSet<Uuid>>()
+        };
+        for job in jobs.iter() {
+            let guid = job.guid();
+            if !guids.insert(guid) {
+                error!("Job {:?} has already been added", guid);
+                return Err(JobSchedulerError::DuplicateId);
+            }
+        }
+        for job in jobs.iter_mut() {
+            self.first_tick(job).await?;
+        }
+
+        let context = self.context.clone();
+        let guids = JobCreator::add_all(&context, jobs).await?;
+        info!("Job creator created {} jobs", guids.len());
+        self.wake_scheduler().await;
+
+        Ok(guids)
+    }
+
     /// Remove a job from the `JobScheduler`
     ///
     /// ```rust,ignore
@@ -286,12 +440,53 @@ impl JobsSchedulerLocked {
         }
 
         let context = self.context();
-        JobDeleter::remove(&context, to_be_removed).await
+        JobDeleter::remove(&context, to_be_removed).await?;
+        self.lift_quarantine(to_be_removed).await;
+        self.wake_scheduler().await;
+        Ok(())
+    }
+
+    /// `remove_all` removes every job, with its notifications and code. The jobs leave the
+    /// metadata storage in one go, see `MetaDataStorage::delete_all`, so the scheduler never sees
+    /// some of them removed and others not. Activations of the jobs that are still queued are
+    /// dropped; runs that already started aren't cancelled.
+    ///
+    /// ```rust,ignore
+    /// sched.remove_all().await?;
+    /// for job in jobs_from_config()? {
+    ///     sched.add(job).await?;
+    /// }
+    /// ```
+    pub async fn remove_all(&self) -> Result<(), JobSchedulerError> {
+        if !self.inited().await {
+            let mut s = self.clone();
+            s.init().await?;
+        }
+
+        let ids = {
+            let mut w = self.context.metadata_storage.write().await;
+            let ids = w
+                .list_jobs()
+                .await?
+                .iter()
+                .filter_map(|n| n.id.as_ref().map(|id| id.into()))
+                .collect::<Vec<Uuid>>();
+            w.delete_all(ids.clone()).await?;
+            ids
+        };
+        for id in ids.iter() {
+            // Drops the notifications and the code of the job, like a removal by the deleter
+            if let Err(e) = self.context.job_deleted_tx.send(Ok(*id)) {
+                error!("Error sending deletion {:?}", e);
+            }
+            self.lift_quarantine(id).await;
+        }
+        self.wake_scheduler().await;
+        Ok(())
     }
 
-    /// The `start` spawns a Tokio task where it loops. Every 500ms it
-    /// runs the tick method to increment any
-    /// any pending jobs.
+    /// The `start` spawns a Tokio task where it loops. It sleeps until the earliest next tick of
+    /// the jobs, or the tick interval if that is sooner, and then runs any pending jobs.
     ///
     /// ```rust,ignore
     /// if let Err(e) = sched.start().await {
@@ -340,6 +535,29 @@ impl JobsSchedulerLocked {
         }
     }
 
+    /// `next_job` returns the GUID and the next tick of the job that runs first, leaving out paused
+    /// jobs. A job whose tick is due but didn't run yet counts as the first.
+    ///
+    /// ```rust,ignore
+    /// if let Some((job_id, next_tick)) = sched.next_job().await? {
+    ///     println!("Next: {:?} at {:?}", job_id, next_tick);
+    /// }
+    /// ```
+    pub async fn next_job(&self) -> Result<Option<(Uuid, DateTime<Utc>)>, JobSchedulerError> {
+        if !self.inited().await {
+            let mut s = self.clone();
+            s.init().await?;
+        }
+        let next_job = {
+            let mut w = self.context.metadata_storage.write().await;
+            w.next_job().await?
+        };
+        Ok(next_job.and_then(|n| {
+            let id: Uuid = n.id.as_ref()?.into();
+            Some((id, n.next_tick_utc()?))
+        }))
+    }
+
     /// `next_tick_for_job` returns the date/time for when the next tick will
     /// be for a job
     pub async fn next_tick_for_job(
@@ -359,64 +577,1576 @@ impl JobsSchedulerLocked {
         })
     }
 
+    /// `effective_next_run` returns when a job will actually run next, unlike `next_tick_for_job`
+    /// which returns its next tick as stored. `None` when the job won't run unless something
+    /// changes: while it is paused with `pause`, or all jobs are with `pause_all`, when its active
+    /// window ends before its next tick, see `with_active_window`, when it made its `max_runs`, or
+    /// when it's quarantined. A tick before the active window starts is moved to the start.
+    /// Returns `JobNotFound` for unknown jobs. The global gate is only asked when the job is due,
+    /// so it isn't accounted for.
     ///
-    /// Shut the scheduler down
-    pub async fn shutdown(&mut self) -> Result<(), JobSchedulerError> {
-        let mut notify = None;
-        std::mem::swap(&mut self.shutdown_notifier, &mut notify);
+    /// ```rust,ignore
+    /// match sched.effective_next_run(&job_id).await? {
+    ///     Some(at) => println!("Runs next at {}", at),
+    ///     None => println!("Won't run until resumed"),
+    /// }
+    /// ```
+    pub async fn effective_next_run(
+        &self,
+        job_id: &Uuid,
+    ) -> Result<Option<DateTime<Utc>>, JobSchedulerError> {
+        let data = self
+            .job_metadata(*job_id)
+            .await?
+            .ok_or(JobSchedulerError::JobNotFound)?;
+        if data.stopped || matches!(data.max_runs, Some(max) if data.count >= max) {
+            return Ok(None);
+        }
+        {
+            let scheduler = self.scheduler.read().await;
+            if scheduler.paused.load(std::sync::atomic::Ordering::Relaxed) {
+                return Ok(None);
+            }
+            let quarantined = scheduler.quarantined.read().await;
+            if quarantined.contains(job_id) {
+                return Ok(None);
+            }
+        }
+        let next_tick = match (data.next_tick_utc(), data.active_from_utc()) {
+            (Some(next_tick), Some(start)) if next_tick < start => start,
+            (Some(next_tick), _) => next_tick,
+            (None, _) => return Ok(None),
+        };
+        Ok(match data.expires_at_utc() {
+            Some(end) if next_tick >= end => None,
+            _ => Some(next_tick),
+        })
+    }
 
-        let mut scheduler = self.scheduler.write().await;
-        scheduler.shutdown().await;
+    /// `next_ticks_all` returns the next tick of every job, like `next_tick_for_job` does for one,
+    /// in a single read of the metadata storage instead of one per job. `None` for jobs that won't
+    /// fire again. The scheduler doesn't have to be running.
+    ///
+    /// ```rust,ignore
+    /// for (job_id, next_tick) in sched.next_ticks_all().await? {
+    ///     println!("{:?} next at {:?}", job_id, next_tick);
+    /// }
+    /// ```
+    pub async fn next_ticks_all(
+        &self,
+    ) -> Result<Vec<(Uuid, Option<DateTime<Utc>>)>, JobSchedulerError> {
+        if !self.inited().await {
+            let mut s = self.clone();
+            s.init().await?;
+        }
+        let jobs = {
+            let mut w = self.context.metadata_storage.write().await;
+            w.list_jobs().await?
+        };
+        Ok(jobs
+            .iter()
+            .filter_map(|n| {
+                let id: Uuid = n.id.as_ref()?.into();
+                Some((id, n.next_tick_utc()))
+            })
+            .collect())
+    }
 
-        if let Some(notify) = notify {
-            let mut notify = notify.write().await;
-            notify().await;
+    /// `export_jobs` returns the configuration of every job, all that's stored of them but their
+    /// code, for a backup or to move them to another environment with `import_jobs`. With the
+    /// `serde` feature the configurations can be serialized.
+    ///
+    /// ```rust,ignore
+    /// let configs = sched.export_jobs().await?;
+    /// std::fs::write("jobs.json", serde_json::to_vec(&configs)?)?;
+    /// ```
+    pub async fn export_jobs(&self) -> Result<Vec<JobConfig>, JobSchedulerError> {
+        if !self.inited().await {
+            let mut s = self.clone();
+            s.init().await?;
         }
-        Ok(())
+        let mut w = self.context.metadata_storage.write().await;
+        let jobs = w.list_jobs().await?;
+        let mut configs = vec![];
+        for id in jobs.iter().filter_map(|n| n.id.as_ref()) {
+            if let Some(data) = w.get(id.into()).await? {
+                configs.push(JobConfig::from(data));
+            }
+        }
+        Ok(configs)
     }
 
+    /// `import_jobs` re-creates the jobs of configurations from `export_jobs`, with the code
+    /// `code` gives for each job's GUID. The jobs keep their GUIDs, ticks and run counts, and
+    /// replace the jobs with the same GUID like `add_or_update` does. Returns the GUIDs in the
+    /// order of the configurations. A configuration that doesn't make a job fails the call, the
+    /// jobs before it are imported.
     ///
-    /// Wait for a signal to shut the runtime down with
-    #[cfg(feature = "signal")]
-    pub fn shutdown_on_signal(&self, signal: SignalKind) {
-        let mut l = self.clone();
-        tokio::spawn(async move {
-            if let Some(_k) = tokio::signal::unix::signal(signal)
-                .expect("Can't wait for signal")
-                .recv()
-                .await
+    /// ```rust,ignore
+    /// let configs: Vec<JobConfig> = serde_json::from_slice(&std::fs::read("jobs.json")?)?;
+    /// sched.import_jobs(configs, |job_id| Box::new(move |_uuid, _l| Box::pin(async move {
+    ///     println!("{:?} was imported", job_id);
+    /// }))).await?;
+    /// ```
+    pub async fn import_jobs<F>(
+        &self,
+        configs: Vec<JobConfig>,
+        mut code: F,
+    ) -> Result<Vec<Uuid>, JobSchedulerError>
+    where
+        F: FnMut(Uuid) -> Box<JobToRunAsync>,
+    {
+        let mut guids = vec![];
+        for config in configs {
+            let guid = config.guid()?;
+            let job = JobLocked::from_job_data_async(config.into_job_data()?, code(guid))?;
+            guids.push(self.add_or_update(job).await?);
+        }
+        Ok(guids)
+    }
+
+    /// `job_metadata` returns everything the metadata storage holds on a job, like its schedule,
+    /// next tick, run count and tags. `None` for unknown jobs. The scheduler doesn't have to be
+    /// running.
+    ///
+    /// ```rust,ignore
+    /// if let Some(data) = sched.job_metadata(job_id).await? {
+    ///     println!("{:?} ran {} times", data.schedule_str(), data.count);
+    /// }
+    /// ```
+    pub async fn job_metadata(
+        &self,
+        job_id: Uuid,
+    ) -> Result<Option<JobStoredData>, JobSchedulerError> {
+        if !self.inited().await {
+            let mut s = self.clone();
+            s.init().await?;
+        }
+        let mut r = self.context.metadata_storage.write().await;
+        r.get(job_id).await
+    }
+
+    /// `run_count` returns how many times a job has fired. The count is kept in the metadata
+    /// storage, so it survives restarts with a persistent store. Returns `JobNotFound` for unknown
+    /// jobs.
+    ///
+    /// ```rust,ignore
+    /// println!("Ran {} times", sched.run_count(job_id).await?);
+    /// ```
+    pub async fn run_count(&self, job_id: Uuid) -> Result<u64, JobSchedulerError> {
+        self.job_metadata(job_id)
+            .await?
+            .map(|data| data.count as u64)
+            .ok_or(JobSchedulerError::JobNotFound)
+    }
+
+    /// `upcoming_ticks_for_job` returns up to `count` upcoming fire times of a job. Cron jobs are
+    /// projected from their schedule from now on, in the job's timezone, repeated jobs from their
+    /// next tick on and one shot jobs give at most their single tick. The scheduler doesn't have
+    /// to be running. Returns `JobNotFound` for unknown jobs.
+    ///
+    /// ```rust,ignore
+    /// for tick in sched.upcoming_ticks_for_job(job_id, 5).await? {
+    ///     println!("Runs at {:?}", tick);
+    /// }
+    /// ```
+    pub async fn upcoming_ticks_for_job(
+        &self,
+        job_id: Uuid,
+        count: usize,
+    ) -> Result<Vec<DateTime<Utc>>, JobSchedulerError> {
+        if !self.inited().await {
+            let mut s = self.clone();
+            s.init().await?;
+        }
+        let data = {
+            let mut r = self.context.metadata_storage.write().await;
+            r.get(job_id).await?.ok_or(JobSchedulerError::JobNotFound)?
+        };
+        let mut ticks = Vec::with_capacity(count);
+        if data.schedule_str().is_some() {
+            let scheduler = self.scheduler.read().await;
+            let parser = scheduler.schedule_parser.read().await;
+            let mut after = scheduler.clock.read().await.now();
+            while ticks.len() < count {
+                match data.next_schedule_tick(&**parser, &after) {
+                    Some(Ok(Some(tick))) => {
+                        ticks.push(tick);
+                        after = tick;
+                    }
+                    Some(Err(e)) => return Err(e),
+                    _ => break,
+                }
+            }
+        } else if let Some(first) = data.next_tick_utc() {
+            match data
+                .repeated_every()
+                .filter(|_| data.job_type == JobType::Repeated as i32)
             {
-                l.shutdown().await.expect("Problem shutting down");
+                Some(every) => {
+                    let every = chrono::Duration::seconds(every as i64);
+                    ticks.extend((0..count as i32).map(|i| first + every * i));
+                }
+                None => ticks.extend(Some(first).into_iter().take(count)),
             }
-        });
+        }
+        Ok(ticks)
     }
 
+    /// `simulate` walks the schedules of the stored jobs from `from` up to and including `to` and
+    /// returns when each job would fire, ordered by time. Nothing is run and no notifications are
+    /// sent, and the scheduler doesn't have to be running. Cron jobs follow their schedule in
+    /// their timezone, repeated jobs their anchor or next tick, and one shot jobs fire at most
+    /// once. Paused jobs are left out, limited jobs stop after their remaining runs, expiring jobs
+    /// stop at their deadline, and the jitter of jobs created `with_jitter` isn't applied.
     ///
-    /// Wait for a signal to shut the runtime down with
-    #[cfg(feature = "signal")]
-    pub fn shutdown_on_ctrl_c(&self) {
-        let mut l = self.clone();
-        tokio::spawn(async move {
-            tokio::signal::ctrl_c()
-                .await
-                .expect("Could not await ctrl-c");
+    /// ```rust,ignore
+    /// let plan = sched.simulate(Utc::now(), Utc::now() + chrono::Duration::days(1)).await?;
+    /// for (tick, job_id) in plan {
+    ///     println!("{:?} fires at {:?}", job_id, tick);
+    /// }
+    /// ```
+    pub async fn simulate(
+        &self,
+        from: DateTime<Utc>,
+        to: DateTime<Utc>,
+    ) -> Result<Vec<(DateTime<Utc>, Uuid)>, JobSchedulerError> {
+        if !self.inited().await {
+            let mut s = self.clone();
+            s.init().await?;
+        }
+        let jobs = {
+            let mut w = self.context.metadata_storage.write().await;
+            let ids = w.list_jobs().await?;
+            let mut jobs = Vec::with_capacity(ids.len());
+            for id in ids.iter().filter_map(|n| n.id.as_ref()) {
+                if let Some(data) = w.get(id.into()).await? {
+                    jobs.push(data);
+                }
+            }
+            jobs
+        };
+        let scheduler = self.scheduler.read().await;
+        let parser = scheduler.schedule_parser.read().await;
+        let mut plan = vec![];
+        for data in jobs.iter().filter(|d| !d.stopped) {
+            let id: Uuid = match data.id.as_ref() {
+                Some(id) => id.into(),
+                None => continue,
+            };
+            let mut runs_left = data
+                .max_runs
+                .map(|max| max.saturating_sub(data.count) as usize)
+                .unwrap_or(usize::MAX);
+            let expires_at = data.expires_at_utc();
+            let mut push = |tick: DateTime<Utc>| {
+                if runs_left == 0 || matches!(expires_at, Some(at) if tick >= at) {
+                    return false;
+                }
+                runs_left -= 1;
+                plan.push((tick, id));
+                true
+            };
+            match JobType::from_i32(data.job_type) {
+                Some(JobType::Cron) => {
+                    // The schedule gives the ticks strictly after, so a tick at `from` counts too
+                    let mut after = from - chrono::Duration::nanoseconds(1);
+                    while let Some(tick) = data.next_schedule_tick(&**parser, &after).transpose()? {
+                        match tick {
+                            Some(tick) if tick <= to && push(tick) => after = tick,
+                            _ => break,
+                        }
+                    }
+                }
+                Some(JobType::Repeated) => {
+                    let every = match data.repeated_every().filter(|every| *every > 0) {
+                        Some(every) => chrono::Duration::seconds(every as i64),
+                        None => continue,
+                    };
+                    let first = match data.anchor() {
+                        Some(anchor) if anchor >= from => Some(anchor),
+                        Some(anchor) => next_anchored_tick(
+                            anchor,
+                            every.num_seconds() as u64,
+                            from - chrono::Duration::nanoseconds(1),
+                        ),
+                        None => data.next_tick_utc().map(|first| {
+                            if first >= from {
+                                first
+                            } else {
+                                let every_ms = every.num_milliseconds();
+                                let behind = (from - first).num_milliseconds() + every_ms - 1;
+                                first + every * (behind / every_ms) as i32
+                            }
+                        }),
+                    };
+                    let mut tick = first;
+                    while let Some(t) = tick.filter(|t| *t <= to) {
+                        if !push(t) {
+                            break;
+                        }
+                        tick = t.checked_add_signed(every);
+                    }
+                }
+                Some(JobType::OneShot) => {
+                    if let Some(tick) = data.next_tick_utc().filter(|t| *t >= from && *t <= to) {
+                        push(tick);
+                    }
+                }
+                None => {}
+            }
+        }
+        plan.sort();
+        Ok(plan)
+    }
 
-            if let Err(err) = l.shutdown().await {
-                error!("{:?}", err);
+    /// `list_jobs` lists every job in the metadata storage with its type and next tick, whether
+    /// it is due or not. With a persistent storage this includes the jobs added before a restart.
+    ///
+    /// ```rust,ignore
+    /// for (job_id, job_type, next_tick) in sched.list_jobs().await? {
+    ///     println!("{:?} {:?} next at {:?}", job_id, job_type, next_tick);
+    /// }
+    /// ```
+    pub async fn list_jobs(
+        &self,
+    ) -> Result<Vec<(Uuid, JobType, Option<DateTime<Utc>>)>, JobSchedulerError> {
+        if !self.inited().await {
+            let mut s = self.clone();
+            s.init().await?;
+        }
+        let jobs = {
+            let mut w = self.context.metadata_storage.write().await;
+            w.list_jobs().await?
+        };
+        Ok(jobs
+            .iter()
+            .filter_map(|n| {
+                let id: Uuid = n.id.as_ref()?.into();
+                let job_type = JobType::from_i32(n.job_type)?;
+                Some((id, job_type, n.next_tick_utc()))
+            })
+            .collect())
+    }
+
+    /// `count_jobs` returns the number of jobs in the metadata storage, without loading them.
+    ///
+    /// ```rust,ignore
+    /// let jobs = sched.count_jobs().await?;
+    /// ```
+    pub async fn count_jobs(&self) -> Result<usize, JobSchedulerError> {
+        if !self.inited().await {
+            let mut s = self.clone();
+            s.init().await?;
+        }
+        let mut w = self.context.metadata_storage.write().await;
+        w.count().await
+    }
+
+    /// `orphaned_jobs` lists the stored jobs that have no code to run. A persistent metadata
+    /// storage keeps the jobs over a restart, but not their code, so until the code is given
+    /// again, with `Job::from_job_data` and `add_or_update`, these jobs don't run.
+    ///
+    /// ```rust,ignore
+    /// for job_id in sched.orphaned_jobs().await? {
+    ///     let data = sched.job_metadata(job_id).await?.unwrap();
+    ///     sched.add_or_update(Job::from_job_data(data, |_uuid, _l| {})?).await?;
+    /// }
+    /// ```
+    pub async fn orphaned_jobs(&self) -> Result<Vec<Uuid>, JobSchedulerError> {
+        if !self.inited().await {
+            let mut s = self.clone();
+            s.init().await?;
+        }
+        let jobs = {
+            let mut w = self.context.metadata_storage.write().await;
+            w.list_jobs().await?
+        };
+        let mut orphaned = vec![];
+        for id in jobs.iter().filter_map(|n| n.id.as_ref()) {
+            let id: Uuid = id.into();
+            let code = {
+                let mut w = self.context.job_code.write().await;
+                w.get(id).await?
+            };
+            if code.is_none() {
+                orphaned.push(id);
             }
-        });
+        }
+        Ok(orphaned)
     }
 
+    /// `jobs_by_tag` lists the ids of the jobs tagged with `tag`, see `Job::with_tags`.
     ///
-    /// Code that is run after the shutdown was run
-    pub fn set_shutdown_handler(&mut self, job: Box<ShutdownNotification>) {
-        self.shutdown_notifier = Some(Arc::new(RwLock::new(job)));
+    /// ```rust,ignore
+    /// let billing_jobs = sched.jobs_by_tag("billing").await?;
+    /// ```
+    pub async fn jobs_by_tag(&self, tag: &str) -> Result<Vec<Uuid>, JobSchedulerError> {
+        if !self.inited().await {
+            let mut s = self.clone();
+            s.init().await?;
+        }
+        let mut w = self.context.metadata_storage.write().await;
+        let jobs = w.list_jobs().await?;
+        let mut tagged = vec![];
+        for id in jobs.iter().filter_map(|n| n.id.as_ref()) {
+            let id: Uuid = id.into();
+            if let Some(data) = w.get(id).await? {
+                if data.tags.iter().any(|t| t == tag) {
+                    tagged.push(id);
+                }
+            }
+        }
+        Ok(tagged)
     }
 
+    /// `remove_by_tag` removes every job tagged with `tag` and returns their ids.
     ///
-    /// Remove the shutdown handler
-    pub fn remove_shutdown_handler(&mut self) {
-        self.shutdown_notifier = None;
+    /// ```rust,ignore
+    /// let removed = sched.remove_by_tag("reports").await?;
+    /// ```
+    pub async fn remove_by_tag(&self, tag: &str) -> Result<Vec<Uuid>, JobSchedulerError> {
+        let ids = self.jobs_by_tag(tag).await?;
+        for id in ids.iter() {
+            self.remove(id).await?;
+        }
+        Ok(ids)
+    }
+
+    /// `overdue_jobs` lists the jobs whose next tick lies before `now` (or the clock's time) and
+    /// that haven't run for it yet, ordered by how long they're overdue. Right after an outage
+    /// this is the backlog the scheduler still has to work through. Note that a job becomes
+    /// overdue for up to the scheduler's tick interval right before it runs.
+    pub async fn overdue_jobs(
+        &self,
+        now: Option<DateTime<Utc>>,
+    ) -> Result<Vec<(Uuid, DateTime<Utc>)>, JobSchedulerError> {
+        if !self.inited().await {
+            let mut s = self.clone();
+            s.init().await?;
+        }
+        let now = match now {
+            Some(now) => now,
+            None => self.now().await,
+        };
+        let next_ticks = {
+            let mut w = self.context.metadata_storage.write().await;
+            w.list_next_ticks().await?
+        };
+        let mut overdue = next_ticks
+            .iter()
+            .filter_map(|n| {
+                let id: Uuid = n.id.as_ref()?.into();
+                let next_tick = n.next_tick_utc()?;
+                let ran = matches!(n.last_tick_utc(), Some(last_tick) if last_tick >= next_tick);
+                if next_tick < now && !ran {
+                    Some((id, next_tick))
+                } else {
+                    None
+                }
+            })
+            .collect::<Vec<_>>();
+        overdue.sort_by_key(|(_, next_tick)| *next_tick);
+        Ok(overdue)
+    }
+
+    /// `quarantined_jobs` lists the jobs whose stored schedule couldn't be parsed, or doesn't fire
+    /// within the horizon of the schedule parser. They are left in the metadata storage as they
+    /// are but aren't run anymore. Replacing such a job using
+    /// `add_or_update`, or removing it, lifts its quarantine.
+    pub async fn quarantined_jobs(&self) -> Vec<Uuid> {
+        let scheduler = self.scheduler.read().await;
+        let quarantined = scheduler.quarantined.read().await;
+        quarantined.clone()
+    }
+
+    async fn lift_quarantine(&self, job_id: &Uuid) {
+        let scheduler = self.scheduler.read().await;
+        let mut quarantined = scheduler.quarantined.write().await;
+        quarantined.retain(|id| id != job_id);
+    }
+
+    /// `reset_stats` clears a job's run count, last tick and run history, as if it never ran. The
+    /// schedule and the next tick are left as they are. Returns `JobNotFound` for unknown jobs.
+    pub async fn reset_stats(&self, job_id: &Uuid) -> Result<(), JobSchedulerError> {
+        if !self.inited().await {
+            let mut s = self.clone();
+            s.init().await?;
+        }
+        {
+            let mut w = self.context.metadata_storage.write().await;
+            let mut data = w
+                .get(*job_id)
+                .await?
+                .ok_or(JobSchedulerError::JobNotFound)?;
+            data.count = 0;
+            data.last_tick = None;
+            data.ran = false;
+            w.add_or_update(data).await?;
+        }
+        let cleared = {
+            let runner = self.job_runner.read().await;
+            let r = runner.history_store.read().await;
+            r.clear(*job_id)
+        };
+        cleared.await
+    }
+
+    /// `update_job_schedule` replaces the schedule of a cron job and recomputes its next tick from
+    /// now, in the job's timezone if it has one. The job keeps its GUID, notifications and run
+    /// count. An invalid schedule returns `ParseSchedule`, and one that doesn't fire within the
+    /// horizon of the schedule parser `ScheduleNeverFires`; both leave the job as it was.
+    ///
+    /// ```rust,ignore
+    /// sched.update_job_schedule(job_id, "0 */5 * * * *").await?;
+    /// ```
+    pub async fn update_job_schedule(
+        &self,
+        job_id: Uuid,
+        schedule: &str,
+    ) -> Result<(), JobSchedulerError> {
+        if !self.inited().await {
+            let mut s = self.clone();
+            s.init().await?;
+        }
+        let (timezone, dst_gap, dst_overlap) = {
+            let mut r = self.context.metadata_storage.write().await;
+            let data = r.get(job_id).await?.ok_or(JobSchedulerError::JobNotFound)?;
+            (data.timezone(), data.dst_gap(), data.dst_overlap())
+        };
+        let next_tick = {
+            let scheduler = self.scheduler.read().await;
+            let parser = scheduler.schedule_parser.read().await;
+            let now = scheduler.clock.read().await.now();
+            match timezone {
+                Some(tz) => next_after_in(&**parser, schedule, &tz, &now, dst_gap, dst_overlap)?,
+                None => parser.next_after(schedule, &now)?,
+            }
+        };
+        {
+            let mut w = self.context.metadata_storage.write().await;
+            let mut data = w.get(job_id).await?.ok_or(JobSchedulerError::JobNotFound)?;
+            match data.job.as_mut() {
+                Some(JobKind::CronJob(cron_job)) => {
+                    cron_job.schedule = schedule.to_string();
+                }
+                _ => {
+                    error!(
+                        "Job {:?} is not a cron job, can't update its schedule",
+                        job_id
+                    );
+                    return Err(JobSchedulerError::UpdateJobData);
+                }
+            }
+            data.set_next_tick(next_tick);
+            w.add_or_update(data).await?;
+        }
+        self.lift_quarantine(&job_id).await;
+        self.wake_scheduler().await;
+        Ok(())
+    }
+
+    /// `update_retry_policy` replaces how a job's failed runs are retried, see `set_retries`,
+    /// taking effect from the job's next run; a run going on keeps the policy it started with.
+    /// The job keeps its GUID, ticks and run count. A policy without attempts returns
+    /// `InvalidRetryPolicy` and an unknown job `JobNotFound`, both leave the job as it was.
+    ///
+    /// ```rust,ignore
+    /// sched.update_retry_policy(&job_id, RetryPolicy::new(5, Duration::from_secs(30))).await?;
+    /// ```
+    pub async fn update_retry_policy(
+        &self,
+        job_id: &Uuid,
+        policy: RetryPolicy,
+    ) -> Result<(), JobSchedulerError> {
+        policy.validate()?;
+        if !self.inited().await {
+            let mut s = self.clone();
+            s.init().await?;
+        }
+        let mut w = self.context.metadata_storage.write().await;
+        let mut data = w
+            .get(*job_id)
+            .await?
+            .ok_or(JobSchedulerError::JobNotFound)?;
+        data.retries = policy.retries();
+        data.retry_backoff_ms = policy.backoff.as_millis() as u64;
+        w.add_or_update(data).await
+    }
+
+    /// `recompute_all_ticks` recomputes the next tick of every stored job from now, as if the jobs
+    /// were just added. Cron jobs get the next tick of their schedule, repeated jobs the next
+    /// tick of their anchor or one interval from now. One shot jobs keep their tick. Use it after
+    /// restoring a backup whose ticks all lie in the past, so the jobs don't all run at once. The
+    /// GUIDs, run counts and last ticks are left as they are.
+    ///
+    /// ```rust,ignore
+    /// sched.recompute_all_ticks().await?;
+    /// ```
+    pub async fn recompute_all_ticks(&self) -> Result<(), JobSchedulerError> {
+        if !self.inited().await {
+            let mut s = self.clone();
+            s.init().await?;
+        }
+        let now = self.now().await;
+        {
+            let mut w = self.context.metadata_storage.write().await;
+            let scheduler = self.scheduler.read().await;
+            let parser = scheduler.schedule_parser.read().await;
+            let jobs = w.list_jobs().await?;
+            for id in jobs.iter().filter_map(|n| n.id.as_ref()) {
+                let id: Uuid = id.into();
+                let mut data = match w.get(id).await? {
+                    Some(data) => data,
+                    None => continue,
+                };
+                let next_tick = match JobType::from_i32(data.job_type) {
+                    Some(JobType::Cron) => match data.next_schedule_tick(&**parser, &now) {
+                        Some(Ok(next_tick)) => {
+                            next_tick.map(|t| scheduler.jitter_rng.jitter(t, data.jitter_ms))
+                        }
+                        Some(Err(e)) => {
+                            error!("Not recomputing the tick of job {:?} {:?}", id, e);
+                            continue;
+                        }
+                        None => None,
+                    },
+                    Some(JobType::Repeated) => {
+                        data.repeated_every().and_then(|every| match data.anchor() {
+                            Some(anchor) => next_anchored_tick(anchor, every, now),
+                            None => now.checked_add_signed(chrono::Duration::seconds(every as i64)),
+                        })
+                    }
+                    _ => continue,
+                };
+                data.set_next_tick(next_tick);
+                w.add_or_update(data).await?;
+            }
+        }
+        self.wake_scheduler().await;
+        Ok(())
+    }
+
+    /// `run_job_now` runs a job right away, outside of its schedule, with the same `Started` and
+    /// `Done` notifications as a scheduled run. The job's next tick stays as it was. Paused jobs
+    /// and one shot jobs whose tick passed run too, as long as they are still stored; one shot
+    /// jobs are removed shortly after they fired. The run doesn't count towards the job's
+    /// `max_runs`, so it neither uses one up nor removes a limited job. Returns `JobNotFound` for
+    /// unknown jobs.
+    ///
+    /// ```rust,ignore
+    /// sched.run_job_now(job_id).await?;
+    /// ```
+    pub async fn run_job_now(&self, job_id: Uuid) -> Result<(), JobSchedulerError> {
+        if !self.inited().await {
+            let mut s = self.clone();
+            s.init().await?;
+        }
+        let data = {
+            let mut r = self.context.metadata_storage.write().await;
+            r.get(job_id).await?
+        };
+        if data.is_none() {
+            return Err(JobSchedulerError::JobNotFound);
+        }
+        self.context.job_run_now_tx.send(job_id).map_err(|e| {
+            error!("Error sending job activation {:?}", e);
+            JobSchedulerError::JobTick
+        })?;
+        Ok(())
+    }
+
+    /// `pause` keeps a job from running until it is resumed. Due ticks of a paused job are
+    /// skipped, with a `Skipped` notification, and its next tick keeps advancing. A paused one shot
+    /// job waits and runs once it is resumed. Returns `JobNotFound` for unknown jobs.
+    ///
+    /// ```rust,ignore
+    /// sched.pause(&job_id).await?;
+    /// // ...
+    /// sched.resume(&job_id).await?;
+    /// ```
+    pub async fn pause(&self, job_id: &Uuid) -> Result<(), JobSchedulerError> {
+        self.set_stopped(job_id, true).await
+    }
+
+    /// `resume` lets a job paused with `pause` run again from its next tick on.
+    pub async fn resume(&self, job_id: &Uuid) -> Result<(), JobSchedulerError> {
+        self.set_stopped(job_id, false).await
+    }
+
+    /// `boost_priority` dispatches a job with `priority` instead of its own priority, see
+    /// `with_priority`, until the scheduler's clock reaches `until`. After that it reverts to its
+    /// own priority by itself. The boost is stored with the job, so it lasts over a restart with
+    /// a persistent store; boosting again replaces it, an `until` in the past ends it. Returns
+    /// `JobNotFound` for unknown jobs.
+    ///
+    /// ```rust,ignore
+    /// sched.boost_priority(&report_id, 100, Utc::now() + chrono::Duration::days(3)).await?;
+    /// ```
+    pub async fn boost_priority(
+        &self,
+        job_id: &Uuid,
+        priority: i32,
+        until: DateTime<Utc>,
+    ) -> Result<(), JobSchedulerError> {
+        if !self.inited().await {
+            let mut s = self.clone();
+            s.init().await?;
+        }
+        let mut w = self.context.metadata_storage.write().await;
+        let mut data = w
+            .get(*job_id)
+            .await?
+            .ok_or(JobSchedulerError::JobNotFound)?;
+        data.boosted_priority = Some(priority);
+        data.boost_until = Some(until.timestamp().max(0) as u64);
+        w.add_or_update(data).await
+    }
+
+    async fn set_stopped(&self, job_id: &Uuid, stopped: bool) -> Result<(), JobSchedulerError> {
+        if !self.inited().await {
+            let mut s = self.clone();
+            s.init().await?;
+        }
+        let mut w = self.context.metadata_storage.write().await;
+        let mut data = w
+            .get(*job_id)
+            .await?
+            .ok_or(JobSchedulerError::JobNotFound)?;
+        data.stopped = stopped;
+        w.add_or_update(data).await?;
+        self.wake_scheduler().await;
+        Ok(())
+    }
+
+    ///
+    /// Work out a cron job's first tick with the scheduler's schedule parser, whichever
+    /// constructor created the job, and draw its jitter on top
+    async fn first_tick(&self, job: &mut JobLocked) -> Result<(), JobSchedulerError> {
+        let mut data = job.job_data()?;
+        // A job that ticked before, restored from its stored data, keeps its tick
+        if data.last_tick.is_some() && data.jitter_ms == 0 {
+            return Ok(());
+        }
+        let scheduler = self.scheduler.read().await;
+        let next_tick = {
+            let parser = scheduler.schedule_parser.read().await;
+            let now = scheduler.clock.read().await.now();
+            match data.next_schedule_tick(&**parser, &now) {
+                Some(tick) => tick?,
+                None => return Ok(()),
+            }
+        };
+        let next_tick = match data.jitter_ms {
+            0 => next_tick,
+            jitter_ms => next_tick.map(|t| scheduler.jitter_rng.jitter(t, jitter_ms)),
+        };
+        data.set_next_tick(next_tick);
+        job.set_job_data(data)
+    }
+
+    ///
+    /// Move a cron job's stored next tick onto its schedule, to the first tick of the schedule at
+    /// or after it, when it isn't one of the schedule's ticks
+    async fn align_next_tick(&self, job: &mut JobLocked) -> Result<(), JobSchedulerError> {
+        let mut data = job.job_data()?;
+        let stored = match data.next_tick_utc() {
+            Some(stored) if data.jitter_ms == 0 => stored,
+            _ => return Ok(()),
+        };
+        let aligned = {
+            let scheduler = self.scheduler.read().await;
+            let parser = scheduler.schedule_parser.read().await;
+            let before = stored - chrono::Duration::seconds(1);
+            match data.next_schedule_tick(&**parser, &before) {
+                Some(tick) => tick?,
+                None => return Ok(()),
+            }
+        };
+        match aligned {
+            Some(aligned) if aligned != stored => {
+                data.set_next_tick(Some(aligned));
+                job.set_job_data(data)
+            }
+            _ => Ok(()),
+        }
+    }
+
+    ///
+    /// Have the scheduler's loop look at the next ticks again, instead of sleeping on until the
+    /// tick it was waiting for
+    async fn wake_scheduler(&self) {
+        let scheduler = self.scheduler.read().await;
+        scheduler.wake.notify_one();
+    }
+
+    /// `notifications_for` lists the notifications stored for a job, as pairs of the
+    /// notification's GUID and a state it is registered for. After a restart with a persistent
+    /// notification store these can be handed to `register_notification_code` to reconnect the
+    /// notifications to code.
+    pub async fn notifications_for(
+        &self,
+        job_id: &Uuid,
+    ) -> Result<Vec<(Uuid, JobState)>, JobSchedulerError> {
+        if !self.inited().await {
+            let mut s = self.clone();
+            s.init().await?;
+        }
+        let mut w = self.context.notification_storage.write().await;
+        let notification_ids = w.list_notification_guids_for_job_id(*job_id).await?;
+        let mut ret = vec![];
+        for notification_id in notification_ids {
+            if let Some(data) = w.get(notification_id).await? {
+                ret.extend(
+                    data.job_states
+                        .iter()
+                        .filter_map(|s| JobState::from_i32(*s))
+                        .map(|s| (notification_id, s)),
+                );
+            }
+        }
+        Ok(ret)
+    }
+
+    /// `register_notification_code` attaches code to a notification that is already stored, for
+    /// example one listed by `notifications_for` after a restart. The notification keeps its GUID
+    /// and the states it was registered for. Returns `NotificationNotFound` for unknown
+    /// notifications.
+    pub async fn register_notification_code(
+        &self,
+        job_id: &Uuid,
+        notification_id: &Uuid,
+        run: Box<OnJobNotification>,
+    ) -> Result<Uuid, JobSchedulerError> {
+        if !self.inited().await {
+            let mut s = self.clone();
+            s.init().await?;
+        }
+        let data = {
+            let mut w = self.context.notification_storage.write().await;
+            w.get(*notification_id).await?
+        };
+        let data = data.ok_or(JobSchedulerError::NotificationNotFound)?;
+        let states = data
+            .job_states
+            .iter()
+            .filter_map(|s| JobState::from_i32(*s))
+            .collect::<Vec<_>>();
+        NotificationCreator::add_with_id(&self.context, run, states, job_id, *notification_id).await
+    }
+
+    /// `remove_notification` detaches a single notification from its job, for all the states it
+    /// was registered for. The job and its other notifications are kept. Returns
+    /// `NotificationNotFound` for unknown notifications.
+    ///
+    /// ```rust,ignore
+    /// let notification_id = job.on_done_notification_add(&sched, Box::new(|_, _, _| {
+    ///     Box::pin(async move {
+    ///         println!("Job done");
+    ///     })
+    /// })).await?;
+    /// sched.remove_notification(notification_id).await?;
+    /// ```
+    pub async fn remove_notification(
+        &self,
+        notification_id: Uuid,
+    ) -> Result<(), JobSchedulerError> {
+        if !self.inited().await {
+            let mut s = self.clone();
+            s.init().await?;
+        }
+        let data = {
+            let mut w = self.context.notification_storage.write().await;
+            w.get(notification_id).await?
+        };
+        if data.is_none() {
+            return Err(JobSchedulerError::NotificationNotFound);
+        }
+        NotificationDeleter::remove(&self.context, &notification_id, None)?;
+        Ok(())
+    }
+
+    ///
+    /// Shut the scheduler down. The jobs stay in the metadata storage, so no `Removed`
+    /// notifications are sent. Instead a `SchedulerStopped` event, with a nil job id, is sent to
+    /// the subscribers of `subscribe` once no new runs start anymore. Use
+    /// `set_notify_stop_on_shutdown` to get a `Stop` notification for every job too. The stores are
+    /// flushed before the shutdown handler is called, an error flushing them is returned once the
+    /// handler ran.
+    pub async fn shutdown(&mut self) -> Result<(), JobSchedulerError> {
+        self.shutdown_waiting(None).await
+    }
+
+    ///
+    /// Shut the scheduler down like `shutdown`, but wait up to `timeout` for the jobs that are
+    /// running to finish first. No new runs are started while waiting. Runs still going when the
+    /// timeout expires are aborted and their job ids returned in a `JobsCancelledOnShutdown`
+    /// error; the scheduler is shut down either way.
+    ///
+    /// ```rust,ignore
+    /// if let Err(JobSchedulerError::JobsCancelledOnShutdown(ids)) =
+    ///     sched.shutdown_graceful(Duration::from_secs(30)).await
+    /// {
+    ///     warn!("Cancelled {:?}", ids);
+    /// }
+    /// ```
+    pub async fn shutdown_graceful(
+        &mut self,
+        timeout: std::time::Duration,
+    ) -> Result<(), JobSchedulerError> {
+        self.shutdown_waiting(Some(timeout)).await
+    }
+
+    async fn shutdown_waiting(
+        &mut self,
+        timeout: Option<std::time::Duration>,
+    ) -> Result<(), JobSchedulerError> {
+        let mut notify = None;
+        std::mem::swap(&mut self.shutdown_notifier, &mut notify);
+        let mut notify_fallible = None;
+        std::mem::swap(&mut self.fallible_shutdown_notifier, &mut notify_fallible);
+
+        let notify_stop_on_shutdown = {
+            let mut scheduler = self.scheduler.write().await;
+            scheduler.shutdown().await;
+            scheduler.notify_stop_on_shutdown
+        };
+        self.context.shutdown_tx.send_replace(true);
+
+        let cancelled = match timeout {
+            Some(timeout) => {
+                let runner = self.job_runner.read().await;
+                runner.wait_for_runs(timeout).await
+            }
+            None => vec![],
+        };
+        // Without subscribers there's nobody to miss the event
+        let _ = self.context.event_tx.send(JobEvent {
+            job_id: Uuid::nil(),
+            kind: JobState::SchedulerStopped,
+            at: Utc::now(),
+        });
+
+        if notify_stop_on_shutdown {
+            let jobs = {
+                let mut w = self.context.metadata_storage.write().await;
+                w.list_jobs().await
+            };
+            match jobs {
+                Ok(jobs) => {
+                    for job in jobs {
+                        if let Some(id) = job.id.as_ref() {
+                            if let Err(e) = self.context.notify_tx.send((id.into(), JobState::Stop))
+                            {
+                                error!("Error sending stop notification {:?}", e);
+                            }
+                        }
+                    }
+                }
+                Err(e) => error!("Error listing jobs to notify of shutdown {:?}", e),
+            }
+        }
+
+        // The shutdown handlers may stop the process, so the stores write what they buffered first
+        let flushed = self.flush_stores().await;
+
+        if let Some(notify) = notify {
+            let mut notify = notify.write().await;
+            notify().await;
+        }
+        if let Some(notify) = notify_fallible {
+            let mut notify = notify.write().await;
+            if let Err(e) = notify().await {
+                if !cancelled.is_empty() {
+                    error!("Jobs cancelled on shutdown {:?}", cancelled);
+                }
+                return Err(e);
+            }
+        }
+        if let Err(e) = flushed {
+            if !cancelled.is_empty() {
+                error!("Jobs cancelled on shutdown {:?}", cancelled);
+            }
+            return Err(e);
+        }
+        if cancelled.is_empty() {
+            Ok(())
+        } else {
+            Err(JobSchedulerError::JobsCancelledOnShutdown(cancelled))
+        }
+    }
+
+    ///
+    /// Flush both stores, see `MetaDataStorage::flush`. Both are flushed even when the first one
+    /// fails, the first error is returned.
+    async fn flush_stores(&self) -> Result<(), JobSchedulerError> {
+        let metadata = {
+            let mut w = self.context.metadata_storage.write().await;
+            w.flush().await
+        };
+        if let Err(e) = &metadata {
+            error!("Error flushing the metadata storage {:?}", e);
+        }
+        let notifications = {
+            let mut w = self.context.notification_storage.write().await;
+            w.flush().await
+        };
+        if let Err(e) = &notifications {
+            error!("Error flushing the notification storage {:?}", e);
+        }
+        metadata.and(notifications)
+    }
+
+    ///
+    /// Serve the admin API, see `admin_router`, on the address until the server fails. Spawn it to
+    /// keep it running next to the scheduler. Returns `AdminHttp` when the address can't be bound.
+    ///
+    /// ```rust,ignore
+    /// let admin = sched.clone();
+    /// tokio::spawn(async move { admin.serve_admin(([127, 0, 0, 1], 8080).into()).await });
+    /// ```
+    #[cfg(feature = "admin-http")]
+    pub async fn serve_admin(&self, addr: std::net::SocketAddr) -> Result<(), JobSchedulerError> {
+        crate::admin_http::serve(self.clone(), addr).await
+    }
+
+    ///
+    /// Wait for a signal to shut the runtime down with
+    #[cfg(all(feature = "signal", unix))]
+    pub fn shutdown_on_signal(&self, signal: SignalKind) {
+        self.shutdown_on_signals(&[signal]);
+    }
+
+    ///
+    /// Wait for any of the signals to shut the runtime down with, shutting down on the first one
+    /// received.
+    ///
+    /// ```rust,ignore
+    /// sched.shutdown_on_signals(&[
+    ///     SignalKind::terminate(),
+    ///     SignalKind::interrupt(),
+    ///     SignalKind::quit(),
+    /// ]);
+    /// ```
+    #[cfg(all(feature = "signal", unix))]
+    pub fn shutdown_on_signals(&self, signals: &[SignalKind]) {
+        let mut l = self.clone();
+        let signals = signals.to_vec();
+        tokio::spawn(async move {
+            let (tx, mut rx) = tokio::sync::mpsc::channel(1);
+            let listeners = signals
+                .into_iter()
+                .map(|signal| {
+                    let mut signal =
+                        tokio::signal::unix::signal(signal).expect("Can't wait for signal");
+                    let tx = tx.clone();
+                    tokio::spawn(async move {
+                        if signal.recv().await.is_some() {
+                            let _ = tx.send(()).await;
+                        }
+                    })
+                })
+                .collect::<Vec<_>>();
+            drop(tx);
+            let received = rx.recv().await;
+            for listener in listeners {
+                listener.abort();
+            }
+            if received.is_some() {
+                if let Err(err) = l.shutdown().await {
+                    error!("Problem shutting down {:?}", err);
+                }
+            }
+        });
+    }
+
+    ///
+    /// Wait for a signal to shut the runtime down with
+    #[cfg(feature = "signal")]
+    pub fn shutdown_on_ctrl_c(&self) {
+        let mut l = self.clone();
+        tokio::spawn(async move {
+            tokio::signal::ctrl_c()
+                .await
+                .expect("Could not await ctrl-c");
+
+            if let Err(err) = l.shutdown().await {
+                error!("{:?}", err);
+            }
+        });
+    }
+
+    ///
+    /// Wait for a Ctrl-Break to shut the runtime down with, the Windows counterpart of
+    /// `shutdown_on_signal`
+    #[cfg(all(feature = "signal", windows))]
+    pub fn shutdown_on_ctrl_break(&self) {
+        let mut l = self.clone();
+        tokio::spawn(async move {
+            let mut ctrl_break =
+                tokio::signal::windows::ctrl_break().expect("Can't wait for ctrl-break");
+            if ctrl_break.recv().await.is_some() {
+                if let Err(err) = l.shutdown().await {
+                    error!("{:?}", err);
+                }
+            }
+        });
+    }
+
+    ///
+    /// Code that is run after the shutdown was run
+    pub fn set_shutdown_handler(&mut self, job: Box<ShutdownNotification>) {
+        self.fallible_shutdown_notifier = None;
+        self.shutdown_notifier = Some(Arc::new(RwLock::new(job)));
+    }
+
+    ///
+    /// Code that is run after the shutdown was run, like `set_shutdown_handler`, but that can fail.
+    /// Its error is returned by `shutdown` and `shutdown_graceful`, taking precedence over
+    /// `JobsCancelledOnShutdown`. It replaces a handler set with `set_shutdown_handler`.
+    ///
+    /// ```rust,ignore
+    /// sched.set_shutdown_handler_fallible(Box::new(|| {
+    ///     Box::pin(async move {
+    ///         flush().await.map_err(|_| JobSchedulerError::ShutdownNotifier)
+    ///     })
+    /// }));
+    /// if let Err(e) = sched.shutdown().await {
+    ///     std::process::exit(1);
+    /// }
+    /// ```
+    pub fn set_shutdown_handler_fallible(&mut self, job: Box<FallibleShutdownNotification>) {
+        self.shutdown_notifier = None;
+        self.fallible_shutdown_notifier = Some(Arc::new(RwLock::new(job)));
+    }
+
+    ///
+    /// Remove the shutdown handler
+    pub fn remove_shutdown_handler(&mut self) {
+        self.shutdown_notifier = None;
+        self.fallible_shutdown_notifier = None;
+    }
+
+    ///
+    /// Wait until the scheduler's tick loop is running, after `start`. Returns right away when it
+    /// already is, and never when the scheduler isn't started.
+    ///
+    /// ```rust,ignore
+    /// sched.start().await?;
+    /// sched.wait_until_started().await;
+    /// ```
+    pub async fn wait_until_started(&self) {
+        let started = {
+            let scheduler = self.scheduler.read().await;
+            scheduler.wait_until_started()
+        };
+        started.await
+    }
+
+    ///
+    /// Get when the scheduler's tick loop last ran. The loop updates this on every iteration
+    /// before it goes to the storage, so a watchdog can use it to detect a scheduler that stopped
+    /// making progress. Returns `None` when the loop hasn't ticked yet.
+    pub async fn last_tick_at(&self) -> Option<DateTime<Utc>> {
+        let scheduler = self.scheduler.read().await;
+        scheduler.last_tick_at()
+    }
+
+    ///
+    /// Make the scheduler's loop check for due jobs at least this often. The loop sleeps until the
+    /// earliest next tick and wakes up whenever jobs are added, removed, paused, resumed or
+    /// rescheduled through this scheduler. It only falls back to checking every interval, 500ms
+    /// by default, when no job has a next tick, when the store is shared with other schedulers,
+    /// see `MetaDataStorage::is_shared`, or with a clock other than the system's. Setting an
+    /// interval makes the loop check that often regardless. Intervals under 10ms are raised to
+    /// 10ms. Takes effect from the next tick of the loop.
+    pub async fn set_tick_interval(&self, interval: std::time::Duration) {
+        let scheduler = self.scheduler.read().await;
+        scheduler.set_tick_interval(interval);
+    }
+
+    ///
+    /// Set how often the same error of the scheduler's loop is logged while it persists, like a
+    /// store that is down. The first error is logged right away; after that at most one per
+    /// interval, with the number of errors left out, until a tick goes through again. Defaults to
+    /// 30s, zero logs every error. Every error is still sent to `subscribe_tick_errors`.
+    pub async fn set_tick_error_log_interval(&self, interval: std::time::Duration) {
+        let scheduler = self.scheduler.read().await;
+        scheduler.set_tick_error_log_interval(interval);
+    }
+
+    ///
+    /// The longest the scheduler's loop sleeps between checking for due jobs
+    pub async fn tick_interval(&self) -> std::time::Duration {
+        let scheduler = self.scheduler.read().await;
+        scheduler.tick_interval()
+    }
+    ///
+    /// Code that is run on every tick of the scheduler's loop. It receives the time of the tick.
+    /// Ticks that come while the handler is still running for an earlier one don't call it.
+    pub async fn set_heartbeat_handler(&self, handler: Box<HeartbeatNotification>) {
+        let scheduler = self.scheduler.read().await;
+        let mut w = scheduler.heartbeat_handler.write().await;
+        *w = Some(handler);
+    }
+
+    ///
+    /// Remove the heartbeat handler
+    pub async fn remove_heartbeat_handler(&self) {
+        let scheduler = self.scheduler.read().await;
+        let mut w = scheduler.heartbeat_handler.write().await;
+        *w = None;
+    }
+
+    ///
+    /// Pause all jobs at once, without shutting the scheduler down. The scheduler keeps ticking,
+    /// its heartbeat included, but no job runs and no job is notified while paused. The next ticks
+    /// of the jobs move on as their ticks go by, except for one shot jobs, which stay due. Jobs can
+    /// still be added and removed.
+    ///
+    /// ```rust,ignore
+    /// sched.pause_all().await;
+    /// run_maintenance().await;
+    /// sched.resume_all().await;
+    /// ```
+    pub async fn pause_all(&self) {
+        let scheduler = self.scheduler.read().await;
+        scheduler
+            .paused
+            .store(true, std::sync::atomic::Ordering::Relaxed);
+    }
+
+    ///
+    /// Resume the jobs after `pause_all`. Jobs whose ticks went by while paused are misfired from
+    /// the first tick they missed, they catch up as their `MisfirePolicy` says, by default with a
+    /// single run.
+    pub async fn resume_all(&self) {
+        {
+            let scheduler = self.scheduler.read().await;
+            scheduler
+                .paused
+                .store(false, std::sync::atomic::Ordering::Relaxed);
+        }
+        self.wake_scheduler().await;
+    }
+
+    ///
+    /// Start draining the scheduler, for a rolling deploy. The jobs that are scheduled keep running
+    /// and their notifications keep firing, but `add`, `add_or_update` and `add_all` fail with
+    /// `Draining` from now on, so the jobs can be handed to the instance that replaces this one.
+    /// Wait for the runs going on with `wait_drained`, then shut down.
+    ///
+    /// ```rust,ignore
+    /// sched.drain().await;
+    /// sched.wait_drained().await;
+    /// sched.shutdown().await?;
+    /// ```
+    pub async fn drain(&self) {
+        let scheduler = self.scheduler.read().await;
+        scheduler
+            .draining
+            .store(true, std::sync::atomic::Ordering::Relaxed);
+    }
+
+    ///
+    /// Whether the scheduler is draining and no run is going on, queued runs included. False
+    /// until `drain` was called.
+    pub async fn is_drained(&self) -> bool {
+        let idle = {
+            let runner = self.job_runner.read().await;
+            let running = runner.running.read().await;
+            running.is_empty()
+        };
+        idle && self.is_draining().await
+    }
+
+    ///
+    /// Resolves once the scheduler is drained, see `is_drained`. Only returns after `drain` was
+    /// called.
+    pub async fn wait_drained(&self) {
+        while !self.is_drained().await {
+            tokio::time::sleep(DRAIN_POLL_INTERVAL).await;
+        }
+    }
+
+    async fn is_draining(&self) -> bool {
+        let scheduler = self.scheduler.read().await;
+        scheduler
+            .draining
+            .load(std::sync::atomic::Ordering::Relaxed)
+    }
+
+    ///
+    /// Set a gate that is checked once per tick of the scheduler, when jobs are due. While the gate
+    /// returns false none of the due jobs are run, they're notified as `Skipped` and their next
+    /// tick is moved on as if they ran.
+    pub async fn set_global_gate(&self, gate: Box<GlobalGate>) {
+        let scheduler = self.scheduler.read().await;
+        let mut w = scheduler.global_gate.write().await;
+        *w = Some(gate);
+    }
+
+    ///
+    /// Remove the global gate, letting all due jobs run again
+    pub async fn remove_global_gate(&self) {
+        let scheduler = self.scheduler.read().await;
+        let mut w = scheduler.global_gate.write().await;
+        *w = None;
+    }
+
+    ///
+    /// Subscribe to the reasons jobs are skipped. Every `Skipped` notification of a job is
+    /// accompanied by the job's GUID and the `SkipReason` on this channel, so handlers that need
+    /// to know why a run was skipped can listen here.
+    pub fn subscribe_skipped(&self) -> tokio::sync::broadcast::Receiver<(Uuid, SkipReason)> {
+        self.context.skipped_tx.subscribe()
+    }
+
+    pub(crate) async fn is_shutdown(&self) -> bool {
+        let scheduler = self.scheduler.read().await;
+        let shutdown = scheduler.shutdown.read().await;
+        *shutdown
+    }
+
+    ///
+    /// Subscribe to the errors of failed runs. Every `Failed` notification, of a job created with
+    /// `Job::new_async_result`, of a run a middleware failed or of a run that panicked, is
+    /// accompanied by the job's GUID
+    /// and the error's message on this channel.
+    pub fn subscribe_failed(&self) -> tokio::sync::broadcast::Receiver<(Uuid, String)> {
+        self.context.failed_tx.subscribe()
+    }
+
+    ///
+    /// Subscribe to the lifecycle events of all jobs. Every notification of every job, like a job
+    /// being started, done, removed or failing, is sent on this channel as a `JobEvent`, whether
+    /// or not the job has notifications registered for it. The scheduler shutting down is sent as
+    /// a `SchedulerStopped` event with a nil job id.
+    ///
+    /// The channel holds the last 200 events. A receiver that falls further behind doesn't slow
+    /// the scheduler down; instead its next `recv` returns `RecvError::Lagged` with the number of
+    /// events it missed, and it carries on with the oldest event still held.
+    ///
+    /// ```rust,ignore
+    /// let mut events = sched.subscribe();
+    /// while let Ok(event) = events.recv().await {
+    ///     println!("{:?} {:?} at {:?}", event.job_id, event.kind, event.at);
+    /// }
+    /// ```
+    pub fn subscribe(&self) -> tokio::sync::broadcast::Receiver<JobEvent> {
+        self.context.event_tx.subscribe()
+    }
+
+    ///
+    /// Subscribe to the errors of the scheduler's tick loop. The loop logs an error, like a store
+    /// that can't list the next ticks, and carries on with the next tick as before; it also
+    /// sends the error on this channel, so a caller can count failed ticks and, for instance,
+    /// restart the scheduler after too many in a row.
+    ///
+    /// ```rust,ignore
+    /// let mut tick_errors = sched.subscribe_tick_errors();
+    /// while let Ok(e) = tick_errors.recv().await {
+    ///     eprintln!("Tick failed {:?}", e);
+    /// }
+    /// ```
+    pub fn subscribe_tick_errors(&self) -> tokio::sync::broadcast::Receiver<JobSchedulerError> {
+        self.context.tick_error_tx.subscribe()
+    }
+    ///
+    /// Seed the generator of the jitter of jobs created `with_jitter`, so the offsets of their
+    /// ticks are the same on every run. Jobs get their offsets in the order they are added and
+    /// run.
+    pub async fn set_jitter_seed(&self, seed: u64) {
+        let scheduler = self.scheduler.read().await;
+        scheduler.jitter_rng.seed(seed);
+    }
+
+    ///
+    /// Draw the jitter of jobs created `with_jitter` from `rng` instead of the scheduler's own
+    /// generator, for instance a seeded `StdRng` in tests or an OS backed generator. Calling
+    /// `set_jitter_seed` afterwards goes back to the scheduler's own generator.
+    ///
+    /// ```rust,ignore
+    /// sched.set_jitter_rng(Box::new(rand::rngs::StdRng::seed_from_u64(42))).await;
+    /// ```
+    pub async fn set_jitter_rng(&self, rng: Box<dyn RngCore + Send>) {
+        let scheduler = self.scheduler.read().await;
+        scheduler.jitter_rng.set_rng(rng);
+    }
+
+    ///
+    /// Set the parser used to calculate the ticks of cron jobs, the first one included: it's
+    /// worked out on `add`, whichever constructor created the job. Defaults to a
+    /// `CronScheduleParser`. Needed when jobs are created with `Job::new_cron_job_with_parser` or
+    /// `JobBuilder::with_schedule_parser`.
+    pub async fn set_schedule_parser(&self, parser: Box<dyn ScheduleParser + Send + Sync>) {
+        let scheduler = self.scheduler.read().await;
+        let mut w = scheduler.schedule_parser.write().await;
+        *w = parser;
+    }
+
+    ///
+    /// Set the clock the scheduler takes the time from. Defaults to a `SystemClock`. With a
+    /// `ManualClock` tests decide when jobs are due: the loop fires the jobs whose next tick the
+    /// clock has passed, and computes their next ticks from the clock's time.
+    ///
+    /// Cron jobs compute their first tick from the clock's time when they are added, interval
+    /// jobs from the system clock when they are created. Call `recompute_all_ticks` after adding
+    /// them to start those off from the clock's time too.
+    /// The loop still sleeps in real time between ticks, see `set_tick_interval`.
+    ///
+    /// ```rust,ignore
+    /// let clock = ManualClock::new(Utc.ymd(2030, 1, 1).and_hms(0, 0, 0));
+    /// sched.set_clock(Box::new(clock.clone())).await;
+    /// sched.add(job).await?;
+    /// sched.recompute_all_ticks().await?;
+    /// clock.advance(Duration::from_secs(60 * 60));
+    /// ```
+    pub async fn set_clock(&self, clock: Box<dyn Clock + Send + Sync>) {
+        let scheduler = self.scheduler.read().await;
+        let mut w = scheduler.clock.write().await;
+        *w = clock;
+        drop(w);
+        scheduler.wake.notify_one();
+    }
+
+    ///
+    /// The current time by the scheduler's clock, see `set_clock`
+    pub async fn now(&self) -> DateTime<Utc> {
+        let scheduler = self.scheduler.read().await;
+        let clock = scheduler.clock.read().await;
+        clock.now()
+    }
+
+    ///
+    /// Add a middleware that is wrapped around every job run. Middlewares run in the order they
+    /// were added, the first one being the outermost. See `JobMiddleware`.
+    pub async fn add_job_middleware(&self, middleware: Box<dyn JobMiddleware + Send + Sync>) {
+        let runner = self.job_runner.read().await;
+        let mut w = runner.middlewares.write().await;
+        w.push(Arc::from(middleware));
+    }
+
+    ///
+    /// Whether shutting down sends a `Stop` notification for every job. Off by default. The
+    /// notifications are sent before the shutdown handler runs, but their handlers run
+    /// concurrently with it.
+    pub async fn set_notify_stop_on_shutdown(&self, notify: bool) {
+        let mut scheduler = self.scheduler.write().await;
+        scheduler.notify_stop_on_shutdown = notify;
+    }
+
+    ///
+    /// Set the sink that receives a record of every job run. By default the records are dropped.
+    pub async fn set_run_sink(&self, sink: Box<dyn RunSink + Send + Sync>) {
+        let runner = self.job_runner.read().await;
+        let mut w = runner.run_sink.write().await;
+        *w = sink;
+    }
+
+    ///
+    /// Set the handler that is called when a job's code panics, with the job's id, the panic's
+    /// message and the scheduler, to log, alert or remove the job. A panic fails the run whether
+    /// a handler is set or not: the job is notified as `Failed`, the message is sent on
+    /// `subscribe_failed` and the job keeps its schedule.
+    ///
+    /// ```rust,ignore
+    /// sched.set_job_panic_handler(Box::new(|job_id, message, sched| {
+    ///     Box::pin(async move {
+    ///         alert(&format!("{:?} panicked: {}", job_id, message)).await;
+    ///         let _ = sched.remove(&job_id).await;
+    ///     })
+    /// })).await;
+    /// ```
+    pub async fn set_job_panic_handler(&self, handler: Box<JobPanicHandler>) {
+        let runner = self.job_runner.read().await;
+        let mut w = runner.panic_handler.write().await;
+        *w = Some(handler);
+    }
+
+    ///
+    /// Limit how many job runs go on at once, across all jobs, or lift the limit with `None`.
+    /// Unlimited by default. Runs over the limit queue until another run finishes; the scheduler
+    /// keeps ticking meanwhile. A job's timeout starts once its run leaves the queue. Runs still
+    /// queued when the scheduler shuts down are dropped without running. A new limit applies to
+    /// the runs activated after it was set.
+    ///
+    /// ```rust,ignore
+    /// // At most as many runs as the database has connections
+    /// sched.set_max_concurrent_jobs(Some(10)).await;
+    /// ```
+    pub async fn set_max_concurrent_jobs(&self, max: Option<usize>) {
+        let runner = self.job_runner.read().await;
+        let mut w = runner.max_concurrent.write().await;
+        // A limit of zero would never run anything
+        *w = max.map(|max| Arc::new(tokio::sync::Semaphore::new(max.max(1))));
+    }
+
+    ///
+    /// Run the jobs on another Tokio runtime than the one the scheduler runs on, or back on the
+    /// scheduler's own with `None`. The scheduler keeps ticking and activating jobs on its own
+    /// runtime, so CPU heavy jobs on a dedicated runtime don't starve it, or anything else on it
+    /// like an HTTP server. The runtime needs its time driver enabled for the jobs' timeouts. It
+    /// applies to the runs activated after it was set.
+    ///
+    /// Sync jobs run on a worker thread of the runtime. Jobs that block for long should still
+    /// move that work to `tokio::task::spawn_blocking`.
+    ///
+    /// ```rust,ignore
+    /// let jobs = tokio::runtime::Builder::new_multi_thread()
+    ///     .worker_threads(2)
+    ///     .enable_all()
+    ///     .build()?;
+    /// sched.set_job_runtime(Some(jobs.handle().clone())).await;
+    /// ```
+    pub async fn set_job_runtime(&self, runtime: Option<tokio::runtime::Handle>) {
+        let runner = self.job_runner.read().await;
+        let mut w = runner.runtime.write().await;
+        *w = runtime;
+    }
+
+    ///
+    /// Set the store that keeps the history of the job runs, see `run_history`. By default no
+    /// history is kept.
+    ///
+    /// ```rust,ignore
+    /// sched.set_history_store(Box::new(SimpleHistoryStore::new(20))).await;
+    /// ```
+    pub async fn set_history_store(&self, store: Box<dyn HistoryStore + Send + Sync>) {
+        let runner = self.job_runner.read().await;
+        let mut w = runner.history_store.write().await;
+        *w = store;
+    }
+
+    ///
+    /// The finished runs of a job kept by the history store, oldest first. Empty unless a store
+    /// was set with `set_history_store`.
+    pub async fn run_history(&self, job_id: Uuid) -> Result<Vec<JobRunRecord>, JobSchedulerError> {
+        let history = {
+            let runner = self.job_runner.read().await;
+            let r = runner.history_store.read().await;
+            r.history(job_id)
+        };
+        history.await
+    }
+
+    ///
+    /// The jobs whose code is running right now. A job is in it from the moment its run starts
+    /// until the run finished, failed or timed out. Runs waiting for the limit of
+    /// `set_max_concurrent_jobs` aren't running yet.
+    ///
+    /// ```rust,ignore
+    /// for job_id in sched.running_jobs().await? {
+    ///     println!("{:?} is running", job_id);
+    /// }
+    /// ```
+    pub async fn running_jobs(&self) -> Result<Vec<Uuid>, JobSchedulerError> {
+        let runner = self.job_runner.read().await;
+        let executing = runner
+            .executing
+            .lock()
+            .map_err(|_| JobSchedulerError::GetJobData)?;
+        Ok(executing.keys().cloned().collect())
     }
 
     ///
@@ -3,17 +3,30 @@
 extern crate num_derive;
 extern crate core;
 
+#[cfg(feature = "admin-http")]
+mod admin_http;
+#[cfg(feature = "blocking")]
+mod blocking;
+mod clock;
 mod context;
 mod error;
+#[cfg(feature = "test-util")]
+mod event_recorder;
 pub mod job;
 mod job_scheduler;
+#[cfg(feature = "mongodb_storage")]
+mod mongodb;
 #[cfg(feature = "nats_storage")]
 mod nats;
 mod notification;
 #[cfg(feature = "postgres_storage")]
 mod postgres;
+#[cfg(feature = "redis_storage")]
+mod redis;
 mod scheduler;
 mod simple;
+#[cfg(feature = "sqlite_storage")]
+mod sqlite;
 pub mod store;
 
 use std::ops::Add;
@@ -25,6 +38,7 @@ use crate::job::job_data::ListOfUuids;
 #[cfg(feature = "has_bytes")]
 use crate::job::job_data_prost::ListOfUuids;
 use chrono::{DateTime, Utc};
+use chrono_tz::Tz;
 use cron::Schedule;
 #[cfg(not(feature = "has_bytes"))]
 use job::job_data::{JobAndNextTick, JobStoredData, Uuid as JobUuid};
@@ -32,23 +46,55 @@ use job::job_data::{JobAndNextTick, JobStoredData, Uuid as JobUuid};
 use job::job_data_prost::{JobAndNextTick, JobStoredData, Uuid as JobUuid};
 use uuid::Uuid;
 
+#[cfg(feature = "mongodb_storage")]
+pub use crate::mongodb::{MongoMetadataStore, MongoNotificationStore, MongoStore};
+
 #[cfg(feature = "nats_storage")]
 pub use crate::nats::{NatsMetadataStore, NatsNotificationStore, NatsStore, NatsStoreBuilder};
 
 #[cfg(feature = "postgres_storage")]
 pub use crate::postgres::{PostgresMetadataStore, PostgresNotificationStore, PostgresStore};
 
+#[cfg(feature = "redis_storage")]
+pub use crate::redis::{RedisMetadataStore, RedisNotificationStore, RedisStore};
+
+#[cfg(feature = "sqlite_storage")]
+pub use crate::sqlite::{SqliteMetadataStore, SqliteNotificationStore, SqliteStore};
+
+#[cfg(feature = "blocking")]
+pub use crate::blocking::BlockingScheduler;
+
+#[cfg(feature = "admin-http")]
+pub use crate::admin_http::admin_router;
+
+pub use clock::{Clock, ManualClock, SystemClock};
 pub use context::Context;
 pub use error::JobSchedulerError;
+#[cfg(feature = "test-util")]
+pub use event_recorder::{EventRecorder, RecordedEvent};
 #[cfg(not(feature = "has_bytes"))]
 pub use job::job_data::JobState as JobNotification;
+#[cfg(not(feature = "has_bytes"))]
+pub use job::job_data::MisfirePolicy;
+#[cfg(not(feature = "has_bytes"))]
+pub use job::job_data::{DstGapPolicy, DstOverlapPolicy};
 #[cfg(feature = "has_bytes")]
 pub use job::job_data_prost::JobState as JobNotification;
+#[cfg(feature = "has_bytes")]
+pub use job::job_data_prost::MisfirePolicy;
+#[cfg(feature = "has_bytes")]
+pub use job::job_data_prost::{DstGapPolicy, DstOverlapPolicy};
 pub use job::to_code::{JobCode, NotificationCode, PinnedGetFuture, ToCode};
 pub use job::JobLocked as Job;
-pub use job::OnJobNotification;
-pub use job::{JobToRun, JobToRunAsync};
+pub use job::{describe_schedule, upcoming_for, upcoming_for_tz, validate_schedule};
+pub use job::{CronScheduleParser, NoopRunSink, RunRecord, RunSink, ScheduleParser, SkipReason};
+pub use job::{HistoryStore, JobRunRecord, NoopHistoryStore, Outcome, SimpleHistoryStore};
+pub use job::{JobBuilder, JobConfig};
+pub use job::{JobError, JobEvent, JobToRun, JobToRunAsync};
+pub use job::{JobMiddleware, JobRun, JobRunContext};
+pub use job::{NotificationBuilder, OnJobNotification, RetryPolicy};
 pub use job_scheduler::JobsSchedulerLocked as JobScheduler;
+pub use rand_core::RngCore;
 pub use store::{MetaDataStorage, NotificationStore};
 
 pub use simple::{
@@ -108,20 +154,120 @@ impl JobAndNextTick {
     fn last_tick_utc(&self) -> Option<DateTime<Utc>> {
         self.last_tick.map(JobAndNextTick::utc)
     }
+
+    ///
+    /// The priority the job is dispatched with at `now`, in seconds: the boosted one while the
+    /// boost of `boost_priority` lasts, its own priority otherwise
+    fn priority_at(&self, now: u64) -> i32 {
+        match (self.boosted_priority, self.boost_until) {
+            (Some(boosted), Some(until)) if now < until => boosted,
+            _ => self.priority,
+        }
+    }
 }
 
 impl JobStoredData {
     pub fn schedule(&self) -> Option<Schedule> {
+        self.schedule_str().and_then(|s| Schedule::from_str(s).ok())
+    }
+
+    pub fn schedule_str(&self) -> Option<&str> {
+        self.job.as_ref().and_then(|j| match j {
+            #[cfg(feature = "has_bytes")]
+            job::job_data_prost::job_stored_data::Job::CronJob(cj) => Some(&*cj.schedule),
+            #[cfg(not(feature = "has_bytes"))]
+            job::job_data::job_stored_data::Job::CronJob(cj) => Some(&*cj.schedule),
+            _ => None,
+        })
+    }
+
+    ///
+    /// The timezone a cron job's schedule is evaluated in, `None` for UTC
+    pub fn timezone(&self) -> Option<Tz> {
+        let timezone = self.job.as_ref().and_then(|j| match j {
+            #[cfg(feature = "has_bytes")]
+            job::job_data_prost::job_stored_data::Job::CronJob(cj) => Some(&*cj.timezone),
+            #[cfg(not(feature = "has_bytes"))]
+            job::job_data::job_stored_data::Job::CronJob(cj) => Some(&*cj.timezone),
+            _ => None,
+        })?;
+        if timezone.is_empty() {
+            return None;
+        }
+        match timezone.parse() {
+            Ok(tz) => Some(tz),
+            Err(e) => {
+                tracing::error!("Unknown timezone {:?} {:?}", timezone, e);
+                None
+            }
+        }
+    }
+
+    ///
+    /// How a cron job fires at a wall clock time that doesn't exist in its timezone, when the clocks
+    /// go forward
+    pub fn dst_gap(&self) -> DstGapPolicy {
         self.job
             .as_ref()
             .and_then(|j| match j {
                 #[cfg(feature = "has_bytes")]
-                job::job_data_prost::job_stored_data::Job::CronJob(cj) => Some(&*cj.schedule),
+                job::job_data_prost::job_stored_data::Job::CronJob(cj) => {
+                    DstGapPolicy::from_i32(cj.dst_gap)
+                }
                 #[cfg(not(feature = "has_bytes"))]
-                job::job_data::job_stored_data::Job::CronJob(cj) => Some(&*cj.schedule),
+                job::job_data::job_stored_data::Job::CronJob(cj) => {
+                    DstGapPolicy::from_i32(cj.dst_gap)
+                }
                 _ => None,
             })
-            .and_then(|s| Schedule::from_str(s).ok())
+            .unwrap_or(DstGapPolicy::ShiftByGap)
+    }
+
+    ///
+    /// How a cron job fires at a wall clock time that happens twice in its timezone, when the
+    /// clocks go back
+    pub fn dst_overlap(&self) -> DstOverlapPolicy {
+        self.job
+            .as_ref()
+            .and_then(|j| match j {
+                #[cfg(feature = "has_bytes")]
+                job::job_data_prost::job_stored_data::Job::CronJob(cj) => {
+                    DstOverlapPolicy::from_i32(cj.dst_overlap)
+                }
+                #[cfg(not(feature = "has_bytes"))]
+                job::job_data::job_stored_data::Job::CronJob(cj) => {
+                    DstOverlapPolicy::from_i32(cj.dst_overlap)
+                }
+                _ => None,
+            })
+            .unwrap_or(DstOverlapPolicy::FirstOccurrence)
+    }
+
+    ///
+    /// The first tick of a cron job's schedule after `after`, evaluated in the job's timezone, and
+    /// not before its active window starts. `None` for jobs without a schedule.
+    pub(crate) fn next_schedule_tick(
+        &self,
+        parser: &dyn ScheduleParser,
+        after: &DateTime<Utc>,
+    ) -> Option<Result<Option<DateTime<Utc>>, JobSchedulerError>> {
+        let schedule = self.schedule_str()?;
+        // Not before the job's active window
+        let after = match self.active_from_utc() {
+            Some(start) => (*after).max(start - chrono::Duration::seconds(1)),
+            None => *after,
+        };
+        Some(match self.timezone() {
+            Some(tz) => job::next_after_in(
+                parser,
+                schedule,
+                &tz,
+                &after,
+                self.dst_gap(),
+                self.dst_overlap(),
+            ),
+            None => parser.next_after(schedule, &after),
+        })
     }
 
     pub fn next_tick_utc(&self) -> Option<DateTime<Utc>> {
@@ -148,6 +294,31 @@ impl JobStoredData {
         })
     }
 
+    pub fn anchor(&self) -> Option<DateTime<Utc>> {
+        self.job
+            .as_ref()
+            .and_then(|jt| match jt {
+                #[cfg(feature = "has_bytes")]
+                job::job_data_prost::job_stored_data::Job::NonCronJob(ncj) => ncj.anchor,
+                #[cfg(not(feature = "has_bytes"))]
+                job::job_data::job_stored_data::Job::NonCronJob(ncj) => ncj.anchor,
+                _ => None,
+            })
+            .map(JobAndNextTick::utc)
+    }
+
+    ///
+    /// When the job is removed, see `with_expires_at`
+    pub fn expires_at_utc(&self) -> Option<DateTime<Utc>> {
+        self.expires_at.map(JobAndNextTick::utc)
+    }
+
+    ///
+    /// When the job's active window starts, see `with_active_window`
+    pub fn active_from_utc(&self) -> Option<DateTime<Utc>> {
+        self.active_from.map(JobAndNextTick::utc)
+    }
+
     pub fn set_next_tick(&mut self, tick: Option<DateTime<Utc>>) {
         self.next_tick = match tick {
             Some(t) => t.timestamp() as u64,
@@ -0,0 +1,480 @@
+use crate::job::job_data_prost::{CronJob, JobType, NonCronJob};
+use crate::mongodb::MongoStore;
+use crate::store::{DataStore, InitStore, MetaDataStorage};
+use crate::{JobAndNextTick, JobSchedulerError, JobStoredData, JobUuid};
+use chrono::{DateTime, Utc};
+use futures::TryStreamExt;
+use mongodb::bson::spec::BinarySubtype;
+use mongodb::bson::{doc, Binary, Document};
+use mongodb::options::{FindOneOptions, ReplaceOptions};
+use mongodb::IndexModel;
+use prost::Message;
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::sync::RwLock;
+use tracing::error;
+use uuid::Uuid;
+
+const COLLECTION: &str = "job";
+const MAX_METADATA_BYTES: usize = 1024 * 1024;
+
+///
+/// A MongoDB backed metadata store.
+///
+/// Every job is a document in `collection`, with the job's id as its `_id`. Initializing the store
+/// creates an index on `next_tick`, which finding the due and the next jobs use.
+#[derive(Clone)]
+pub struct MongoMetadataStore {
+    pub store: Arc<RwLock<MongoStore>>,
+    pub collection: String,
+    pub max_metadata_bytes: usize,
+}
+
+impl Default for MongoMetadataStore {
+    fn default() -> Self {
+        Self::new(Arc::new(RwLock::new(MongoStore::default())))
+    }
+}
+
+impl MongoMetadataStore {
+    ///
+    /// A metadata store on `store`, which can be shared with a `MongoNotificationStore`
+    pub fn new(store: Arc<RwLock<MongoStore>>) -> Self {
+        let collection =
+            std::env::var("MONGODB_METADATA_COLLECTION").unwrap_or_else(|_| COLLECTION.to_string());
+        let max_metadata_bytes = std::env::var("MONGODB_MAX_METADATA_BYTES")
+            .ok()
+            .and_then(|s| s.parse().ok())
+            .unwrap_or(MAX_METADATA_BYTES);
+        Self {
+            store,
+            collection,
+            max_metadata_bytes,
+        }
+    }
+}
+
+fn to_document(id: &Uuid, data: &JobStoredData) -> Document {
+    use crate::job::job_data_prost::job_stored_data::Job::CronJob as CronJobType;
+    use crate::job::job_data_prost::job_stored_data::Job::NonCronJob as NonCronJobType;
+
+    let (schedule, timezone, dst_gap, dst_overlap) = match data.job.as_ref() {
+        Some(CronJobType(ct)) => (
+            Some(ct.schedule.clone()),
+            Some(ct.timezone.clone()).filter(|tz| !tz.is_empty()),
+            Some(ct.dst_gap),
+            Some(ct.dst_overlap),
+        ),
+        _ => (None, None, None, None),
+    };
+    let (repeating, repeated_every, anchor) = match data.job.as_ref() {
+        Some(NonCronJobType(ct)) => (
+            Some(ct.repeating),
+            Some(ct.repeated_every as i64),
+            ct.anchor.map(|a| a as i64),
+        ),
+        _ => (None, None, None),
+    };
+    doc! {
+        "_id": id.to_string(),
+        "last_updated": data.last_updated.map(|i| i as i64),
+        "next_tick": data.next_tick as i64,
+        "last_tick": data.last_tick.map(|i| i as i64),
+        "job_type": data.job_type,
+        "count": data.count as i64,
+        "ran": data.ran,
+        "stopped": data.stopped,
+        "expires_at": data.expires_at.map(|e| e as i64),
+        "priority": data.priority,
+        "active_from": data.active_from.map(|a| a as i64),
+        "boosted_priority": data.boosted_priority,
+        "boost_until": data.boost_until.map(|b| b as i64),
+        "schedule": schedule,
+        "timezone": timezone,
+        "dst_gap": dst_gap,
+        "dst_overlap": dst_overlap,
+        "repeating": repeating,
+        "repeated_every": repeated_every,
+        "anchor": anchor,
+        "extra": Binary {
+            subtype: BinarySubtype::Generic,
+            bytes: data.extra.clone(),
+        },
+        "timeout_ms": data.timeout_ms.map(|t| t as i64),
+        "skip_overlapping": data.skip_overlapping,
+        "retries": data.retries as i64,
+        "retry_backoff_ms": data.retry_backoff_ms as i64,
+        "jitter_ms": data.jitter_ms as i64,
+        "tags": data.tags.clone(),
+        "misfire_policy": data.misfire_policy,
+        "max_runs": data.max_runs.map(|m| m as i64),
+    }
+}
+
+fn get_u64(document: &Document, key: &str) -> Option<u64> {
+    document.get_i64(key).ok().map(|i| i as u64)
+}
+
+fn get_id(document: &Document) -> Option<Uuid> {
+    match document.get_str("_id").map(Uuid::parse_str) {
+        Ok(Ok(id)) => Some(id),
+        _ => {
+            error!("Document without a job id {:?}", document.get("_id"));
+            None
+        }
+    }
+}
+
+fn from_document(document: &Document) -> Option<JobStoredData> {
+    use crate::job::job_data_prost::job_stored_data::Job::CronJob as CronJobType;
+    use crate::job::job_data_prost::job_stored_data::Job::NonCronJob as NonCronJobType;
+
+    let id = get_id(document)?;
+    let job_type = document.get_i32("job_type").unwrap_or_default();
+    let job = match JobType::from_i32(job_type) {
+        Some(JobType::Cron) => document.get_str("schedule").ok().map(|schedule| {
+            let timezone = document.get_str("timezone").unwrap_or_default();
+            CronJobType(CronJob {
+                schedule: schedule.to_string(),
+                timezone: timezone.to_string(),
+                dst_gap: document.get_i32("dst_gap").unwrap_or_default(),
+                dst_overlap: document.get_i32("dst_overlap").unwrap_or_default(),
+            })
+        }),
+        Some(_) => Some(NonCronJobType(NonCronJob {
+            repeating: document.get_bool("repeating").unwrap_or_default(),
+            repeated_every: get_u64(document, "repeated_every").unwrap_or_default(),
+            anchor: get_u64(document, "anchor"),
+        })),
+        None => None,
+    };
+    let tags = document
+        .get_array("tags")
+        .map(|tags| {
+            tags.iter()
+                .filter_map(|tag| tag.as_str().map(String::from))
+                .collect()
+        })
+        .unwrap_or_default();
+
+    Some(JobStoredData {
+        id: Some(id.into()),
+        last_updated: get_u64(document, "last_updated"),
+        last_tick: get_u64(document, "last_tick"),
+        next_tick: get_u64(document, "next_tick").unwrap_or_default(),
+        job_type,
+        count: get_u64(document, "count").unwrap_or_default() as u32,
+        extra: document
+            .get_binary_generic("extra")
+            .cloned()
+            .unwrap_or_default(),
+        ran: document.get_bool("ran").unwrap_or_default(),
+        stopped: document.get_bool("stopped").unwrap_or_default(),
+        timeout_ms: get_u64(document, "timeout_ms"),
+        skip_overlapping: document.get_bool("skip_overlapping").unwrap_or_default(),
+        retries: get_u64(document, "retries").unwrap_or_default() as u32,
+        retry_backoff_ms: get_u64(document, "retry_backoff_ms").unwrap_or_default(),
+        jitter_ms: get_u64(document, "jitter_ms").unwrap_or_default(),
+        tags,
+        misfire_policy: document.get_i32("misfire_policy").unwrap_or_default(),
+        max_runs: get_u64(document, "max_runs").map(|m| m as u32),
+        expires_at: get_u64(document, "expires_at"),
+        priority: document.get_i32("priority").unwrap_or_default(),
+        active_from: get_u64(document, "active_from"),
+        boosted_priority: document.get_i32("boosted_priority").ok(),
+        boost_until: get_u64(document, "boost_until"),
+        job,
+    })
+}
+
+fn job_and_next_tick(document: &Document) -> Option<JobAndNextTick> {
+    let id: JobUuid = get_id(document)?.into();
+    Some(JobAndNextTick {
+        id: Some(id),
+        job_type: document.get_i32("job_type").unwrap_or_default(),
+        next_tick: get_u64(document, "next_tick").unwrap_or_default(),
+        last_tick: get_u64(document, "last_tick"),
+        stopped: document.get_bool("stopped").unwrap_or_default(),
+        expires_at: get_u64(document, "expires_at"),
+        priority: document.get_i32("priority").unwrap_or_default(),
+        boosted_priority: document.get_i32("boosted_priority").ok(),
+        boost_until: get_u64(document, "boost_until"),
+    })
+}
+
+impl DataStore<JobStoredData> for MongoMetadataStore {
+    fn get(
+        &mut self,
+        id: Uuid,
+    ) -> Pin<Box<dyn Future<Output = Result<Option<JobStoredData>, JobSchedulerError>> + Send>>
+    {
+        let store = self.store.clone();
+        let collection = self.collection.clone();
+        Box::pin(async move {
+            let collection = match store.read().await.collection(&*collection) {
+                Some(collection) => collection,
+                None => return Err(JobSchedulerError::GetJobData),
+            };
+            match collection
+                .find_one(doc! { "_id": id.to_string() }, None)
+                .await
+            {
+                Ok(document) => Ok(document.as_ref().and_then(from_document)),
+                Err(e) => {
+                    error!("Error getting value {:?}", e);
+                    Err(JobSchedulerError::GetJobData)
+                }
+            }
+        })
+    }
+
+    fn add_or_update(
+        &mut self,
+        data: JobStoredData,
+    ) -> Pin<Box<dyn Future<Output = Result<(), JobSchedulerError>> + Send>> {
+        let size = data.encoded_len();
+        if size > self.max_metadata_bytes {
+            error!(
+                "Metadata of {:?} is {} bytes, more than the maximum of {}",
+                data.id, size, self.max_metadata_bytes
+            );
+            return Box::pin(async { Err(JobSchedulerError::MetadataTooLarge) });
+        }
+        let store = self.store.clone();
+        let collection = self.collection.clone();
+        Box::pin(async move {
+            let collection = match store.read().await.collection(&*collection) {
+                Some(collection) => collection,
+                None => return Err(JobSchedulerError::UpdateJobData),
+            };
+            let id: Uuid = match data.id.as_ref() {
+                Some(id) => id.into(),
+                None => return Err(JobSchedulerError::UpdateJobData),
+            };
+            let upsert = ReplaceOptions::builder().upsert(true).build();
+            collection
+                .replace_one(
+                    doc! { "_id": id.to_string() },
+                    to_document(&id, &data),
+                    upsert,
+                )
+                .await
+                .map(|_| ())
+                .map_err(|e| {
+                    error!("Error {:?}", e);
+                    JobSchedulerError::CantAdd
+                })
+        })
+    }
+
+    fn delete(
+        &mut self,
+        guid: Uuid,
+    ) -> Pin<Box<dyn Future<Output = Result<(), JobSchedulerError>> + Send>> {
+        let store = self.store.clone();
+        let collection = self.collection.clone();
+        Box::pin(async move {
+            let collection = match store.read().await.collection(&*collection) {
+                Some(collection) => collection,
+                None => return Err(JobSchedulerError::CantRemove),
+            };
+            collection
+                .delete_one(doc! { "_id": guid.to_string() }, None)
+                .await
+                .map(|_| ())
+                .map_err(|e| {
+                    error!("Error deleting job data {:?}", e);
+                    JobSchedulerError::CantRemove
+                })
+        })
+    }
+}
+
+impl InitStore for MongoMetadataStore {
+    fn init(&mut self) -> Pin<Box<dyn Future<Output = Result<(), JobSchedulerError>> + Send>> {
+        let store = self.store.clone();
+        let collection = self.collection.clone();
+        Box::pin(async move {
+            let mut w = store.write().await;
+            let v = w.clone().init().await?;
+            if let Some(collection) = v.collection(&*collection) {
+                let index = IndexModel::builder().keys(doc! { "next_tick": 1 }).build();
+                if let Err(e) = collection.create_index(index, None).await {
+                    error!("Error on init MongoDB Metadata store {:?}", e);
+                    return Err(JobSchedulerError::StoreInit(e.to_string()));
+                }
+            }
+            *w = v;
+            Ok(())
+        })
+    }
+
+    fn inited(&mut self) -> Pin<Box<dyn Future<Output = Result<bool, JobSchedulerError>> + Send>> {
+        let store = self.store.clone();
+        Box::pin(async move {
+            let store = store.read().await;
+            Ok(store.inited())
+        })
+    }
+}
+
+impl MetaDataStorage for MongoMetadataStore {
+    fn is_shared(&self) -> bool {
+        true
+    }
+
+    fn list_next_ticks(
+        &mut self,
+    ) -> Pin<Box<dyn Future<Output = Result<Vec<JobAndNextTick>, JobSchedulerError>> + Send>> {
+        let store = self.store.clone();
+        let collection = self.collection.clone();
+        Box::pin(async move {
+            let collection = match store.read().await.collection(&*collection) {
+                Some(collection) => collection,
+                None => return Err(JobSchedulerError::CantListNextTicks),
+            };
+            let now = Utc::now().timestamp();
+            let due = doc! { "$or": [
+                { "next_tick": { "$gt": 0_i64, "$lte": now } },
+                { "expires_at": { "$lte": now } },
+            ] };
+            let documents = match collection.find(due, None).await {
+                Ok(cursor) => cursor.try_collect::<Vec<_>>().await,
+                Err(e) => Err(e),
+            };
+            match documents {
+                Ok(documents) => Ok(documents.iter().filter_map(job_and_next_tick).collect()),
+                Err(e) => {
+                    error!("Error getting next ticks {:?}", e);
+                    Err(JobSchedulerError::CantListNextTicks)
+                }
+            }
+        })
+    }
+
+    fn list_jobs(
+        &mut self,
+    ) -> Pin<Box<dyn Future<Output = Result<Vec<JobAndNextTick>, JobSchedulerError>> + Send>> {
+        let store = self.store.clone();
+        let collection = self.collection.clone();
+        Box::pin(async move {
+            let collection = match store.read().await.collection(&*collection) {
+                Some(collection) => collection,
+                None => return Err(JobSchedulerError::CantListGuids),
+            };
+            let documents = match collection.find(doc! {}, None).await {
+                Ok(cursor) => cursor.try_collect::<Vec<_>>().await,
+                Err(e) => Err(e),
+            };
+            match documents {
+                Ok(documents) => Ok(documents.iter().filter_map(job_and_next_tick).collect()),
+                Err(e) => {
+                    error!("Error listing jobs {:?}", e);
+                    Err(JobSchedulerError::CantListGuids)
+                }
+            }
+        })
+    }
+
+    fn count(&mut self) -> Pin<Box<dyn Future<Output = Result<usize, JobSchedulerError>> + Send>> {
+        let store = self.store.clone();
+        let collection = self.collection.clone();
+        Box::pin(async move {
+            let collection = match store.read().await.collection(&*collection) {
+                Some(collection) => collection,
+                None => return Err(JobSchedulerError::CantListGuids),
+            };
+            collection
+                .count_documents(doc! {}, None)
+                .await
+                .map(|count| count as usize)
+                .map_err(|e| {
+                    error!("Error counting jobs {:?}", e);
+                    JobSchedulerError::CantListGuids
+                })
+        })
+    }
+
+    fn set_next_and_last_tick(
+        &mut self,
+        guid: Uuid,
+        next_tick: Option<DateTime<Utc>>,
+        last_tick: Option<DateTime<Utc>>,
+    ) -> Pin<Box<dyn Future<Output = Result<(), JobSchedulerError>> + Send>> {
+        let store = self.store.clone();
+        let collection = self.collection.clone();
+        Box::pin(async move {
+            let collection = match store.read().await.collection(&*collection) {
+                Some(collection) => collection,
+                None => return Err(JobSchedulerError::UpdateJobData),
+            };
+            let next_tick = next_tick.map(|b| b.timestamp()).unwrap_or(0);
+            let last_tick = last_tick.map(|b| b.timestamp());
+            let update = doc! { "$set": { "next_tick": next_tick, "last_tick": last_tick } };
+            collection
+                .update_one(doc! { "_id": guid.to_string() }, update, None)
+                .await
+                .map(|_| ())
+                .map_err(|e| {
+                    error!("Error updating next and last tick {:?}", e);
+                    JobSchedulerError::UpdateJobData
+                })
+        })
+    }
+
+    fn time_till_next_job(
+        &mut self,
+    ) -> Pin<Box<dyn Future<Output = Result<Option<Duration>, JobSchedulerError>> + Send>> {
+        let store = self.store.clone();
+        let collection = self.collection.clone();
+        Box::pin(async move {
+            let collection = match store.read().await.collection(&*collection) {
+                Some(collection) => collection,
+                None => return Err(JobSchedulerError::CouldNotGetTimeUntilNextTick),
+            };
+            let now = Utc::now().timestamp();
+            let upcoming = doc! { "next_tick": { "$gt": now }, "stopped": { "$ne": true } };
+            let first = FindOneOptions::builder()
+                .sort(doc! { "next_tick": 1 })
+                .build();
+            match collection.find_one(upcoming, first).await {
+                Ok(document) => Ok(document
+                    .and_then(|d| d.get_i64("next_tick").ok())
+                    .map(|ts| ts - now)
+                    .filter(|ts| *ts > 0)
+                    .map(|ts| Duration::from_secs(ts as u64))),
+                Err(e) => {
+                    error!("Error getting time until next job {:?}", e);
+                    Err(JobSchedulerError::CouldNotGetTimeUntilNextTick)
+                }
+            }
+        })
+    }
+
+    fn next_job(
+        &mut self,
+    ) -> Pin<Box<dyn Future<Output = Result<Option<JobAndNextTick>, JobSchedulerError>> + Send>>
+    {
+        let store = self.store.clone();
+        let collection = self.collection.clone();
+        Box::pin(async move {
+            let collection = match store.read().await.collection(&*collection) {
+                Some(collection) => collection,
+                None => return Err(JobSchedulerError::CouldNotGetTimeUntilNextTick),
+            };
+            let scheduled = doc! { "next_tick": { "$gt": 0 }, "stopped": { "$ne": true } };
+            let first = FindOneOptions::builder()
+                .sort(doc! { "next_tick": 1 })
+                .build();
+            match collection.find_one(scheduled, first).await {
+                Ok(document) => Ok(document.as_ref().and_then(job_and_next_tick)),
+                Err(e) => {
+                    error!("Error getting the next job {:?}", e);
+                    Err(JobSchedulerError::CouldNotGetTimeUntilNextTick)
+                }
+            }
+        })
+    }
+}
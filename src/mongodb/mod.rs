@@ -0,0 +1,73 @@
+mod metadata_store;
+mod notification_store;
+
+use crate::JobSchedulerError;
+use mongodb::bson::Document;
+use mongodb::{Client, Collection, Database};
+use std::future::Future;
+use std::pin::Pin;
+use tracing::error;
+
+pub use metadata_store::MongoMetadataStore;
+pub use notification_store::MongoNotificationStore;
+
+const URL: &str = "mongodb://localhost:27017";
+const DATABASE: &str = "tokiocron";
+
+///
+/// A MongoDB database, shared between the metadata and notification store by putting it in the
+/// same `Arc<RwLock<..>>`.
+#[derive(Clone)]
+pub enum MongoStore {
+    Created { url: String, database: String },
+    Inited(Database),
+}
+
+impl MongoStore {
+    ///
+    /// A store for the database named `database` on the MongoDB deployment at `url`
+    pub fn new(url: &str, database: &str) -> Self {
+        Self::Created {
+            url: url.to_string(),
+            database: database.to_string(),
+        }
+    }
+
+    pub fn inited(&self) -> bool {
+        matches!(self, MongoStore::Inited(_))
+    }
+
+    pub(crate) fn collection(&self, name: &str) -> Option<Collection<Document>> {
+        match self {
+            MongoStore::Created { .. } => None,
+            MongoStore::Inited(database) => Some(database.collection(name)),
+        }
+    }
+}
+
+impl Default for MongoStore {
+    fn default() -> Self {
+        let url = std::env::var("MONGODB_URL").unwrap_or_else(|_| URL.to_string());
+        let database = std::env::var("MONGODB_DATABASE").unwrap_or_else(|_| DATABASE.to_string());
+        Self::Created { url, database }
+    }
+}
+
+impl MongoStore {
+    pub fn init(
+        self,
+    ) -> Pin<Box<dyn Future<Output = Result<MongoStore, JobSchedulerError>> + Send>> {
+        Box::pin(async move {
+            match self {
+                MongoStore::Created { url, database } => match Client::with_uri_str(&*url).await {
+                    Ok(client) => Ok(MongoStore::Inited(client.database(&*database))),
+                    Err(e) => {
+                        error!("Error connecting to MongoDB {:?}", e);
+                        Err(JobSchedulerError::StoreInit(e.to_string()))
+                    }
+                },
+                inited => Ok(inited),
+            }
+        })
+    }
+}
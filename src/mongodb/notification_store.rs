@@ -0,0 +1,299 @@
+use crate::job::job_data_prost::{JobIdAndNotification, JobState, NotificationData};
+use crate::job::{JobId, NotificationId};
+use crate::mongodb::MongoStore;
+use crate::store::{DataStore, InitStore, NotificationStore};
+use crate::JobSchedulerError;
+use futures::TryStreamExt;
+use mongodb::bson::spec::BinarySubtype;
+use mongodb::bson::{doc, Binary, Document};
+use mongodb::options::ReplaceOptions;
+use mongodb::IndexModel;
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::Arc;
+use tokio::sync::RwLock;
+use tracing::error;
+use uuid::Uuid;
+
+const COLLECTION: &str = "notification";
+
+///
+/// A MongoDB backed notification store.
+///
+/// Every notification is a document in `collection` with the notification's id as its `_id`, the
+/// id of its job and the states it's for. Initializing the store creates an index on `job_id`.
+#[derive(Clone)]
+pub struct MongoNotificationStore {
+    pub store: Arc<RwLock<MongoStore>>,
+    pub collection: String,
+}
+
+impl Default for MongoNotificationStore {
+    fn default() -> Self {
+        Self::new(Arc::new(RwLock::new(MongoStore::default())))
+    }
+}
+
+impl MongoNotificationStore {
+    ///
+    /// A notification store on `store`, which can be shared with a `MongoMetadataStore`
+    pub fn new(store: Arc<RwLock<MongoStore>>) -> Self {
+        let collection = std::env::var("MONGODB_NOTIFICATION_COLLECTION")
+            .unwrap_or_else(|_| COLLECTION.to_string());
+        Self { store, collection }
+    }
+}
+
+fn get_id(document: &Document, key: &str) -> Option<Uuid> {
+    match document.get_str(key).map(Uuid::parse_str) {
+        Ok(Ok(id)) => Some(id),
+        _ => {
+            error!("Document without a {} {:?}", key, document.get(key));
+            None
+        }
+    }
+}
+
+fn from_document(document: &Document) -> Option<NotificationData> {
+    let notification_id = get_id(document, "_id")?;
+    let job_id = get_id(document, "job_id")?;
+    let job_states = document
+        .get_array("states")
+        .map(|states| states.iter().filter_map(|s| s.as_i32()).collect())
+        .unwrap_or_default();
+    Some(NotificationData {
+        job_id: Some(JobIdAndNotification {
+            job_id: Some(job_id.into()),
+            notification_id: Some(notification_id.into()),
+        }),
+        job_states,
+        extra: document
+            .get_binary_generic("extra")
+            .cloned()
+            .unwrap_or_default(),
+    })
+}
+
+impl DataStore<NotificationData> for MongoNotificationStore {
+    fn get(
+        &mut self,
+        id: Uuid,
+    ) -> Pin<Box<dyn Future<Output = Result<Option<NotificationData>, JobSchedulerError>> + Send>>
+    {
+        let store = self.store.clone();
+        let collection = self.collection.clone();
+        Box::pin(async move {
+            let collection = match store.read().await.collection(&*collection) {
+                Some(collection) => collection,
+                None => return Err(JobSchedulerError::GetJobData),
+            };
+            match collection
+                .find_one(doc! { "_id": id.to_string() }, None)
+                .await
+            {
+                Ok(document) => Ok(document.as_ref().and_then(from_document)),
+                Err(e) => {
+                    error!("Error fetching notification data {:?}", e);
+                    Err(JobSchedulerError::GetJobData)
+                }
+            }
+        })
+    }
+
+    fn add_or_update(
+        &mut self,
+        data: NotificationData,
+    ) -> Pin<Box<dyn Future<Output = Result<(), JobSchedulerError>> + Send>> {
+        let store = self.store.clone();
+        let collection = self.collection.clone();
+        Box::pin(async move {
+            let collection = match store.read().await.collection(&*collection) {
+                Some(collection) => collection,
+                None => return Err(JobSchedulerError::UpdateJobData),
+            };
+            let (job_id, notification_id) = match data.job_id_and_notification_id_from_data() {
+                Some((job_id, notification_id)) => (job_id, notification_id),
+                None => return Err(JobSchedulerError::UpdateJobData),
+            };
+            let document = doc! {
+                "_id": notification_id.to_string(),
+                "job_id": job_id.to_string(),
+                "states": data.job_states,
+                "extra": Binary {
+                    subtype: BinarySubtype::Generic,
+                    bytes: data.extra,
+                },
+            };
+            let upsert = ReplaceOptions::builder().upsert(true).build();
+            collection
+                .replace_one(
+                    doc! { "_id": notification_id.to_string() },
+                    document,
+                    upsert,
+                )
+                .await
+                .map(|_| ())
+                .map_err(|e| {
+                    error!("Error doing the upsert {:?}", e);
+                    JobSchedulerError::UpdateJobData
+                })
+        })
+    }
+
+    fn delete(
+        &mut self,
+        guid: Uuid,
+    ) -> Pin<Box<dyn Future<Output = Result<(), JobSchedulerError>> + Send>> {
+        let store = self.store.clone();
+        let collection = self.collection.clone();
+        Box::pin(async move {
+            let collection = match store.read().await.collection(&*collection) {
+                Some(collection) => collection,
+                None => return Err(JobSchedulerError::CantRemove),
+            };
+            collection
+                .delete_one(doc! { "_id": guid.to_string() }, None)
+                .await
+                .map(|_| ())
+                .map_err(|e| {
+                    error!("Error deleting notification {:?}", e);
+                    JobSchedulerError::CantRemove
+                })
+        })
+    }
+}
+
+impl InitStore for MongoNotificationStore {
+    fn init(&mut self) -> Pin<Box<dyn Future<Output = Result<(), JobSchedulerError>> + Send>> {
+        let store = self.store.clone();
+        let collection = self.collection.clone();
+        Box::pin(async move {
+            let mut w = store.write().await;
+            let v = w.clone().init().await?;
+            if let Some(collection) = v.collection(&*collection) {
+                let index = IndexModel::builder().keys(doc! { "job_id": 1 }).build();
+                if let Err(e) = collection.create_index(index, None).await {
+                    error!("Error on init MongoDB Notification store {:?}", e);
+                    return Err(JobSchedulerError::StoreInit(e.to_string()));
+                }
+            }
+            *w = v;
+            Ok(())
+        })
+    }
+
+    fn inited(&mut self) -> Pin<Box<dyn Future<Output = Result<bool, JobSchedulerError>> + Send>> {
+        let store = self.store.clone();
+        Box::pin(async move {
+            let store = store.read().await;
+            Ok(store.inited())
+        })
+    }
+}
+
+impl NotificationStore for MongoNotificationStore {
+    fn list_notification_guids_for_job_and_state(
+        &mut self,
+        job: JobId,
+        state: JobState,
+    ) -> Pin<Box<dyn Future<Output = Result<Vec<NotificationId>, JobSchedulerError>> + Send>> {
+        let store = self.store.clone();
+        let collection = self.collection.clone();
+        Box::pin(async move {
+            let collection = match store.read().await.collection(&*collection) {
+                Some(collection) => collection,
+                None => return Err(JobSchedulerError::CantListGuids),
+            };
+            let filter = doc! { "job_id": job.to_string(), "states": state as i32 };
+            let documents = match collection.find(filter, None).await {
+                Ok(cursor) => cursor.try_collect::<Vec<_>>().await,
+                Err(e) => Err(e),
+            };
+            match documents {
+                Ok(documents) => Ok(documents.iter().filter_map(|d| get_id(d, "_id")).collect()),
+                Err(e) => {
+                    error!("Error listing notification guids for job and state {:?}", e);
+                    Err(JobSchedulerError::CantListGuids)
+                }
+            }
+        })
+    }
+
+    fn list_notification_guids_for_job_id(
+        &mut self,
+        job_id: Uuid,
+    ) -> Pin<Box<dyn Future<Output = Result<Vec<Uuid>, JobSchedulerError>> + Send>> {
+        let store = self.store.clone();
+        let collection = self.collection.clone();
+        Box::pin(async move {
+            let collection = match store.read().await.collection(&*collection) {
+                Some(collection) => collection,
+                None => return Err(JobSchedulerError::CantListGuids),
+            };
+            let documents = match collection
+                .find(doc! { "job_id": job_id.to_string() }, None)
+                .await
+            {
+                Ok(cursor) => cursor.try_collect::<Vec<_>>().await,
+                Err(e) => Err(e),
+            };
+            match documents {
+                Ok(documents) => Ok(documents.iter().filter_map(|d| get_id(d, "_id")).collect()),
+                Err(e) => {
+                    error!(
+                        "Error getting list of notifications guids for job id {:?}",
+                        e
+                    );
+                    Err(JobSchedulerError::CantListGuids)
+                }
+            }
+        })
+    }
+
+    fn delete_notification_for_state(
+        &mut self,
+        notification_id: Uuid,
+        state: JobState,
+    ) -> Pin<Box<dyn Future<Output = Result<bool, JobSchedulerError>> + Send>> {
+        let store = self.store.clone();
+        let collection = self.collection.clone();
+        Box::pin(async move {
+            let collection = match store.read().await.collection(&*collection) {
+                Some(collection) => collection,
+                None => return Err(JobSchedulerError::CantRemove),
+            };
+            let state = state as i32;
+            let filter = doc! { "_id": notification_id.to_string(), "states": state };
+            let update = doc! { "$pull": { "states": state } };
+            match collection.update_one(filter, update, None).await {
+                Ok(result) => Ok(result.modified_count > 0),
+                Err(e) => {
+                    error!("Error deleting notification for state {:?}", e);
+                    Err(JobSchedulerError::CantRemove)
+                }
+            }
+        })
+    }
+
+    fn delete_for_job(
+        &mut self,
+        job_id: Uuid,
+    ) -> Pin<Box<dyn Future<Output = Result<(), JobSchedulerError>> + Send>> {
+        let store = self.store.clone();
+        let collection = self.collection.clone();
+        Box::pin(async move {
+            let collection = match store.read().await.collection(&*collection) {
+                Some(collection) => collection,
+                None => return Err(JobSchedulerError::CantRemove),
+            };
+            collection
+                .delete_many(doc! { "job_id": job_id.to_string() }, None)
+                .await
+                .map(|_| ())
+                .map_err(|e| {
+                    error!("Error deleting for job {:?}", e);
+                    JobSchedulerError::CantRemove
+                })
+        })
+    }
+}
@@ -14,12 +14,27 @@ use uuid::Uuid;
 
 const LIST_NAME: &str = "TCS_JOB_LIST";
 const METADATA_PRE: &str = "META_";
+const MAX_METADATA_BYTES: usize = 1024 * 1024;
 
 ///
 /// A Nats KV store backed metadata store
-#[derive(Clone, Default)]
+#[derive(Clone)]
 pub struct NatsMetadataStore {
     pub store: NatsStore,
+    pub max_metadata_bytes: usize,
+}
+
+impl Default for NatsMetadataStore {
+    fn default() -> Self {
+        let max_metadata_bytes = std::env::var("NATS_MAX_METADATA_BYTES")
+            .ok()
+            .and_then(|s| s.parse().ok())
+            .unwrap_or(MAX_METADATA_BYTES);
+        Self {
+            store: NatsStore::default(),
+            max_metadata_bytes,
+        }
+    }
 }
 
 fn uuid_to_nats_id(uuid: Uuid) -> String {
@@ -52,6 +67,14 @@ impl DataStore<JobStoredData> for NatsMetadataStore {
     ) -> Pin<Box<dyn Future<Output = Result<(), JobSchedulerError>> + Send>> {
         let bucket = self.store.bucket.clone();
         let uuid: Uuid = data.id.as_ref().unwrap().into();
+        let size = data.encoded_len();
+        if size > self.max_metadata_bytes {
+            error!(
+                "Metadata of {:?} is {} bytes, more than the maximum of {}",
+                uuid, size, self.max_metadata_bytes
+            );
+            return Box::pin(async { Err(JobSchedulerError::MetadataTooLarge) });
+        }
         let get = self.get(uuid);
         let add_to_list = self.add_to_list_of_guids(uuid);
         Box::pin(async move {
@@ -112,6 +135,10 @@ impl InitStore for NatsMetadataStore {
 }
 
 impl MetaDataStorage for NatsMetadataStore {
+    fn is_shared(&self) -> bool {
+        true
+    }
+
     fn list_next_ticks(
         &mut self,
     ) -> Pin<Box<dyn Future<Output = Result<Vec<JobAndNextTick>, JobSchedulerError>> + Send>> {
@@ -140,12 +167,32 @@ impl MetaDataStorage for NatsMetadataStore {
                     job_type: jd.job_type,
                     next_tick: jd.next_tick,
                     last_tick: jd.last_tick,
+                    stopped: jd.stopped,
+                    expires_at: jd.expires_at,
+                    priority: jd.priority,
+                    boosted_priority: jd.boosted_priority,
+                    boost_until: jd.boost_until,
                 })
                 .collect::<Vec<_>>();
             Ok(list)
         })
     }
 
+    fn list_jobs(
+        &mut self,
+    ) -> Pin<Box<dyn Future<Output = Result<Vec<JobAndNextTick>, JobSchedulerError>> + Send>> {
+        // All jobs are listed as next ticks already
+        self.list_next_ticks()
+    }
+
+    fn count(&mut self) -> Pin<Box<dyn Future<Output = Result<usize, JobSchedulerError>> + Send>> {
+        let list_guids = self.list_guids();
+        Box::pin(async move {
+            let list = list_guids.await?;
+            Ok(list.uuids.len())
+        })
+    }
+
     fn set_next_and_last_tick(
         &mut self,
         guid: Uuid,
@@ -210,6 +257,7 @@ impl MetaDataStorage for NatsMetadataStore {
                 .flat_map(|uuid| bucket.get(&*uuid_to_nats_id(uuid)))
                 .flatten()
                 .flat_map(|b| JobStoredData::decode(b.as_slice()))
+                .filter(|jd| !jd.stopped)
                 .filter_map(|jd| match jd.next_tick {
                     0 => None,
                     i => {
@@ -94,7 +94,16 @@ impl NotificationCreator {
         job_states: Vec<JobState>,
         job_id: &Uuid,
     ) -> Result<Uuid, JobSchedulerError> {
-        let notification_id = Uuid::new_v4();
+        NotificationCreator::add_with_id(context, run, job_states, job_id, Uuid::new_v4()).await
+    }
+
+    pub async fn add_with_id(
+        context: &Context,
+        run: Box<OnJobNotification>,
+        job_states: Vec<JobState>,
+        job_id: &Uuid,
+        notification_id: Uuid,
+    ) -> Result<Uuid, JobSchedulerError> {
         let data = NotificationData {
             #[cfg(feature = "has_bytes")]
             job_id: Some(crate::job::job_data_prost::JobIdAndNotification {
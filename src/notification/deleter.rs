@@ -17,18 +17,23 @@ use tracing::error;
 pub struct NotificationDeleter {}
 
 impl NotificationDeleter {
+    /// Deletes the notifications of jobs once their metadata is deleted, however they were removed
     async fn listen_to_job_removals(
         storage: Arc<RwLock<Box<dyn NotificationStore + Send + Sync>>>,
-        mut rx_job_delete: Receiver<JobId>,
+        mut rx_job_deleted: Receiver<Result<JobId, (JobSchedulerError, Option<JobId>)>>,
         tx_notification_deleted: Sender<NotificationDeletedResult>,
     ) {
         loop {
-            let val = rx_job_delete.recv().await;
+            let val = rx_job_deleted.recv().await;
             if let Err(e) = val {
-                error!("Error receiving delete jobs {:?}", e);
+                error!("Error receiving deleted jobs {:?}", e);
                 break;
             }
-            let job_id = val.unwrap();
+            let job_id = match val.unwrap() {
+                Ok(job_id) => job_id,
+                // The job is still stored, so are its notifications
+                Err(_) => continue,
+            };
             let mut storage = storage.write().await;
             let guids = storage.list_notification_guids_for_job_id(job_id).await;
             if let Err(e) = guids {
@@ -94,7 +99,7 @@ impl NotificationDeleter {
         &mut self,
         context: &Context,
     ) -> Pin<Box<dyn Future<Output = Result<(), JobSchedulerError>> + Send>> {
-        let rx_job_delete = context.job_delete_tx.subscribe();
+        let rx_job_deleted = context.job_deleted_tx.subscribe();
         let rx_notification_delete = context.notify_delete_tx.subscribe();
         let tx_notification_deleted = context.notify_deleted_tx.clone();
         let storage = context.notification_storage.clone();
@@ -102,7 +107,7 @@ impl NotificationDeleter {
         Box::pin(async move {
             tokio::spawn(NotificationDeleter::listen_to_job_removals(
                 storage.clone(),
-                rx_job_delete,
+                rx_job_deleted,
                 tx_notification_deleted.clone(),
             ));
             tokio::spawn(NotificationDeleter::listen_for_notification_removals(
@@ -4,12 +4,14 @@ use crate::job::job_data::JobState;
 #[cfg(feature = "has_bytes")]
 use crate::job::job_data_prost::JobState;
 use crate::job::to_code::NotificationCode;
+use crate::job::JobEvent;
 use crate::store::NotificationStore;
 use crate::JobSchedulerError;
+use chrono::Utc;
 use std::future::Future;
 use std::pin::Pin;
 use std::sync::Arc;
-use tokio::sync::broadcast::Receiver;
+use tokio::sync::broadcast::{Receiver, Sender};
 use tokio::sync::RwLock;
 use tracing::error;
 use uuid::Uuid;
@@ -22,6 +24,7 @@ impl NotificationRunner {
         code: Arc<RwLock<Box<dyn NotificationCode + Send + Sync>>>,
         mut rx: Receiver<(Uuid, JobState)>,
         storage: Arc<RwLock<Box<dyn NotificationStore + Send + Sync>>>,
+        event_tx: Sender<JobEvent>,
     ) {
         loop {
             let val = rx.recv().await;
@@ -30,6 +33,12 @@ impl NotificationRunner {
                 break;
             }
             let (job_id, state) = val.unwrap();
+            // Without subscribers there's nobody to miss the event
+            let _ = event_tx.send(JobEvent {
+                job_id,
+                kind: state,
+                at: Utc::now(),
+            });
             let mut storage = storage.write().await;
             let notifications = storage
                 .list_notification_guids_for_job_and_state(job_id, state)
@@ -72,10 +81,11 @@ impl NotificationRunner {
         let code = context.notification_code.clone();
         let rx = context.notify_tx.subscribe();
         let storage = context.notification_storage.clone();
+        let event_tx = context.event_tx.clone();
 
         Box::pin(async move {
             tokio::spawn(NotificationRunner::listen_for_activations(
-                code, rx, storage,
+                code, rx, storage, event_tx,
             ));
             Ok(())
         })
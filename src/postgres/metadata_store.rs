@@ -3,22 +3,25 @@ use crate::postgres::PostgresStore;
 use crate::store::{DataStore, InitStore, MetaDataStorage};
 use crate::{JobAndNextTick, JobSchedulerError, JobStoredData, JobUuid};
 use chrono::{DateTime, Utc};
+use prost::Message;
 use std::future::Future;
 use std::pin::Pin;
 use std::sync::Arc;
 use std::time::Duration;
 use tokio::sync::RwLock;
-use tokio_postgres::Row;
+use tokio_postgres::{GenericClient, Row};
 use tracing::error;
 use uuid::Uuid;
 
 const TABLE: &str = "job";
+const MAX_METADATA_BYTES: usize = 1024 * 1024;
 
 #[derive(Clone)]
 pub struct PostgresMetadataStore {
     pub store: Arc<RwLock<PostgresStore>>,
     pub init_tables: bool,
     pub table: String,
+    pub max_metadata_bytes: usize,
 }
 
 impl Default for PostgresMetadataStore {
@@ -28,15 +31,145 @@ impl Default for PostgresMetadataStore {
             .unwrap_or_default();
         let table =
             std::env::var("POSTGRES_METADATA_TABLE").unwrap_or_else(|_| TABLE.to_lowercase());
+        let max_metadata_bytes = std::env::var("POSTGRES_MAX_METADATA_BYTES")
+            .ok()
+            .and_then(|s| s.parse().ok())
+            .unwrap_or(MAX_METADATA_BYTES);
         let store = Arc::new(RwLock::new(PostgresStore::default()));
         Self {
             init_tables,
             table,
             store,
+            max_metadata_bytes,
         }
     }
 }
 
+///
+/// Insert a job's metadata, or update it when the job is stored already
+async fn upsert<C>(client: &C, table: &str, data: JobStoredData) -> Result<(), JobSchedulerError>
+where
+    C: GenericClient + Sync,
+{
+    use crate::job::job_data_prost::job_stored_data::Job::CronJob as CronJobType;
+    use crate::job::job_data_prost::job_stored_data::Job::NonCronJob as NonCronJobType;
+
+    let uuid: Uuid = data.id.as_ref().unwrap().into();
+    let sql = "INSERT INTO ".to_string()
+        + &*table
+        + " (\
+        id, last_updated, next_tick, job_type, count, \
+        ran, stopped, schedule, repeating, repeated_every, \
+        extra, last_tick, anchor, timeout_ms, skip_overlapping, \
+        retries, retry_backoff_ms, timezone, jitter_ms, tags, misfire_policy, max_runs, \
+        dst_gap, dst_overlap, expires_at, priority, active_from, boosted_priority, boost_until \
+    )\
+    VALUES (\
+        $1, $2, $3, $4, $5, \
+        $6, $7, $8, $9, $10,\
+        $11, $12, $13, $14, $15, \
+        $16, $17, $18, $19, $20, \
+        $21, $22, $23, $24, $25, \
+        $26, $27, $28, $29 \
+    )\
+    ON CONFLICT (id) \
+    DO \
+        UPDATE \
+        SET \
+            last_updated=$2, next_tick=$3, job_type=$4, count=$5, \
+            ran=$6, stopped=$7, schedule=$8, repeating=$9, repeated_every=$10, \
+            extra=$11, last_tick=$12, anchor=$13, timeout_ms=$14, \
+            skip_overlapping=$15, retries=$16, retry_backoff_ms=$17, timezone=$18, \
+            jitter_ms=$19, tags=$20, misfire_policy=$21, max_runs=$22, \
+            dst_gap=$23, dst_overlap=$24, expires_at=$25, priority=$26, \
+            active_from=$27, boosted_priority=$28, boost_until=$29
+    ";
+    let last_updated = data.last_updated.as_ref().map(|i| *i as i64);
+    let next_tick = data.next_tick as i64;
+    let job_type = data.job_type;
+    let count = data.count as i32;
+    let ran = data.ran;
+    let stopped = data.stopped;
+    let schedule = match data.job.as_ref() {
+        Some(CronJobType(ct)) => Some(ct.schedule.clone()),
+        _ => None,
+    };
+    let timezone = match data.job.as_ref() {
+        Some(CronJobType(ct)) if !ct.timezone.is_empty() => Some(ct.timezone.clone()),
+        _ => None,
+    };
+    let repeating = match data.job.as_ref() {
+        Some(NonCronJobType(ct)) => Some(ct.repeating),
+        _ => None,
+    };
+    let repeated_every = match data.job.as_ref() {
+        Some(NonCronJobType(ct)) => Some(ct.repeated_every as i64),
+        _ => None,
+    };
+    let anchor = match data.job.as_ref() {
+        Some(NonCronJobType(ct)) => ct.anchor.map(|a| a as i64),
+        _ => None,
+    };
+    let extra = data.extra;
+    let last_tick = data.last_tick.as_ref().map(|i| *i as i64);
+    let timeout_ms = data.timeout_ms.map(|t| t as i64);
+    let skip_overlapping = data.skip_overlapping;
+    let retries = data.retries as i32;
+    let retry_backoff_ms = data.retry_backoff_ms as i64;
+    let jitter_ms = data.jitter_ms as i64;
+    let max_runs = data.max_runs.map(|m| m as i32);
+    let (dst_gap, dst_overlap) = match data.job.as_ref() {
+        Some(CronJobType(ct)) => (Some(ct.dst_gap), Some(ct.dst_overlap)),
+        _ => (None, None),
+    };
+    let expires_at = data.expires_at.map(|e| e as i64);
+    let active_from = data.active_from.map(|a| a as i64);
+    let boost_until = data.boost_until.map(|b| b as i64);
+
+    let val = client
+        .query(
+            &*sql,
+            &[
+                &uuid,
+                &last_updated,
+                &next_tick,
+                &job_type,
+                &count,
+                &ran,
+                &stopped,
+                &schedule,
+                &repeating,
+                &repeated_every,
+                &extra,
+                &last_tick,
+                &anchor,
+                &timeout_ms,
+                &skip_overlapping,
+                &retries,
+                &retry_backoff_ms,
+                &timezone,
+                &jitter_ms,
+                &data.tags,
+                &data.misfire_policy,
+                &max_runs,
+                &dst_gap,
+                &dst_overlap,
+                &expires_at,
+                &data.priority,
+                &active_from,
+                &data.boosted_priority,
+                &boost_until,
+            ],
+        )
+        .await;
+    if let Err(e) = val {
+        error!("Error {:?}", e);
+        Err(JobSchedulerError::CantAdd)
+    } else {
+        Ok(())
+    }
+}
+
 impl DataStore<JobStoredData> for PostgresMetadataStore {
     fn get(
         &mut self,
@@ -54,18 +187,20 @@ impl DataStore<JobStoredData> for PostgresMetadataStore {
                     let sql = "select \
                         id, last_updated, next_tick, last_tick, job_type, count, \
                         ran, stopped, schedule, repeating, repeated_every, \
-                        extra \
+                        extra, anchor, timeout_ms, skip_overlapping, retries, retry_backoff_ms, \
+                        timezone, jitter_ms, tags, misfire_policy, max_runs, dst_gap, dst_overlap, expires_at, \
+                        priority, active_from, boosted_priority, boost_until \
                      from "
                         .to_string()
                         + &*table
                         + " where id = $1 limit 1";
-                    let row = store.query_one(&*sql, &[&id]).await;
+                    let row = store.query_opt(&*sql, &[&id]).await;
                     if let Err(e) = row {
                         error!("Error getting value {:?}", e);
                         return Err(JobSchedulerError::GetJobData);
                     }
                     let row = row.unwrap();
-                    Ok(Some(row.into()))
+                    Ok(row.map(|row| row.into()))
                 }
             }
         })
@@ -75,84 +210,23 @@ impl DataStore<JobStoredData> for PostgresMetadataStore {
         &mut self,
         data: JobStoredData,
     ) -> Pin<Box<dyn Future<Output = Result<(), JobSchedulerError>> + Send>> {
+        let size = data.encoded_len();
+        if size > self.max_metadata_bytes {
+            error!(
+                "Metadata of {:?} is {} bytes, more than the maximum of {}",
+                data.id, size, self.max_metadata_bytes
+            );
+            return Box::pin(async { Err(JobSchedulerError::MetadataTooLarge) });
+        }
         let store = self.store.clone();
         let table = self.table.clone();
         Box::pin(async move {
-            use crate::job::job_data_prost::job_stored_data::Job::CronJob as CronJobType;
-            use crate::job::job_data_prost::job_stored_data::Job::NonCronJob as NonCronJobType;
-
             let store = store.read().await;
             match &*store {
                 PostgresStore::Created(_) => Err(JobSchedulerError::UpdateJobData),
                 PostgresStore::Inited(store) => {
-                    let uuid: Uuid = data.id.as_ref().unwrap().into();
                     let store = store.read().await;
-                    let sql = "INSERT INTO ".to_string()
-                        + &*table
-                        + " (\
-                        id, last_updated, next_tick, job_type, count, \
-                        ran, stopped, schedule, repeating, repeated_every, \
-                        extra, last_tick \
-                    )\
-                    VALUES (\
-                        $1, $2, $3, $4, $5, \
-                        $6, $7, $8, $9, $10,\
-                        $11, $12 \
-                    )\
-                    ON CONFLICT (id) \
-                    DO \
-                        UPDATE \
-                        SET \
-                            last_updated=$2, next_tick=$3, job_type=$4, count=$5, \
-                            ran=$6, stopped=$7, schedule=$8, repeating=$9, repeated_every=$10, \
-                            extra=$11, last_tick=$12
-                    ";
-                    let last_updated = data.last_updated.as_ref().map(|i| *i as i64);
-                    let next_tick = data.next_tick as i64;
-                    let job_type = data.job_type;
-                    let count = data.count as i32;
-                    let ran = data.ran;
-                    let stopped = data.stopped;
-                    let schedule = match data.job.as_ref() {
-                        Some(CronJobType(ct)) => Some(ct.schedule.clone()),
-                        _ => None,
-                    };
-                    let repeating = match data.job.as_ref() {
-                        Some(NonCronJobType(ct)) => Some(ct.repeating),
-                        _ => None,
-                    };
-                    let repeated_every = match data.job.as_ref() {
-                        Some(NonCronJobType(ct)) => Some(ct.repeated_every as i64),
-                        _ => None,
-                    };
-                    let extra = data.extra;
-                    let last_tick = data.last_tick.as_ref().map(|i| *i as i64);
-
-                    let val = store
-                        .query(
-                            &*sql,
-                            &[
-                                &uuid,
-                                &last_updated,
-                                &next_tick,
-                                &job_type,
-                                &count,
-                                &ran,
-                                &stopped,
-                                &schedule,
-                                &repeating,
-                                &repeated_every,
-                                &extra,
-                                &last_tick,
-                            ],
-                        )
-                        .await;
-                    if let Err(e) = val {
-                        error!("Error {:?}", e);
-                        Err(JobSchedulerError::CantAdd)
-                    } else {
-                        Ok(())
-                    }
+                    upsert(&*store, &table, data).await
                 }
             }
         })
@@ -186,12 +260,43 @@ impl DataStore<JobStoredData> for PostgresMetadataStore {
     }
 }
 
+fn job_and_next_tick(row: &Row) -> JobAndNextTick {
+    let id: Uuid = row.get(0);
+    let id: JobUuid = id.into();
+    let job_type = row.get(1);
+    let next_tick = row
+        .try_get(2)
+        .ok()
+        .map(|i: i64| i as u64)
+        .unwrap_or_default();
+    let last_tick = row.try_get(3).ok().map(|i: i64| i as u64);
+    let stopped = row.try_get(4).unwrap_or_default();
+    let expires_at = row.try_get(5).ok().map(|i: i64| i as u64);
+    let priority = row.try_get(6).unwrap_or_default();
+    let boosted_priority = row.try_get(7).ok().flatten();
+    let boost_until = row.try_get(8).ok().map(|i: i64| i as u64);
+
+    JobAndNextTick {
+        id: Some(id),
+        job_type,
+        next_tick,
+        last_tick,
+        stopped,
+        expires_at,
+        priority,
+        boosted_priority,
+        boost_until,
+    }
+}
+
 impl From<Row> for JobStoredData {
     fn from(row: Row) -> Self {
         /*
         id, last_updated, next_tick, last_tick, job_type, count, \
                         ran, stopped, schedule, repeating, repeated_every, \
-                        extra
+                        extra, anchor, timeout_ms, skip_overlapping, retries, retry_backoff_ms, \
+                        timezone, jitter_ms, tags, misfire_policy, max_runs, dst_gap, dst_overlap, expires_at,
+                        priority, active_from, boosted_priority, boost_until
          */
         let id: Uuid = row.get(0);
         let last_updated = row.try_get(1).ok().map(|i: i64| i as u64);
@@ -213,7 +318,29 @@ impl From<Row> for JobStoredData {
             let job_type = JobType::from_i32(job_type);
             match job_type {
                 Some(JobType::Cron) => match row.try_get(8) {
-                    Ok(schedule) => Some(CronJobType(CronJob { schedule })),
+                    Ok(schedule) => {
+                        let timezone = row
+                            .try_get::<_, Option<String>>(17)
+                            .ok()
+                            .flatten()
+                            .unwrap_or_default();
+                        let dst_gap = row
+                            .try_get::<_, Option<i32>>(22)
+                            .ok()
+                            .flatten()
+                            .unwrap_or_default();
+                        let dst_overlap = row
+                            .try_get::<_, Option<i32>>(23)
+                            .ok()
+                            .flatten()
+                            .unwrap_or_default();
+                        Some(CronJobType(CronJob {
+                            schedule,
+                            timezone,
+                            dst_gap,
+                            dst_overlap,
+                        }))
+                    }
                     _ => None,
                 },
                 Some(_) => {
@@ -223,15 +350,34 @@ impl From<Row> for JobStoredData {
                         .ok()
                         .map(|i: i64| i as u64)
                         .unwrap_or_default();
+                    let anchor = row.try_get(12).ok().map(|i: i64| i as u64);
                     Some(NonCronJobType(NonCronJob {
                         repeating,
                         repeated_every,
+                        anchor,
                     }))
                 }
                 None => None,
             }
         };
         let extra = row.try_get(11).unwrap_or_default();
+        let timeout_ms = row.try_get(13).ok().map(|i: i64| i as u64);
+        let skip_overlapping = row.try_get(14).unwrap_or_default();
+        let retries = row.try_get(15).map(|i: i32| i as u32).unwrap_or_default();
+        let retry_backoff_ms = row.try_get(16).map(|i: i64| i as u64).unwrap_or_default();
+        let jitter_ms = row.try_get(18).map(|i: i64| i as u64).unwrap_or_default();
+        let tags = row
+            .try_get::<_, Option<Vec<String>>>(19)
+            .ok()
+            .flatten()
+            .unwrap_or_default();
+        let misfire_policy = row.try_get(20).unwrap_or_default();
+        let max_runs = row.try_get(21).ok().map(|i: i32| i as u32);
+        let expires_at = row.try_get(24).ok().map(|i: i64| i as u64);
+        let priority = row.try_get(25).unwrap_or_default();
+        let active_from = row.try_get(26).ok().map(|i: i64| i as u64);
+        let boosted_priority = row.try_get(27).ok().flatten();
+        let boost_until = row.try_get(28).ok().map(|i: i64| i as u64);
 
         Self {
             id: Some(id.into()),
@@ -243,6 +389,19 @@ impl From<Row> for JobStoredData {
             extra,
             ran,
             stopped,
+            timeout_ms,
+            skip_overlapping,
+            retries,
+            retry_backoff_ms,
+            jitter_ms,
+            tags,
+            misfire_policy,
+            max_runs,
+            expires_at,
+            priority,
+            active_from,
+            boosted_priority,
+            boost_until,
             job,
         }
     }
@@ -280,12 +439,54 @@ impl InitStore for PostgresMetadataStore {
                                             repeating BOOL,\
                                             repeated_every BIGINT,\
                                             extra BYTEA,
+                                            anchor BIGINT,
+                                            timeout_ms BIGINT,
+                                            skip_overlapping BOOL,
+                                            retries INTEGER,
+                                            retry_backoff_ms BIGINT,
+                                            timezone TEXT,
+                                            jitter_ms BIGINT,
+                                            tags TEXT[],
+                                            misfire_policy INTEGER,
+                                            max_runs INTEGER,
+                                            dst_gap INTEGER,
+                                            dst_overlap INTEGER,
+                                            expires_at BIGINT,
+                                            priority INTEGER,
+                                            active_from BIGINT,
+                                            boosted_priority INTEGER,
+                                            boost_until BIGINT,
                                             CONSTRAINT pk_metadata PRIMARY KEY (id)
                                         )";
                                 let create = v.execute(&*sql, &[]).await;
                                 if let Err(e) = create {
                                     error!("Error on init Postgres Metadata store {:?}", e);
-                                    return Err(JobSchedulerError::CantInit);
+                                    return Err(JobSchedulerError::StoreInit(e.to_string()));
+                                }
+                                // Tables created by older versions lack the newer columns
+                                let sql = "ALTER TABLE ".to_string()
+                                    + &*table
+                                    + " ADD COLUMN IF NOT EXISTS anchor BIGINT, \
+                                        ADD COLUMN IF NOT EXISTS timeout_ms BIGINT, \
+                                        ADD COLUMN IF NOT EXISTS skip_overlapping BOOL, \
+                                        ADD COLUMN IF NOT EXISTS retries INTEGER, \
+                                        ADD COLUMN IF NOT EXISTS retry_backoff_ms BIGINT, \
+                                        ADD COLUMN IF NOT EXISTS timezone TEXT, \
+                                        ADD COLUMN IF NOT EXISTS jitter_ms BIGINT, \
+                                        ADD COLUMN IF NOT EXISTS tags TEXT[], \
+                                        ADD COLUMN IF NOT EXISTS misfire_policy INTEGER, \
+                                        ADD COLUMN IF NOT EXISTS max_runs INTEGER, \
+                                        ADD COLUMN IF NOT EXISTS dst_gap INTEGER, \
+                                        ADD COLUMN IF NOT EXISTS dst_overlap INTEGER, \
+                                        ADD COLUMN IF NOT EXISTS expires_at BIGINT, \
+                                        ADD COLUMN IF NOT EXISTS priority INTEGER, \
+                                        ADD COLUMN IF NOT EXISTS active_from BIGINT, \
+                                        ADD COLUMN IF NOT EXISTS boosted_priority INTEGER, \
+                                        ADD COLUMN IF NOT EXISTS boost_until BIGINT";
+                                let alter = v.execute(&*sql, &[]).await;
+                                if let Err(e) = alter {
+                                    error!("Error on migrating Postgres Metadata store {:?}", e);
+                                    return Err(JobSchedulerError::StoreInit(e.to_string()));
                                 }
                             }
                         }
@@ -313,6 +514,10 @@ impl InitStore for PostgresMetadataStore {
 }
 
 impl MetaDataStorage for PostgresMetadataStore {
+    fn is_shared(&self) -> bool {
+        true
+    }
+
     fn list_next_ticks(
         &mut self,
     ) -> Pin<Box<dyn Future<Output = Result<Vec<JobAndNextTick>, JobSchedulerError>> + Send>> {
@@ -327,37 +532,18 @@ impl MetaDataStorage for PostgresMetadataStore {
                     let store = store.read().await;
                     let now = Utc::now().timestamp();
                     let sql = "SELECT \
-                            id, job_type, next_tick, last_tick \
+                            id, job_type, next_tick, last_tick, stopped, expires_at, priority, \
+                            boosted_priority, boost_until \
                         FROM "
                         .to_string()
                         + &*table
                         + " \
                         WHERE \
-                              next_tick > 0 \
-                          AND next_tick < $1";
+                              (next_tick > 0 AND next_tick <= $1) \
+                           OR expires_at <= $1";
                     let rows = store.query(&*sql, &[&now]).await;
                     match rows {
-                        Ok(rows) => Ok(rows
-                            .iter()
-                            .map(|row| {
-                                let id: Uuid = row.get(0);
-                                let id: JobUuid = id.into();
-                                let job_type = row.get(1);
-                                let next_tick = row
-                                    .try_get(2)
-                                    .ok()
-                                    .map(|i: i64| i as u64)
-                                    .unwrap_or_default();
-                                let last_tick = row.try_get(3).ok().map(|i: i64| i as u64);
-
-                                JobAndNextTick {
-                                    id: Some(id),
-                                    job_type,
-                                    next_tick,
-                                    last_tick,
-                                }
-                            })
-                            .collect::<Vec<_>>()),
+                        Ok(rows) => Ok(rows.iter().map(job_and_next_tick).collect::<Vec<_>>()),
                         Err(e) => {
                             error!("Error getting next ticks {:?}", e);
                             Err(JobSchedulerError::CantListNextTicks)
@@ -368,6 +554,133 @@ impl MetaDataStorage for PostgresMetadataStore {
         })
     }
 
+    fn list_jobs(
+        &mut self,
+    ) -> Pin<Box<dyn Future<Output = Result<Vec<JobAndNextTick>, JobSchedulerError>> + Send>> {
+        let store = self.store.clone();
+        let table = self.table.clone();
+
+        Box::pin(async move {
+            let store = store.read().await;
+            match &*store {
+                PostgresStore::Created(_) => Err(JobSchedulerError::CantListGuids),
+                PostgresStore::Inited(store) => {
+                    let store = store.read().await;
+                    let sql =
+                        "SELECT id, job_type, next_tick, last_tick, stopped, expires_at, priority, \
+                        boosted_priority, boost_until FROM "
+                            .to_string() + &*table;
+                    let rows = store.query(&*sql, &[]).await;
+                    match rows {
+                        Ok(rows) => Ok(rows.iter().map(job_and_next_tick).collect::<Vec<_>>()),
+                        Err(e) => {
+                            error!("Error listing jobs {:?}", e);
+                            Err(JobSchedulerError::CantListGuids)
+                        }
+                    }
+                }
+            }
+        })
+    }
+
+    fn add_all(
+        &mut self,
+        jobs: Vec<JobStoredData>,
+    ) -> Pin<Box<dyn Future<Output = Result<(), JobSchedulerError>> + Send>> {
+        if let Some(data) = jobs
+            .iter()
+            .find(|data| data.encoded_len() > self.max_metadata_bytes)
+        {
+            error!(
+                "Metadata of {:?} is {} bytes, more than the maximum of {}",
+                data.id,
+                data.encoded_len(),
+                self.max_metadata_bytes
+            );
+            return Box::pin(async { Err(JobSchedulerError::MetadataTooLarge) });
+        }
+        let store = self.store.clone();
+        let table = self.table.clone();
+        Box::pin(async move {
+            let store = store.read().await;
+            match &*store {
+                PostgresStore::Created(_) => Err(JobSchedulerError::UpdateJobData),
+                PostgresStore::Inited(store) => {
+                    let mut store = store.write().await;
+                    let transaction = store.transaction().await.map_err(|e| {
+                        error!("Error starting a transaction {:?}", e);
+                        JobSchedulerError::CantAdd
+                    })?;
+                    // Dropping the transaction on an error rolls it back
+                    for data in jobs {
+                        upsert(&transaction, &table, data).await?;
+                    }
+                    transaction.commit().await.map_err(|e| {
+                        error!("Error committing the jobs {:?}", e);
+                        JobSchedulerError::CantAdd
+                    })
+                }
+            }
+        })
+    }
+
+    fn delete_all(
+        &mut self,
+        guids: Vec<Uuid>,
+    ) -> Pin<Box<dyn Future<Output = Result<(), JobSchedulerError>> + Send>> {
+        let store = self.store.clone();
+        let table = self.table.clone();
+        Box::pin(async move {
+            let store = store.read().await;
+            match &*store {
+                PostgresStore::Created(_) => Err(JobSchedulerError::CantRemove),
+                PostgresStore::Inited(store) => {
+                    let mut store = store.write().await;
+                    let transaction = store.transaction().await.map_err(|e| {
+                        error!("Error starting a transaction {:?}", e);
+                        JobSchedulerError::CantRemove
+                    })?;
+                    let sql = "DELETE FROM ".to_string() + &*table + " WHERE id = $1";
+                    // Dropping the transaction on an error rolls it back
+                    for guid in guids {
+                        if let Err(e) = transaction.execute(&*sql, &[&guid]).await {
+                            error!("Error deleting job data {:?}", e);
+                            return Err(JobSchedulerError::CantRemove);
+                        }
+                    }
+                    transaction.commit().await.map_err(|e| {
+                        error!("Error committing the deletes {:?}", e);
+                        JobSchedulerError::CantRemove
+                    })
+                }
+            }
+        })
+    }
+
+    fn count(&mut self) -> Pin<Box<dyn Future<Output = Result<usize, JobSchedulerError>> + Send>> {
+        let store = self.store.clone();
+        let table = self.table.clone();
+
+        Box::pin(async move {
+            let store = store.read().await;
+            match &*store {
+                PostgresStore::Created(_) => Err(JobSchedulerError::CantListGuids),
+                PostgresStore::Inited(store) => {
+                    let store = store.read().await;
+                    let sql = "SELECT COUNT(*) FROM ".to_string() + &*table;
+                    let row = store.query_one(&*sql, &[]).await;
+                    match row {
+                        Ok(row) => Ok(row.get::<_, i64>(0) as usize),
+                        Err(e) => {
+                            error!("Error counting jobs {:?}", e);
+                            Err(JobSchedulerError::CantListGuids)
+                        }
+                    }
+                }
+            }
+        })
+    }
+
     fn set_next_and_last_tick(
         &mut self,
         guid: Uuid,
@@ -423,8 +736,9 @@ impl MetaDataStorage for PostgresMetadataStore {
                         + &*table
                         + " \
                         WHERE \
-                              next_tick > 0\
-                          AND next_tick > $2 \
+                              next_tick > 0 \
+                          AND next_tick > $1 \
+                          AND stopped IS NOT TRUE \
                         ORDER BY next_tick ASC \
                         LIMIT 1";
                     let row = store.query(&*sql, &[&now]).await;
@@ -444,4 +758,40 @@ impl MetaDataStorage for PostgresMetadataStore {
             }
         })
     }
+
+    fn next_job(
+        &mut self,
+    ) -> Pin<Box<dyn Future<Output = Result<Option<JobAndNextTick>, JobSchedulerError>> + Send>>
+    {
+        let store = self.store.clone();
+        let table = self.table.clone();
+        Box::pin(async move {
+            let store = store.read().await;
+            match &*store {
+                PostgresStore::Created(_) => Err(JobSchedulerError::CouldNotGetTimeUntilNextTick),
+                PostgresStore::Inited(store) => {
+                    let store = store.read().await;
+                    let sql = "SELECT \
+                            id, job_type, next_tick, last_tick, stopped, expires_at, priority, \
+                            boosted_priority, boost_until \
+                        FROM "
+                        .to_string()
+                        + &*table
+                        + " \
+                        WHERE \
+                              next_tick > 0 \
+                          AND stopped IS NOT TRUE \
+                        ORDER BY next_tick ASC \
+                        LIMIT 1";
+                    match store.query(&*sql, &[]).await {
+                        Ok(rows) => Ok(rows.get(0).map(job_and_next_tick)),
+                        Err(e) => {
+                            error!("Error getting the next job {:?}", e);
+                            Err(JobSchedulerError::CouldNotGetTimeUntilNextTick)
+                        }
+                    }
+                }
+            }
+        })
+    }
 }
@@ -87,7 +87,7 @@ impl PostgresStore {
                     let connect = tokio_postgres::connect(&*url, tls).await;
                     if let Err(e) = connect {
                         error!("Error connecting to postgres {:?}", e);
-                        return Err(JobSchedulerError::CantInit);
+                        return Err(JobSchedulerError::StoreInit(e.to_string()));
                     }
                     let (client, connection) = connect.unwrap();
                     tokio::spawn(async move {
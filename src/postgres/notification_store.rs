@@ -226,7 +226,7 @@ impl InitStore for PostgresNotificationStore {
                                 let create = v.query(&*sql, &[]).await;
                                 if let Err(e) = create {
                                     error!("Error creating notification table {:?}", e);
-                                    return Err(JobSchedulerError::CantInit);
+                                    return Err(JobSchedulerError::StoreInit(e.to_string()));
                                 }
                                 let sql = "CREATE TABLE IF NOT EXISTS ".to_string()
                                     + &*states_table
@@ -241,7 +241,7 @@ impl InitStore for PostgresNotificationStore {
                                 let create = v.query(&*sql, &[]).await;
                                 if let Err(e) = create {
                                     error!("Error creating notification states table {:?}", e);
-                                    return Err(JobSchedulerError::CantInit);
+                                    return Err(JobSchedulerError::StoreInit(e.to_string()));
                                 }
                             }
                         }
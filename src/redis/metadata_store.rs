@@ -0,0 +1,476 @@
+use crate::redis::RedisStore;
+use crate::store::{DataStore, InitStore, MetaDataStorage};
+use crate::{JobAndNextTick, JobSchedulerError, JobStoredData};
+use chrono::{DateTime, Utc};
+use prost::Message;
+use redis::aio::ConnectionManager;
+use redis::RedisResult;
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::sync::RwLock;
+use tracing::error;
+use uuid::Uuid;
+
+const MAX_METADATA_BYTES: usize = 1024 * 1024;
+const TICK_LOCK_MS: u64 = 60_000;
+
+///
+/// A Redis backed metadata store.
+///
+/// Every job is kept as its encoded `JobStoredData` under `{prefix}:job:{id}`. The sorted set
+/// `{prefix}:next_ticks` holds all jobs scored on their next tick, `{prefix}:active_ticks` only
+/// the ones that aren't paused.
+///
+/// When several schedulers share a Redis instance, `list_next_ticks` only hands out a due tick
+/// to the instance that claimed it first, so a tick isn't run twice. Claims expire after
+/// `tick_lock_ms`.
+#[derive(Clone)]
+pub struct RedisMetadataStore {
+    pub store: Arc<RwLock<RedisStore>>,
+    pub max_metadata_bytes: usize,
+    pub tick_lock_ms: u64,
+    owner: String,
+}
+
+impl Default for RedisMetadataStore {
+    fn default() -> Self {
+        Self::new(Arc::new(RwLock::new(RedisStore::default())))
+    }
+}
+
+impl RedisMetadataStore {
+    ///
+    /// A metadata store on `store`, which can be shared with a `RedisNotificationStore`
+    pub fn new(store: Arc<RwLock<RedisStore>>) -> Self {
+        let max_metadata_bytes = std::env::var("REDIS_MAX_METADATA_BYTES")
+            .ok()
+            .and_then(|s| s.parse().ok())
+            .unwrap_or(MAX_METADATA_BYTES);
+        let tick_lock_ms = std::env::var("REDIS_TICK_LOCK_MS")
+            .ok()
+            .and_then(|s| s.parse().ok())
+            .unwrap_or(TICK_LOCK_MS);
+        Self {
+            store,
+            max_metadata_bytes,
+            tick_lock_ms,
+            owner: Uuid::new_v4().to_string(),
+        }
+    }
+}
+
+fn job_key(prefix: &str, id: &Uuid) -> String {
+    format!("{}:job:{}", prefix, id)
+}
+
+fn next_ticks_key(prefix: &str) -> String {
+    format!("{}:next_ticks", prefix)
+}
+
+fn active_ticks_key(prefix: &str) -> String {
+    format!("{}:active_ticks", prefix)
+}
+
+fn tick_lock_key(prefix: &str, id: &Uuid, next_tick: u64) -> String {
+    format!("{}:lock:{}:{}", prefix, id, next_tick)
+}
+
+fn decode(bytes: &[u8]) -> Option<JobStoredData> {
+    match JobStoredData::decode(bytes) {
+        Ok(data) => Some(data),
+        Err(e) => {
+            error!("Error decoding job data {:?}", e);
+            None
+        }
+    }
+}
+
+fn job_and_next_tick(data: &JobStoredData) -> JobAndNextTick {
+    JobAndNextTick {
+        id: data.id.clone(),
+        job_type: data.job_type,
+        next_tick: data.next_tick,
+        last_tick: data.last_tick,
+        stopped: data.stopped,
+        expires_at: data.expires_at,
+        priority: data.priority,
+        boosted_priority: data.boosted_priority,
+        boost_until: data.boost_until,
+    }
+}
+
+async fn write(
+    connection: &mut ConnectionManager,
+    prefix: &str,
+    id: &Uuid,
+    data: &JobStoredData,
+) -> RedisResult<()> {
+    let member = id.to_string();
+    let mut pipe = redis::pipe();
+    pipe.atomic()
+        .set(job_key(prefix, id), data.encode_to_vec())
+        .ignore()
+        .zadd(next_ticks_key(prefix), &member, data.next_tick)
+        .ignore();
+    if data.stopped {
+        pipe.zrem(active_ticks_key(prefix), &member).ignore();
+    } else {
+        pipe.zadd(active_ticks_key(prefix), &member, data.next_tick)
+            .ignore();
+    }
+    pipe.query_async(connection).await
+}
+
+async fn get_many(
+    connection: &mut ConnectionManager,
+    prefix: &str,
+    ids: &[String],
+) -> RedisResult<Vec<JobStoredData>> {
+    if ids.is_empty() {
+        return Ok(vec![]);
+    }
+    let keys = ids
+        .iter()
+        .map(|id| format!("{}:job:{}", prefix, id))
+        .collect::<Vec<_>>();
+    let values: Vec<Option<Vec<u8>>> = redis::cmd("MGET").arg(keys).query_async(connection).await?;
+    Ok(values
+        .iter()
+        .flatten()
+        .filter_map(|bytes| decode(bytes))
+        .collect::<Vec<_>>())
+}
+
+impl DataStore<JobStoredData> for RedisMetadataStore {
+    fn get(
+        &mut self,
+        id: Uuid,
+    ) -> Pin<Box<dyn Future<Output = Result<Option<JobStoredData>, JobSchedulerError>> + Send>>
+    {
+        let store = self.store.clone();
+        Box::pin(async move {
+            let (mut connection, prefix) = match store.read().await.connection() {
+                Some(c) => c,
+                None => return Err(JobSchedulerError::GetJobData),
+            };
+            let value: RedisResult<Option<Vec<u8>>> = redis::cmd("GET")
+                .arg(job_key(&prefix, &id))
+                .query_async(&mut connection)
+                .await;
+            match value {
+                Ok(value) => Ok(value.and_then(|bytes| decode(&bytes))),
+                Err(e) => {
+                    error!("Error getting value {:?}", e);
+                    Err(JobSchedulerError::GetJobData)
+                }
+            }
+        })
+    }
+
+    fn add_or_update(
+        &mut self,
+        data: JobStoredData,
+    ) -> Pin<Box<dyn Future<Output = Result<(), JobSchedulerError>> + Send>> {
+        let size = data.encoded_len();
+        if size > self.max_metadata_bytes {
+            error!(
+                "Metadata of {:?} is {} bytes, more than the maximum of {}",
+                data.id, size, self.max_metadata_bytes
+            );
+            return Box::pin(async { Err(JobSchedulerError::MetadataTooLarge) });
+        }
+        let store = self.store.clone();
+        Box::pin(async move {
+            let (mut connection, prefix) = match store.read().await.connection() {
+                Some(c) => c,
+                None => return Err(JobSchedulerError::UpdateJobData),
+            };
+            let id: Uuid = match data.id.as_ref() {
+                Some(id) => id.into(),
+                None => return Err(JobSchedulerError::UpdateJobData),
+            };
+            write(&mut connection, &prefix, &id, &data)
+                .await
+                .map_err(|e| {
+                    error!("Error {:?}", e);
+                    JobSchedulerError::CantAdd
+                })
+        })
+    }
+
+    fn delete(
+        &mut self,
+        guid: Uuid,
+    ) -> Pin<Box<dyn Future<Output = Result<(), JobSchedulerError>> + Send>> {
+        let store = self.store.clone();
+        Box::pin(async move {
+            let (mut connection, prefix) = match store.read().await.connection() {
+                Some(c) => c,
+                None => return Err(JobSchedulerError::CantRemove),
+            };
+            let member = guid.to_string();
+            let result: RedisResult<()> = redis::pipe()
+                .atomic()
+                .del(job_key(&prefix, &guid))
+                .ignore()
+                .zrem(next_ticks_key(&prefix), &member)
+                .ignore()
+                .zrem(active_ticks_key(&prefix), &member)
+                .ignore()
+                .query_async(&mut connection)
+                .await;
+            result.map_err(|e| {
+                error!("Error deleting job data {:?}", e);
+                JobSchedulerError::CantRemove
+            })
+        })
+    }
+}
+
+impl InitStore for RedisMetadataStore {
+    fn init(&mut self) -> Pin<Box<dyn Future<Output = Result<(), JobSchedulerError>> + Send>> {
+        let store = self.store.clone();
+        Box::pin(async move {
+            let mut w = store.write().await;
+            let v = w.clone().init().await;
+            match v {
+                Ok(v) => {
+                    *w = v;
+                    Ok(())
+                }
+                Err(e) => {
+                    error!("Error initialising {:?}", e);
+                    Err(e)
+                }
+            }
+        })
+    }
+
+    fn inited(&mut self) -> Pin<Box<dyn Future<Output = Result<bool, JobSchedulerError>> + Send>> {
+        let store = self.store.clone();
+        Box::pin(async move {
+            let store = store.read().await;
+            Ok(store.inited())
+        })
+    }
+}
+
+impl MetaDataStorage for RedisMetadataStore {
+    fn is_shared(&self) -> bool {
+        true
+    }
+
+    fn list_next_ticks(
+        &mut self,
+    ) -> Pin<Box<dyn Future<Output = Result<Vec<JobAndNextTick>, JobSchedulerError>> + Send>> {
+        let store = self.store.clone();
+        let owner = self.owner.clone();
+        let tick_lock_ms = self.tick_lock_ms;
+        Box::pin(async move {
+            let (mut connection, prefix) = match store.read().await.connection() {
+                Some(c) => c,
+                None => return Err(JobSchedulerError::CantListNextTicks),
+            };
+            let now = Utc::now().timestamp();
+            let ids: RedisResult<Vec<String>> = redis::cmd("ZRANGEBYSCORE")
+                .arg(next_ticks_key(&prefix))
+                .arg(1)
+                .arg(now)
+                .query_async(&mut connection)
+                .await;
+            let jobs = match ids {
+                Ok(ids) => get_many(&mut connection, &prefix, &ids).await,
+                Err(e) => Err(e),
+            };
+            let jobs = match jobs {
+                Ok(jobs) => jobs,
+                Err(e) => {
+                    error!("Error getting next ticks {:?}", e);
+                    return Err(JobSchedulerError::CantListNextTicks);
+                }
+            };
+
+            let mut ret = vec![];
+            for job in jobs {
+                let id: Uuid = match job.id.as_ref() {
+                    Some(id) => id.into(),
+                    None => continue,
+                };
+                // Whoever sets the lock first gets to run this tick; asking again for a tick
+                // this instance already holds still hands it out.
+                let lock = tick_lock_key(&prefix, &id, job.next_tick);
+                let claimed: RedisResult<Option<String>> = redis::cmd("SET")
+                    .arg(&lock)
+                    .arg(&owner)
+                    .arg("NX")
+                    .arg("PX")
+                    .arg(tick_lock_ms)
+                    .query_async(&mut connection)
+                    .await;
+                let claimed = match claimed {
+                    Ok(Some(_)) => true,
+                    Ok(None) => {
+                        let holder: RedisResult<Option<String>> = redis::cmd("GET")
+                            .arg(&lock)
+                            .query_async(&mut connection)
+                            .await;
+                        matches!(holder, Ok(Some(holder)) if holder == owner)
+                    }
+                    Err(e) => {
+                        error!("Error claiming tick of {:?} {:?}", id, e);
+                        false
+                    }
+                };
+                if claimed {
+                    ret.push(job_and_next_tick(&job));
+                }
+            }
+            Ok(ret)
+        })
+    }
+
+    fn list_jobs(
+        &mut self,
+    ) -> Pin<Box<dyn Future<Output = Result<Vec<JobAndNextTick>, JobSchedulerError>> + Send>> {
+        let store = self.store.clone();
+        Box::pin(async move {
+            let (mut connection, prefix) = match store.read().await.connection() {
+                Some(c) => c,
+                None => return Err(JobSchedulerError::CantListGuids),
+            };
+            let ids: RedisResult<Vec<String>> = redis::cmd("ZRANGE")
+                .arg(next_ticks_key(&prefix))
+                .arg(0)
+                .arg(-1)
+                .query_async(&mut connection)
+                .await;
+            let jobs = match ids {
+                Ok(ids) => get_many(&mut connection, &prefix, &ids).await,
+                Err(e) => Err(e),
+            };
+            match jobs {
+                Ok(jobs) => Ok(jobs.iter().map(job_and_next_tick).collect::<Vec<_>>()),
+                Err(e) => {
+                    error!("Error listing jobs {:?}", e);
+                    Err(JobSchedulerError::CantListGuids)
+                }
+            }
+        })
+    }
+
+    fn count(&mut self) -> Pin<Box<dyn Future<Output = Result<usize, JobSchedulerError>> + Send>> {
+        let store = self.store.clone();
+        Box::pin(async move {
+            let (mut connection, prefix) = match store.read().await.connection() {
+                Some(c) => c,
+                None => return Err(JobSchedulerError::CantListGuids),
+            };
+            let count: RedisResult<usize> = redis::cmd("ZCARD")
+                .arg(next_ticks_key(&prefix))
+                .query_async(&mut connection)
+                .await;
+            count.map_err(|e| {
+                error!("Error counting jobs {:?}", e);
+                JobSchedulerError::CantListGuids
+            })
+        })
+    }
+
+    fn set_next_and_last_tick(
+        &mut self,
+        guid: Uuid,
+        next_tick: Option<DateTime<Utc>>,
+        last_tick: Option<DateTime<Utc>>,
+    ) -> Pin<Box<dyn Future<Output = Result<(), JobSchedulerError>> + Send>> {
+        let mut this = self.clone();
+        Box::pin(async move {
+            let data = this.get(guid).await?;
+            let mut data = match data {
+                Some(data) => data,
+                None => return Err(JobSchedulerError::UpdateJobData),
+            };
+            data.set_next_tick(next_tick);
+            data.set_last_tick(last_tick);
+            let (mut connection, prefix) = match this.store.read().await.connection() {
+                Some(c) => c,
+                None => return Err(JobSchedulerError::UpdateJobData),
+            };
+            write(&mut connection, &prefix, &guid, &data)
+                .await
+                .map_err(|e| {
+                    error!("Error updating next and last tick {:?}", e);
+                    JobSchedulerError::UpdateJobData
+                })
+        })
+    }
+
+    fn time_till_next_job(
+        &mut self,
+    ) -> Pin<Box<dyn Future<Output = Result<Option<Duration>, JobSchedulerError>> + Send>> {
+        let store = self.store.clone();
+        Box::pin(async move {
+            let (mut connection, prefix) = match store.read().await.connection() {
+                Some(c) => c,
+                None => return Err(JobSchedulerError::CouldNotGetTimeUntilNextTick),
+            };
+            let now = Utc::now().timestamp();
+            let next: RedisResult<Vec<(String, i64)>> = redis::cmd("ZRANGEBYSCORE")
+                .arg(active_ticks_key(&prefix))
+                .arg(format!("({}", now))
+                .arg("+inf")
+                .arg("WITHSCORES")
+                .arg("LIMIT")
+                .arg(0)
+                .arg(1)
+                .query_async(&mut connection)
+                .await;
+            match next {
+                Ok(next) => Ok(next
+                    .first()
+                    .map(|(_, ts)| ts - now)
+                    .filter(|ts| *ts > 0)
+                    .map(|ts| ts as u64)
+                    .map(std::time::Duration::from_secs)),
+                Err(e) => {
+                    error!("Error getting time until next job {:?}", e);
+                    Err(JobSchedulerError::CouldNotGetTimeUntilNextTick)
+                }
+            }
+        })
+    }
+
+    fn next_job(
+        &mut self,
+    ) -> Pin<Box<dyn Future<Output = Result<Option<JobAndNextTick>, JobSchedulerError>> + Send>>
+    {
+        let store = self.store.clone();
+        Box::pin(async move {
+            let (mut connection, prefix) = match store.read().await.connection() {
+                Some(c) => c,
+                None => return Err(JobSchedulerError::CouldNotGetTimeUntilNextTick),
+            };
+            let ids: RedisResult<Vec<String>> = redis::cmd("ZRANGEBYSCORE")
+                .arg(active_ticks_key(&prefix))
+                .arg("(0")
+                .arg("+inf")
+                .arg("LIMIT")
+                .arg(0)
+                .arg(1)
+                .query_async(&mut connection)
+                .await;
+            let jobs = match ids {
+                Ok(ids) => get_many(&mut connection, &prefix, &ids).await,
+                Err(e) => Err(e),
+            };
+            match jobs {
+                Ok(jobs) => Ok(jobs.first().map(job_and_next_tick)),
+                Err(e) => {
+                    error!("Error getting the next job {:?}", e);
+                    Err(JobSchedulerError::CouldNotGetTimeUntilNextTick)
+                }
+            }
+        })
+    }
+}
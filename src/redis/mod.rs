@@ -0,0 +1,92 @@
+mod metadata_store;
+mod notification_store;
+
+use crate::JobSchedulerError;
+use redis::aio::ConnectionManager;
+use std::future::Future;
+use std::pin::Pin;
+use tracing::error;
+
+pub use metadata_store::RedisMetadataStore;
+pub use notification_store::RedisNotificationStore;
+
+const URL: &str = "redis://127.0.0.1/";
+const PREFIX: &str = "tokiocron";
+
+///
+/// A Redis connection, shared between the metadata and notification store by putting it in
+/// the same `Arc<RwLock<..>>`. Keys written by either store start with `prefix`.
+#[derive(Clone)]
+pub enum RedisStore {
+    Created {
+        url: String,
+        prefix: String,
+    },
+    Inited {
+        connection: ConnectionManager,
+        prefix: String,
+    },
+}
+
+impl RedisStore {
+    ///
+    /// A store for the Redis server at `url`, using `prefix` to namespace its keys
+    pub fn new(url: &str, prefix: &str) -> Self {
+        Self::Created {
+            url: url.to_string(),
+            prefix: prefix.to_string(),
+        }
+    }
+
+    pub fn inited(&self) -> bool {
+        matches!(self, RedisStore::Inited { .. })
+    }
+
+    pub fn prefix(&self) -> &str {
+        match self {
+            RedisStore::Created { prefix, .. } => prefix,
+            RedisStore::Inited { prefix, .. } => prefix,
+        }
+    }
+
+    pub(crate) fn connection(&self) -> Option<(ConnectionManager, String)> {
+        match self {
+            RedisStore::Created { .. } => None,
+            RedisStore::Inited { connection, prefix } => Some((connection.clone(), prefix.clone())),
+        }
+    }
+}
+
+impl Default for RedisStore {
+    fn default() -> Self {
+        let url = std::env::var("REDIS_URL").unwrap_or_else(|_| URL.to_string());
+        let prefix = std::env::var("REDIS_PREFIX").unwrap_or_else(|_| PREFIX.to_string());
+        Self::Created { url, prefix }
+    }
+}
+
+impl RedisStore {
+    pub fn init(
+        self,
+    ) -> Pin<Box<dyn Future<Output = Result<RedisStore, JobSchedulerError>> + Send>> {
+        Box::pin(async move {
+            match self {
+                RedisStore::Created { url, prefix } => {
+                    let client = redis::Client::open(&*url);
+                    if let Err(e) = client {
+                        error!("Error parsing Redis url {:?}", e);
+                        return Err(JobSchedulerError::StoreInit(e.to_string()));
+                    }
+                    match ConnectionManager::new(client.unwrap()).await {
+                        Ok(connection) => Ok(RedisStore::Inited { connection, prefix }),
+                        Err(e) => {
+                            error!("Error connecting to Redis {:?}", e);
+                            Err(JobSchedulerError::StoreInit(e.to_string()))
+                        }
+                    }
+                }
+                inited => Ok(inited),
+            }
+        })
+    }
+}
@@ -0,0 +1,352 @@
+use crate::job::job_data_prost::{JobState, NotificationData};
+use crate::job::{JobId, NotificationId};
+use crate::redis::RedisStore;
+use crate::store::{DataStore, InitStore, NotificationStore};
+use crate::JobSchedulerError;
+use prost::Message;
+use redis::aio::ConnectionManager;
+use redis::RedisResult;
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::Arc;
+use tokio::sync::RwLock;
+use tracing::error;
+use uuid::Uuid;
+
+///
+/// A Redis backed notification store. Every notification is kept as its encoded
+/// `NotificationData` under `{prefix}:notification:{id}`, with the set
+/// `{prefix}:job_notifications:{job_id}` listing the notifications of a job.
+#[derive(Clone)]
+pub struct RedisNotificationStore {
+    pub store: Arc<RwLock<RedisStore>>,
+}
+
+impl Default for RedisNotificationStore {
+    fn default() -> Self {
+        Self::new(Arc::new(RwLock::new(RedisStore::default())))
+    }
+}
+
+impl RedisNotificationStore {
+    ///
+    /// A notification store on `store`, which can be shared with a `RedisMetadataStore`
+    pub fn new(store: Arc<RwLock<RedisStore>>) -> Self {
+        Self { store }
+    }
+}
+
+fn notification_key(prefix: &str, id: &Uuid) -> String {
+    format!("{}:notification:{}", prefix, id)
+}
+
+fn job_notifications_key(prefix: &str, job_id: &Uuid) -> String {
+    format!("{}:job_notifications:{}", prefix, job_id)
+}
+
+async fn get(
+    connection: &mut ConnectionManager,
+    prefix: &str,
+    id: &Uuid,
+) -> RedisResult<Option<NotificationData>> {
+    let value: Option<Vec<u8>> = redis::cmd("GET")
+        .arg(notification_key(prefix, id))
+        .query_async(connection)
+        .await?;
+    Ok(
+        value.and_then(|bytes| match NotificationData::decode(bytes.as_slice()) {
+            Ok(data) => Some(data),
+            Err(e) => {
+                error!("Error decoding notification data {:?}", e);
+                None
+            }
+        }),
+    )
+}
+
+async fn list_for_job(
+    connection: &mut ConnectionManager,
+    prefix: &str,
+    job_id: &Uuid,
+) -> RedisResult<Vec<Uuid>> {
+    let ids: Vec<String> = redis::cmd("SMEMBERS")
+        .arg(job_notifications_key(prefix, job_id))
+        .query_async(connection)
+        .await?;
+    Ok(ids
+        .iter()
+        .filter_map(|id| Uuid::parse_str(id).ok())
+        .collect::<Vec<_>>())
+}
+
+async fn write(
+    connection: &mut ConnectionManager,
+    prefix: &str,
+    data: &NotificationData,
+) -> RedisResult<()> {
+    let (job_id, notification_id) = match data.job_id_and_notification_id_from_data() {
+        Some(ids) => ids,
+        None => return Ok(()),
+    };
+    redis::pipe()
+        .atomic()
+        .set(
+            notification_key(prefix, &notification_id),
+            data.encode_to_vec(),
+        )
+        .ignore()
+        .sadd(
+            job_notifications_key(prefix, &job_id),
+            notification_id.to_string(),
+        )
+        .ignore()
+        .query_async(connection)
+        .await
+}
+
+async fn remove(
+    connection: &mut ConnectionManager,
+    prefix: &str,
+    job_id: &Uuid,
+    notification_id: &Uuid,
+) -> RedisResult<()> {
+    redis::pipe()
+        .atomic()
+        .del(notification_key(prefix, notification_id))
+        .ignore()
+        .srem(
+            job_notifications_key(prefix, job_id),
+            notification_id.to_string(),
+        )
+        .ignore()
+        .query_async(connection)
+        .await
+}
+
+impl DataStore<NotificationData> for RedisNotificationStore {
+    fn get(
+        &mut self,
+        id: Uuid,
+    ) -> Pin<Box<dyn Future<Output = Result<Option<NotificationData>, JobSchedulerError>> + Send>>
+    {
+        let store = self.store.clone();
+        Box::pin(async move {
+            let (mut connection, prefix) = match store.read().await.connection() {
+                Some(c) => c,
+                None => return Err(JobSchedulerError::GetJobData),
+            };
+            get(&mut connection, &prefix, &id).await.map_err(|e| {
+                error!("Error fetching notification data {:?}", e);
+                JobSchedulerError::GetJobData
+            })
+        })
+    }
+
+    fn add_or_update(
+        &mut self,
+        data: NotificationData,
+    ) -> Pin<Box<dyn Future<Output = Result<(), JobSchedulerError>> + Send>> {
+        let store = self.store.clone();
+        Box::pin(async move {
+            if data.job_id_and_notification_id_from_data().is_none() {
+                return Err(JobSchedulerError::UpdateJobData);
+            }
+            let (mut connection, prefix) = match store.read().await.connection() {
+                Some(c) => c,
+                None => return Err(JobSchedulerError::UpdateJobData),
+            };
+            write(&mut connection, &prefix, &data).await.map_err(|e| {
+                error!("Error writing notification {:?}", e);
+                JobSchedulerError::UpdateJobData
+            })
+        })
+    }
+
+    fn delete(
+        &mut self,
+        guid: Uuid,
+    ) -> Pin<Box<dyn Future<Output = Result<(), JobSchedulerError>> + Send>> {
+        let store = self.store.clone();
+        Box::pin(async move {
+            let (mut connection, prefix) = match store.read().await.connection() {
+                Some(c) => c,
+                None => return Err(JobSchedulerError::CantRemove),
+            };
+            let data = get(&mut connection, &prefix, &guid).await;
+            let result = match data {
+                Ok(Some(data)) => match data.job_id_and_notification_id_from_data() {
+                    Some((job_id, _)) => remove(&mut connection, &prefix, &job_id, &guid).await,
+                    None => Ok(()),
+                },
+                Ok(None) => Ok(()),
+                Err(e) => Err(e),
+            };
+            result.map_err(|e| {
+                error!("Error deleting notification {:?}", e);
+                JobSchedulerError::CantRemove
+            })
+        })
+    }
+}
+
+impl InitStore for RedisNotificationStore {
+    fn init(&mut self) -> Pin<Box<dyn Future<Output = Result<(), JobSchedulerError>> + Send>> {
+        let store = self.store.clone();
+        Box::pin(async move {
+            let mut w = store.write().await;
+            let v = w.clone().init().await;
+            match v {
+                Ok(v) => {
+                    *w = v;
+                    Ok(())
+                }
+                Err(e) => {
+                    error!("Error initialising {:?}", e);
+                    Err(e)
+                }
+            }
+        })
+    }
+
+    fn inited(&mut self) -> Pin<Box<dyn Future<Output = Result<bool, JobSchedulerError>> + Send>> {
+        let store = self.store.clone();
+        Box::pin(async move {
+            let store = store.read().await;
+            Ok(store.inited())
+        })
+    }
+}
+
+impl NotificationStore for RedisNotificationStore {
+    fn list_notification_guids_for_job_and_state(
+        &mut self,
+        job: JobId,
+        state: JobState,
+    ) -> Pin<Box<dyn Future<Output = Result<Vec<NotificationId>, JobSchedulerError>> + Send>> {
+        let store = self.store.clone();
+        Box::pin(async move {
+            let (mut connection, prefix) = match store.read().await.connection() {
+                Some(c) => c,
+                None => return Err(JobSchedulerError::CantListGuids),
+            };
+            let ids = list_for_job(&mut connection, &prefix, &job).await;
+            let ids = match ids {
+                Ok(ids) => ids,
+                Err(e) => {
+                    error!("Error listing notification guids for job and state {:?}", e);
+                    return Err(JobSchedulerError::CantListGuids);
+                }
+            };
+            let state = state as i32;
+            let mut ret = vec![];
+            for id in ids {
+                match get(&mut connection, &prefix, &id).await {
+                    Ok(Some(data)) if data.job_states.contains(&state) => ret.push(id),
+                    Ok(_) => {}
+                    Err(e) => {
+                        error!("Error getting notification {:?}", e);
+                        return Err(JobSchedulerError::CantListGuids);
+                    }
+                }
+            }
+            Ok(ret)
+        })
+    }
+
+    fn list_notification_guids_for_job_id(
+        &mut self,
+        job_id: Uuid,
+    ) -> Pin<Box<dyn Future<Output = Result<Vec<Uuid>, JobSchedulerError>> + Send>> {
+        let store = self.store.clone();
+        Box::pin(async move {
+            let (mut connection, prefix) = match store.read().await.connection() {
+                Some(c) => c,
+                None => return Err(JobSchedulerError::CantListGuids),
+            };
+            list_for_job(&mut connection, &prefix, &job_id)
+                .await
+                .map_err(|e| {
+                    error!(
+                        "Error getting list of notifications guids for job id {:?}",
+                        e
+                    );
+                    JobSchedulerError::CantListGuids
+                })
+        })
+    }
+
+    fn delete_notification_for_state(
+        &mut self,
+        notification_id: Uuid,
+        state: JobState,
+    ) -> Pin<Box<dyn Future<Output = Result<bool, JobSchedulerError>> + Send>> {
+        let store = self.store.clone();
+        Box::pin(async move {
+            let (mut connection, prefix) = match store.read().await.connection() {
+                Some(c) => c,
+                None => return Err(JobSchedulerError::CantRemove),
+            };
+            let data = get(&mut connection, &prefix, &notification_id).await;
+            let mut data = match data {
+                Ok(Some(data)) => data,
+                Ok(None) => return Ok(false),
+                Err(e) => {
+                    error!("Error getting notification {:?}", e);
+                    return Err(JobSchedulerError::CantRemove);
+                }
+            };
+            let state = state as i32;
+            if !data.job_states.contains(&state) {
+                return Ok(false);
+            }
+            data.job_states.retain(|s| *s != state);
+            let result = if data.job_states.is_empty() {
+                match data.job_id_and_notification_id_from_data() {
+                    Some((job_id, _)) => {
+                        remove(&mut connection, &prefix, &job_id, &notification_id).await
+                    }
+                    None => Ok(()),
+                }
+            } else {
+                write(&mut connection, &prefix, &data).await
+            };
+            match result {
+                Ok(()) => Ok(true),
+                Err(e) => {
+                    error!("Error deleting notification for state {:?}", e);
+                    Err(JobSchedulerError::CantRemove)
+                }
+            }
+        })
+    }
+
+    fn delete_for_job(
+        &mut self,
+        job_id: Uuid,
+    ) -> Pin<Box<dyn Future<Output = Result<(), JobSchedulerError>> + Send>> {
+        let store = self.store.clone();
+        Box::pin(async move {
+            let (mut connection, prefix) = match store.read().await.connection() {
+                Some(c) => c,
+                None => return Err(JobSchedulerError::CantRemove),
+            };
+            let ids = list_for_job(&mut connection, &prefix, &job_id).await;
+            let result = match ids {
+                Ok(ids) => {
+                    let mut pipe = redis::pipe();
+                    pipe.atomic();
+                    for id in ids.iter() {
+                        pipe.del(notification_key(&prefix, id)).ignore();
+                    }
+                    pipe.del(job_notifications_key(&prefix, &job_id)).ignore();
+                    pipe.query_async::<_, ()>(&mut connection).await
+                }
+                Err(e) => Err(e),
+            };
+            result.map_err(|e| {
+                error!("Error deleting notifications for job {:?}", e);
+                JobSchedulerError::CantRemove
+            })
+        })
+    }
+}
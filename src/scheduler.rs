@@ -1,23 +1,253 @@
+use crate::clock::{Clock, SystemClock};
 use crate::context::Context;
 #[cfg(not(feature = "has_bytes"))]
-use crate::job::job_data::{JobState, JobType};
+use crate::job::job_data::{JobAndNextTick, JobState, JobType, MisfirePolicy};
 #[cfg(feature = "has_bytes")]
-use crate::job::job_data_prost::{JobState, JobType};
+use crate::job::job_data_prost::{JobAndNextTick, JobState, JobType, MisfirePolicy};
+use crate::job::{next_anchored_tick, CronScheduleParser, ScheduleParser, SkipReason};
+use crate::job_scheduler::{GlobalGate, HeartbeatNotification};
+use crate::store::MetaDataStorage;
 use crate::JobSchedulerError;
-use chrono::Utc;
-use std::sync::Arc;
-use std::time::Duration;
+use chrono::{DateTime, Utc};
+use rand_core::RngCore;
+use std::collections::HashMap;
+use std::future::Future;
+use std::sync::atomic::{AtomicBool, AtomicI64, AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant, SystemTime};
 use tokio::sync::oneshot::{Receiver, Sender};
-use tokio::sync::RwLock;
-use tracing::error;
+use tokio::sync::{broadcast, Notify, RwLock};
+use tracing::{error, info};
 use uuid::Uuid;
 
+const TICK_INTERVAL: Duration = Duration::from_millis(500);
+/// Shorter intervals are raised to this, so the loop never spins
+const MIN_TICK_INTERVAL: Duration = Duration::from_millis(10);
+/// A tick found later than this after the tick interval was missed, see `MisfirePolicy`
+const MISFIRE_GRACE: Duration = Duration::from_secs(1);
+/// The most runs a `MisfirePolicy::FireAll` job makes up for at once
+const MAX_MISFIRED_RUNS: usize = 10;
+/// How often the same tick loop error is logged while it persists
+const TICK_ERROR_LOG_INTERVAL: Duration = Duration::from_secs(30);
+
+///
+/// Rate limits the logging of the tick loop's errors, so a store that is down doesn't log an error
+/// on every tick. The first error is logged, then at most one per interval until a tick succeeds.
+pub struct TickErrorLog {
+    pub interval_ms: AtomicU64,
+    /// When the last error was logged and how many errors weren't logged since
+    state: Mutex<(Option<Instant>, u64)>,
+}
+
+impl Default for TickErrorLog {
+    fn default() -> Self {
+        Self {
+            interval_ms: AtomicU64::new(TICK_ERROR_LOG_INTERVAL.as_millis() as u64),
+            state: Mutex::new((None, 0)),
+        }
+    }
+}
+
+impl TickErrorLog {
+    fn error(&self, message: &str, e: &JobSchedulerError) {
+        let interval = Duration::from_millis(self.interval_ms.load(Ordering::Relaxed));
+        let mut state = match self.state.lock() {
+            Ok(state) => state,
+            Err(poisoned) => poisoned.into_inner(),
+        };
+        let (last_logged, suppressed) = &mut *state;
+        match last_logged {
+            Some(at) if at.elapsed() < interval => *suppressed += 1,
+            _ => {
+                if *suppressed > 0 {
+                    error!("{} {:?}, {} more tick errors since", message, e, suppressed);
+                } else {
+                    error!("{} {:?}", message, e);
+                }
+                *last_logged = Some(Instant::now());
+                *suppressed = 0;
+            }
+        }
+    }
+
+    fn recovered(&self) {
+        let mut state = match self.state.lock() {
+            Ok(state) => state,
+            Err(poisoned) => poisoned.into_inner(),
+        };
+        if state.0.is_some() {
+            if state.1 > 0 {
+                info!(
+                    "Tick loop recovered, {} tick errors weren't logged",
+                    state.1
+                );
+            }
+            *state = (None, 0);
+        }
+    }
+}
+
+///
+/// Hand an error of the tick loop to the subscribers of `JobScheduler::subscribe_tick_errors`
+fn report_tick_error(tick_error_tx: &broadcast::Sender<JobSchedulerError>, e: JobSchedulerError) {
+    if let Err(e) = tick_error_tx.send(e) {
+        // Only fails when nobody subscribed to the tick errors
+        tracing::trace!("No listener for tick error {:?}", e);
+    }
+}
+
+///
+/// The number of runs a job whose tick was missed makes up for, by its `MisfirePolicy`
+async fn misfired_runs(
+    metadata_storage: &Arc<RwLock<Box<dyn MetaDataStorage + Send + Sync>>>,
+    schedule_parser: &Arc<RwLock<Box<dyn ScheduleParser + Send + Sync>>>,
+    uuid: Uuid,
+    missed: DateTime<Utc>,
+    now: DateTime<Utc>,
+) -> usize {
+    let job = {
+        let mut w = metadata_storage.write().await;
+        w.get(uuid).await
+    };
+    let job = match job {
+        Ok(Some(job)) => job,
+        _ => return 1,
+    };
+    match MisfirePolicy::from_i32(job.misfire_policy) {
+        Some(MisfirePolicy::Skip) => 0,
+        Some(MisfirePolicy::FireAll) => {
+            let mut runs = 1;
+            match JobType::from_i32(job.job_type) {
+                Some(JobType::Cron) => {
+                    let parser = schedule_parser.read().await;
+                    let mut tick = missed;
+                    while runs <= MAX_MISFIRED_RUNS {
+                        match job.next_schedule_tick(&**parser, &tick) {
+                            Some(Ok(Some(next))) if next <= now => {
+                                runs += 1;
+                                tick = next;
+                            }
+                            _ => break,
+                        }
+                    }
+                }
+                Some(JobType::Repeated) => {
+                    if let Some(every) = job.repeated_every().filter(|every| *every > 0) {
+                        let missed_for = (now - missed).num_seconds().max(0) as u64;
+                        runs += (missed_for / every) as usize;
+                    }
+                }
+                _ => {}
+            }
+            if runs > MAX_MISFIRED_RUNS {
+                tracing::warn!(
+                    "Job {:?} missed {} runs or more, only making up for {}",
+                    uuid,
+                    runs,
+                    MAX_MISFIRED_RUNS
+                );
+            }
+            runs.min(MAX_MISFIRED_RUNS)
+        }
+        _ => 1,
+    }
+}
+
+///
+/// A splitmix64 generator for the jitter of next ticks. Good enough to spread ticks out, and
+/// seedable so tests can predict them. A generator set with `set_rng` is used instead.
+pub struct JitterRng {
+    state: AtomicU64,
+    rng: Mutex<Option<Box<dyn RngCore + Send>>>,
+}
+
+impl Default for JitterRng {
+    fn default() -> Self {
+        let seed = SystemTime::now()
+            .duration_since(SystemTime::UNIX_EPOCH)
+            .map(|d| d.as_nanos() as u64)
+            .unwrap_or_default();
+        Self::new(seed)
+    }
+}
+
+impl JitterRng {
+    pub fn new(seed: u64) -> Self {
+        Self {
+            state: AtomicU64::new(seed),
+            rng: Mutex::new(None),
+        }
+    }
+
+    ///
+    /// Seed the built-in generator, dropping a generator set with `set_rng`
+    pub fn seed(&self, seed: u64) {
+        if let Ok(mut w) = self.rng.lock() {
+            *w = None;
+        }
+        self.state.store(seed, Ordering::SeqCst);
+    }
+
+    pub fn set_rng(&self, rng: Box<dyn RngCore + Send>) {
+        if let Ok(mut w) = self.rng.lock() {
+            *w = Some(rng);
+        }
+    }
+
+    fn next_u64(&self) -> u64 {
+        if let Ok(mut w) = self.rng.lock() {
+            if let Some(rng) = w.as_mut() {
+                return rng.next_u64();
+            }
+        }
+        let mut z = self
+            .state
+            .fetch_add(0x9E37_79B9_7F4A_7C15, Ordering::SeqCst)
+            .wrapping_add(0x9E37_79B9_7F4A_7C15);
+        z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+        z ^ (z >> 31)
+    }
+
+    ///
+    /// `tick` moved a random number of milliseconds later, up to `max_ms`
+    pub fn jitter(&self, tick: DateTime<Utc>, max_ms: u64) -> DateTime<Utc> {
+        if max_ms == 0 {
+            return tick;
+        }
+        let ms = self.next_u64() % (max_ms + 1);
+        tick + chrono::Duration::milliseconds(ms as i64)
+    }
+}
+
 pub struct Scheduler {
     pub shutdown: Arc<RwLock<bool>>,
     pub start_tx: Arc<RwLock<Option<Sender<bool>>>>,
     pub start_rx: Arc<RwLock<Option<Receiver<bool>>>>,
     pub ticking: Arc<RwLock<bool>>,
+    /// Set once the loop runs, see `JobScheduler::wait_until_started`
+    pub started: Arc<AtomicBool>,
+    pub started_notify: Arc<Notify>,
     pub inited: bool,
+    pub last_tick_at: Arc<AtomicI64>,
+    pub tick_interval_ms: Arc<AtomicU64>,
+    /// Set by `set_tick_interval`, the loop then checks for due jobs at least every interval
+    pub tick_interval_set: Arc<AtomicBool>,
+    pub tick_error_log: Arc<TickErrorLog>,
+    /// Wakes the loop up to look at the next ticks again
+    pub wake: Arc<Notify>,
+    pub jitter_rng: Arc<JitterRng>,
+    pub heartbeat_handler: Arc<RwLock<Option<Box<HeartbeatNotification>>>>,
+    pub global_gate: Arc<RwLock<Option<Box<GlobalGate>>>>,
+    /// While set, the loop keeps ticking but leaves all jobs be
+    pub paused: Arc<AtomicBool>,
+    /// Set by `JobScheduler::drain`, new jobs are refused while the scheduled ones keep running
+    pub draining: Arc<AtomicBool>,
+    pub schedule_parser: Arc<RwLock<Box<dyn ScheduleParser + Send + Sync>>>,
+    /// What the loop takes as now, when finding due jobs and their next ticks
+    pub clock: Arc<RwLock<Box<dyn Clock + Send + Sync>>>,
+    pub quarantined: Arc<RwLock<Vec<Uuid>>>,
+    pub notify_stop_on_shutdown: bool,
 }
 
 impl Default for Scheduler {
@@ -29,6 +259,22 @@ impl Default for Scheduler {
             start_tx: Arc::new(RwLock::new(Some(ticker_tx))),
             start_rx: Arc::new(RwLock::new(Some(ticker_rx))),
             ticking: Arc::new(RwLock::new(false)),
+            started: Arc::new(AtomicBool::new(false)),
+            started_notify: Arc::new(Notify::new()),
+            last_tick_at: Arc::new(AtomicI64::new(0)),
+            tick_interval_ms: Arc::new(AtomicU64::new(TICK_INTERVAL.as_millis() as u64)),
+            tick_interval_set: Arc::new(AtomicBool::new(false)),
+            tick_error_log: Arc::new(TickErrorLog::default()),
+            wake: Arc::new(Notify::new()),
+            jitter_rng: Arc::new(JitterRng::default()),
+            heartbeat_handler: Arc::new(RwLock::new(None)),
+            global_gate: Arc::new(RwLock::new(None)),
+            paused: Arc::new(AtomicBool::new(false)),
+            draining: Arc::new(AtomicBool::new(false)),
+            schedule_parser: Arc::new(RwLock::new(Box::new(CronScheduleParser::default()))),
+            clock: Arc::new(RwLock::new(Box::new(SystemClock::default()))),
+            quarantined: Arc::new(RwLock::new(vec![])),
+            notify_stop_on_shutdown: false,
         }
     }
 }
@@ -41,9 +287,25 @@ impl Scheduler {
 
         let job_activation_tx = context.job_activation_tx.clone();
         let notify_tx = context.notify_tx.clone();
+        let skipped_tx = context.skipped_tx.clone();
+        let tick_error_tx = context.tick_error_tx.clone();
         let job_delete_tx = context.job_delete_tx.clone();
+        let job_deleted_tx = context.job_deleted_tx.clone();
         let shutdown = self.shutdown.clone();
         let metadata_storage = context.metadata_storage.clone();
+        let last_tick_at = self.last_tick_at.clone();
+        let tick_interval_ms = self.tick_interval_ms.clone();
+        let tick_interval_set = self.tick_interval_set.clone();
+        let tick_error_log = self.tick_error_log.clone();
+        let wake = self.wake.clone();
+        let jitter_rng = self.jitter_rng.clone();
+        let heartbeat_handler = self.heartbeat_handler.clone();
+        let heartbeat_running = Arc::new(AtomicBool::new(false));
+        let global_gate = self.global_gate.clone();
+        let paused = self.paused.clone();
+        let schedule_parser = self.schedule_parser.clone();
+        let clock = self.clock.clone();
+        let quarantined = self.quarantined.clone();
 
         self.inited = true;
 
@@ -56,6 +318,8 @@ impl Scheduler {
         };
 
         let ticking = self.ticking.clone();
+        let started = self.started.clone();
+        let started_notify = self.started_notify.clone();
         tokio::spawn(async move {
             let is_ticking = {
                 let ticking = ticking.read().await;
@@ -85,7 +349,46 @@ impl Scheduler {
                     }
                 }
             }
+            // The first tick of the jobs that went by during `pause_all`, made up for on resume
+            let mut missed: HashMap<Uuid, DateTime<Utc>> = HashMap::new();
             'next_tick: loop {
+                if !started.swap(true, Ordering::SeqCst) {
+                    started_notify.notify_waiters();
+                }
+                let tick_interval = Duration::from_millis(tick_interval_ms.load(Ordering::Relaxed));
+                let (till_next_job, shared) = {
+                    let mut w = metadata_storage.write().await;
+                    (w.time_till_next_job().await, w.is_shared())
+                };
+                // Another scheduler or clock can make jobs due without waking the loop up
+                let poll = shared
+                    || tick_interval_set.load(Ordering::Relaxed)
+                    || !clock.read().await.is_system();
+                let sleep_for = match till_next_job {
+                    // Stores count down in whole seconds, sleep until the exact second instead
+                    Ok(Some(till_next_job)) => {
+                        let next_tick = Utc::now().timestamp() + till_next_job.as_secs() as i64;
+                        let until_next_tick = next_tick * 1000 - Utc::now().timestamp_millis();
+                        let until_next_tick = Duration::from_millis(until_next_tick.max(0) as u64);
+                        if poll {
+                            until_next_tick.min(tick_interval)
+                        } else {
+                            until_next_tick
+                        }
+                    }
+                    // Nothing to sleep until, look again after the interval
+                    Ok(None) => tick_interval,
+                    Err(e) => {
+                        tick_error_log.error("Error getting the time till the next job", &e);
+                        report_tick_error(&tick_error_tx, e);
+                        tick_interval
+                    }
+                };
+                let wake_at = tokio::time::Instant::now() + sleep_for.max(MIN_TICK_INTERVAL);
+                tokio::select! {
+                    _ = tokio::time::sleep_until(wake_at) => {}
+                    _ = wake.notified() => {}
+                }
                 let shutdown = {
                     let r = shutdown.read().await;
                     *r
@@ -93,16 +396,56 @@ impl Scheduler {
                 if shutdown {
                     break 'next_tick;
                 }
-                tokio::time::sleep(Duration::from_millis(500)).await;
-                let now = Utc::now();
+                let now = {
+                    let clock = clock.read().await;
+                    clock.now()
+                };
+                last_tick_at.store(now.timestamp_millis(), Ordering::Relaxed);
+                // Ticks that come while the handler is still busy don't queue up behind it
+                if heartbeat_running
+                    .compare_exchange(false, true, Ordering::AcqRel, Ordering::Acquire)
+                    .is_ok()
+                {
+                    let heartbeat_handler = heartbeat_handler.clone();
+                    let heartbeat_running = heartbeat_running.clone();
+                    tokio::spawn(async move {
+                        // In a task of its own, so a handler that panics doesn't keep the flag set
+                        let ran = tokio::spawn(async move {
+                            let mut w = heartbeat_handler.write().await;
+                            if let Some(handler) = w.as_mut() {
+                                handler(now).await;
+                            }
+                        })
+                        .await;
+                        if let Err(e) = ran {
+                            error!("Heartbeat handler failed {:?}", e);
+                        }
+                        heartbeat_running.store(false, Ordering::Release);
+                    });
+                }
+                // The ticks of the jobs go by without firing, on resume they're misfired
+                let all_paused = paused.load(Ordering::Relaxed);
+                #[cfg(feature = "metrics")]
+                {
+                    let count = {
+                        let mut w = metadata_storage.write().await;
+                        w.count().await
+                    };
+                    match count {
+                        Ok(count) => metrics::gauge!("tcs_jobs", count as f64),
+                        Err(e) => error!("Error counting the jobs {:?}", e),
+                    }
+                }
                 let next_ticks = {
                     let mut w = metadata_storage.write().await;
                     w.list_next_ticks().await
                 };
                 if let Err(e) = next_ticks {
-                    error!("Error with listing next ticks {:?}", e);
+                    tick_error_log.error("Error with listing next ticks", &e);
+                    report_tick_error(&tick_error_tx, e);
                     continue 'next_tick;
                 }
+                tick_error_log.recovered();
                 let mut next_ticks = next_ticks.unwrap();
                 let to_be_deleted = next_ticks.iter().filter_map(|v| {
                     v.id.as_ref()?;
@@ -124,6 +467,51 @@ impl Scheduler {
 
                 next_ticks.retain(|n| n.next_tick != 0);
 
+                // Expired jobs are removed before they could fire, also when they aren't due
+                let now_secs = now.timestamp().max(0) as u64;
+                // Nothing is removed or notified while paused
+                let is_expired = |n: &JobAndNextTick| {
+                    !all_paused && matches!(n.expires_at, Some(at) if at <= now_secs)
+                };
+                let expired = next_ticks
+                    .iter()
+                    .filter(|n| is_expired(n))
+                    .filter_map(|n| n.id.as_ref().map(Uuid::from))
+                    .collect::<Vec<_>>();
+                for uuid in expired {
+                    let delete = {
+                        let mut w = metadata_storage.write().await;
+                        w.delete(uuid).await
+                    };
+                    if let Err(e) = delete {
+                        error!("Error removing expired job {:?} {:?}", uuid, e);
+                        continue;
+                    }
+                    info!("Removed job {:?}, it expired", uuid);
+                    let tx = notify_tx.clone();
+                    let job_deleted_tx = job_deleted_tx.clone();
+                    tokio::spawn(async move {
+                        if let Err(e) = job_deleted_tx.send(Ok(uuid)) {
+                            error!("Error sending deletion {:?}", e);
+                        }
+                        if let Err(e) = tx.send((uuid, JobState::Removed)) {
+                            error!("Error sending notification removed {:?}", e);
+                        }
+                    });
+                }
+                next_ticks.retain(|n| !is_expired(n));
+                {
+                    let quarantined = quarantined.read().await;
+                    if !quarantined.is_empty() {
+                        next_ticks.retain(|n| match n.id.as_ref() {
+                            Some(id) => !quarantined.contains(&id.into()),
+                            None => true,
+                        });
+                    }
+                }
+                // Stable, jobs with the same priority keep the order the store listed them in
+                next_ticks.sort_by_key(|n| std::cmp::Reverse(n.priority_at(now_secs)));
+
                 let must_runs = next_ticks.iter().filter_map(|n| {
                     let next_tick = n.next_tick_utc();
                     let last_tick = n.last_tick_utc();
@@ -156,33 +544,95 @@ impl Scheduler {
                         }
                         _ => false,
                     };
-                    if must_run {
-                        let id: Uuid = n.id.as_ref().map(|f| f.into()).unwrap();
-                        Some(id)
+                    let id: Uuid = n.id.as_ref().map(|f| f.into())?;
+                    // Missed during `pause_all`, misfired from the first tick that went by
+                    let first_missed = if all_paused { None } else { missed.remove(&id) };
+                    // A paused one shot job stays due, so it still runs once it is resumed
+                    let paused_one_shot =
+                        (n.stopped || all_paused) && matches!(job_type, JobType::OneShot);
+                    if must_run && !paused_one_shot {
+                        Some((id, n.stopped, first_missed.or(next_tick).unwrap_or(now)))
                     } else {
-                        None
+                        first_missed.map(|first_missed| (id, n.stopped, first_missed))
                     }
                 });
+                let must_runs = must_runs.collect::<Vec<_>>();
+                if !all_paused {
+                    // Jobs removed while paused
+                    missed.clear();
+                }
 
-                for uuid in must_runs {
-                    {
-                        let tx = notify_tx.clone();
-                        tokio::spawn(async move {
-                            if let Err(e) = tx.send((uuid, JobState::Scheduled)) {
-                                error!("Error sending notification activation {:?}", e);
-                            }
-                        });
+                let gate_open = if all_paused || must_runs.iter().all(|(_, paused, _)| *paused) {
+                    true
+                } else {
+                    let mut w = global_gate.write().await;
+                    match w.as_mut() {
+                        Some(gate) => gate().await,
+                        None => true,
                     }
-                    {
-                        let tx = job_activation_tx.clone();
-                        tokio::spawn(async move {
-                            if let Err(e) = tx.send(uuid) {
-                                error!("Error sending job activation tx {:?}", e);
+                };
+
+                let misfire_after = chrono::Duration::from_std(tick_interval + MISFIRE_GRACE)
+                    .unwrap_or_else(|_| chrono::Duration::seconds(1));
+                for (uuid, paused, next_tick) in must_runs {
+                    let misfired = next_tick + misfire_after < now;
+                    if all_paused {
+                        if !paused {
+                            missed.entry(uuid).or_insert(next_tick);
+                        }
+                    } else {
+                        let runs = if misfired && !paused && gate_open {
+                            misfired_runs(&metadata_storage, &schedule_parser, uuid, next_tick, now)
+                                .await
+                        } else {
+                            1
+                        };
+                        let skip_reason = if paused {
+                            Some(SkipReason::Paused)
+                        } else if !gate_open {
+                            Some(SkipReason::GlobalGate)
+                        } else if runs == 0 {
+                            Some(SkipReason::Misfire)
+                        } else {
+                            None
+                        };
+                        if let Some(skip_reason) = skip_reason {
+                            let tx = notify_tx.clone();
+                            let skipped_tx = skipped_tx.clone();
+                            tokio::spawn(async move {
+                                if let Err(e) = skipped_tx.send((uuid, skip_reason)) {
+                                    // Only fails when nobody subscribed to the skip reasons
+                                    tracing::trace!("No listener for skip reason {:?}", e);
+                                }
+                                if let Err(e) = tx.send((uuid, JobState::Skipped)) {
+                                    error!("Error sending notification skipped {:?}", e);
+                                }
+                            });
+                        } else {
+                            for _ in 0..runs {
+                                {
+                                    let tx = notify_tx.clone();
+                                    tokio::spawn(async move {
+                                        if let Err(e) = tx.send((uuid, JobState::Scheduled)) {
+                                            error!("Error sending notification activation {:?}", e);
+                                        }
+                                    });
+                                }
+                                // Sent right away, so the runner gets the activations by priority
+                                if let Err(e) = job_activation_tx.send(uuid) {
+                                    error!("Error sending job activation tx {:?}", e);
+                                }
                             }
-                        });
+                        }
                     }
 
                     let storage = metadata_storage.clone();
+                    let schedule_parser = schedule_parser.clone();
+                    let tick_error_tx = tick_error_tx.clone();
+                    let quarantined = quarantined.clone();
+                    let wake = wake.clone();
+                    let jitter_rng = jitter_rng.clone();
+                    let tick_error_log = tick_error_log.clone();
                     tokio::spawn(async move {
                         let mut w = storage.write().await;
                         let job = w.get(uuid).await;
@@ -190,13 +640,42 @@ impl Scheduler {
                         let next_and_last_tick = match job {
                             Ok(Some(job)) => {
                                 let job_type: JobType = JobType::from_i32(job.job_type).unwrap();
-                                let schedule = job.schedule();
                                 let repeated_every = job.repeated_every();
+                                let anchor = job.anchor();
                                 let next_tick = job.next_tick_utc();
-                                let next_tick = match job_type {
-                                    JobType::Cron => schedule.and_then(|s| s.after(&now).next()),
-                                    JobType::OneShot => None,
-                                    JobType::Repeated => repeated_every.and_then(|r| {
+                                let next_tick = match (job_type, anchor) {
+                                    (JobType::Cron, _) => {
+                                        let parser = schedule_parser.read().await;
+                                        match job.next_schedule_tick(&**parser, &now) {
+                                            Some(Ok(next_tick)) => next_tick
+                                                .map(|t| jitter_rng.jitter(t, job.jitter_ms)),
+                                            Some(Err(e)) => {
+                                                // Keep the job's data as it is, so it can be
+                                                // inspected and fixed, but stop running it
+                                                error!(
+                                                    "Quarantining job {:?}, could not compute its next tick {:?}",
+                                                    uuid, e
+                                                );
+                                                let mut quarantined = quarantined.write().await;
+                                                if !quarantined.contains(&uuid) {
+                                                    quarantined.push(uuid);
+                                                }
+                                                return;
+                                            }
+                                            None => None,
+                                        }
+                                    }
+                                    (JobType::OneShot, _) => None,
+                                    (JobType::Repeated, Some(anchor)) => repeated_every
+                                        .and_then(|r| next_anchored_tick(anchor, r, now)),
+                                    // Catching up on every missed tick is up to the misfire policy
+                                    (JobType::Repeated, None) if misfired => repeated_every
+                                        .and_then(|r| {
+                                            now.checked_add_signed(chrono::Duration::seconds(
+                                                r as i64,
+                                            ))
+                                        }),
+                                    (JobType::Repeated, None) => repeated_every.and_then(|r| {
                                         next_tick.and_then(|nt| {
                                             nt.checked_add_signed(chrono::Duration::seconds(
                                                 r as i64,
@@ -217,8 +696,10 @@ impl Scheduler {
                             if let Err(e) =
                                 w.set_next_and_last_tick(uuid, next_tick, last_tick).await
                             {
-                                error!("Could not set next and last tick {:?}", e);
+                                tick_error_log.error("Could not set next and last tick", &e);
+                                report_tick_error(&tick_error_tx, e);
                             }
+                            wake.notify_one();
                         }
                     });
                 }
@@ -226,9 +707,57 @@ impl Scheduler {
         });
     }
 
+    ///
+    /// Wait for the tick loop to run. Returns right away when it already did.
+    pub fn wait_until_started(&self) -> impl Future<Output = ()> {
+        let started = self.started.clone();
+        let started_notify = self.started_notify.clone();
+        async move {
+            loop {
+                // Created before the check, so a notification in between isn't missed
+                let notified = started_notify.notified();
+                if started.load(Ordering::SeqCst) {
+                    return;
+                }
+                notified.await;
+            }
+        }
+    }
+
+    ///
+    /// The time the tick loop last ran, if it has ran at all
+    pub fn last_tick_at(&self) -> Option<DateTime<Utc>> {
+        match self.last_tick_at.load(Ordering::Relaxed) {
+            0 => None,
+            millis => Some(DateTime::from(
+                SystemTime::UNIX_EPOCH + Duration::from_millis(millis as u64),
+            )),
+        }
+    }
+
+    ///
+    /// Set the longest the tick loop sleeps between checking for due jobs, at least 10ms
+    pub fn set_tick_interval(&self, interval: Duration) {
+        let interval = interval.max(MIN_TICK_INTERVAL);
+        self.tick_interval_ms
+            .store(interval.as_millis() as u64, Ordering::Relaxed);
+        self.tick_interval_set.store(true, Ordering::Relaxed);
+    }
+
+    pub fn tick_interval(&self) -> Duration {
+        Duration::from_millis(self.tick_interval_ms.load(Ordering::Relaxed))
+    }
+
+    pub fn set_tick_error_log_interval(&self, interval: Duration) {
+        self.tick_error_log
+            .interval_ms
+            .store(interval.as_millis() as u64, Ordering::Relaxed);
+    }
+
     pub async fn shutdown(&mut self) {
         let mut w = self.shutdown.write().await;
         *w = true;
+        self.wake.notify_one();
     }
 
     pub async fn start(&mut self) -> Result<(), JobSchedulerError> {
@@ -15,6 +15,7 @@ use uuid::Uuid;
 pub struct SimpleMetadataStore {
     pub data: Arc<RwLock<HashMap<Uuid, JobStoredData>>>,
     pub inited: bool,
+    pub max_finished: Option<usize>,
 }
 
 impl Default for SimpleMetadataStore {
@@ -22,10 +23,46 @@ impl Default for SimpleMetadataStore {
         Self {
             data: Arc::new(RwLock::new(HashMap::new())),
             inited: false,
+            max_finished: None,
         }
     }
 }
 
+impl SimpleMetadataStore {
+    ///
+    /// A store that keeps at most `max_finished` jobs that ran and have no next tick anymore,
+    /// like one shot jobs that fired, until they are removed. Past that the ones that ran longest ago are
+    /// evicted. Jobs that are still scheduled are never evicted. The default store keeps them all.
+    pub fn with_max_finished(max_finished: usize) -> Self {
+        Self {
+            max_finished: Some(max_finished),
+            ..Default::default()
+        }
+    }
+}
+
+///
+/// Evict the finished jobs that ran longest ago, until at most `max_finished` are left
+fn evict_finished(data: &mut HashMap<Uuid, JobStoredData>, max_finished: Option<usize>) {
+    let max_finished = match max_finished {
+        Some(max_finished) => max_finished,
+        None => return,
+    };
+    let mut finished = data
+        .iter()
+        // A one shot job loses its next tick right before it runs, keep it until it ran
+        .filter(|(_, v)| v.next_tick == 0 && v.count > 0)
+        .map(|(id, v)| (v.last_tick.or(v.last_updated).unwrap_or_default(), *id))
+        .collect::<Vec<_>>();
+    if finished.len() <= max_finished {
+        return;
+    }
+    finished.sort();
+    for (_, id) in finished.iter().take(finished.len() - max_finished) {
+        data.remove(id);
+    }
+}
+
 impl DataStore<JobStoredData> for SimpleMetadataStore {
     fn get(
         &mut self,
@@ -46,9 +83,11 @@ impl DataStore<JobStoredData> for SimpleMetadataStore {
     ) -> Pin<Box<dyn Future<Output = Result<(), JobSchedulerError>> + Send>> {
         let id: Uuid = data.id.as_ref().unwrap().into();
         let job_data = self.data.clone();
+        let max_finished = self.max_finished;
         Box::pin(async move {
             let mut w = job_data.write().await;
             w.insert(id, data);
+            evict_finished(&mut w, max_finished);
             Ok(())
         })
     }
@@ -87,18 +126,37 @@ impl MetaDataStorage for SimpleMetadataStore {
             let r = data.read().await;
             let ret = r
                 .iter()
-                .map(|(_, v)| (v.id.clone(), v.next_tick, v.last_tick, v.job_type))
-                .map(|(id, next_tick, last_tick, job_type)| JobAndNextTick {
-                    id,
-                    next_tick,
-                    last_tick,
-                    job_type,
+                .map(|(_, v)| JobAndNextTick {
+                    id: v.id.clone(),
+                    next_tick: v.next_tick,
+                    last_tick: v.last_tick,
+                    job_type: v.job_type,
+                    stopped: v.stopped,
+                    expires_at: v.expires_at,
+                    priority: v.priority,
+                    boosted_priority: v.boosted_priority,
+                    boost_until: v.boost_until,
                 })
                 .collect::<Vec<_>>();
             Ok(ret)
         })
     }
 
+    fn list_jobs(
+        &mut self,
+    ) -> Pin<Box<dyn Future<Output = Result<Vec<JobAndNextTick>, JobSchedulerError>> + Send>> {
+        // All jobs are listed as next ticks already
+        self.list_next_ticks()
+    }
+
+    fn count(&mut self) -> Pin<Box<dyn Future<Output = Result<usize, JobSchedulerError>> + Send>> {
+        let data = self.data.clone();
+        Box::pin(async move {
+            let r = data.read().await;
+            Ok(r.len())
+        })
+    }
+
     fn set_next_and_last_tick(
         &mut self,
         guid: Uuid,
@@ -106,6 +164,7 @@ impl MetaDataStorage for SimpleMetadataStore {
         last_tick: Option<DateTime<Utc>>,
     ) -> Pin<Box<dyn Future<Output = Result<(), JobSchedulerError>> + Send>> {
         let data = self.data.clone();
+        let max_finished = self.max_finished;
         Box::pin(async move {
             let mut w = data.write().await;
             let val = w.get_mut(&guid);
@@ -113,6 +172,7 @@ impl MetaDataStorage for SimpleMetadataStore {
                 Some(val) => {
                     val.set_next_tick(next_tick);
                     val.set_last_tick(last_tick);
+                    evict_finished(&mut w, max_finished);
                     Ok(())
                 }
                 None => Err(JobSchedulerError::UpdateJobData),
@@ -131,6 +191,7 @@ impl MetaDataStorage for SimpleMetadataStore {
             let now = now.timestamp() as u64;
             let val = r
                 .iter()
+                .filter(|(_, jd)| !jd.stopped)
                 .filter_map(|(_, jd)| match jd.next_tick {
                     0 => None,
                     i => {
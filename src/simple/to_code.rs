@@ -49,6 +49,24 @@ impl SimpleJobCode {
         }
     }
 
+    async fn listen_for_batch_additions(
+        data: LockedJobToRunMap,
+        mut rx: Receiver<Vec<(JobStoredData, Arc<RwLock<Box<JobToRunAsync>>>)>>,
+    ) {
+        loop {
+            let val = rx.recv().await;
+            if let Err(e) = val {
+                error!("Error receiving {:?}", e);
+                break;
+            }
+            let mut w = data.write().await;
+            for (JobStoredData { id: job_id, .. }, val) in val.unwrap() {
+                let uuid: Uuid = job_id.as_ref().unwrap().into();
+                w.insert(uuid, val);
+            }
+        }
+    }
+
     async fn listen_for_removals(
         data: LockedJobToRunMap,
         mut rx: Receiver<Result<Uuid, (JobSchedulerError, Option<Uuid>)>>,
@@ -75,6 +93,7 @@ impl ToCode<Box<JobToRunAsync>> for SimpleJobCode {
     ) -> Pin<Box<dyn Future<Output = Result<(), JobSchedulerError>> + Send>> {
         let data = self.job_code.clone();
         let job_create = context.job_create_tx.subscribe();
+        let jobs_create = context.jobs_create_tx.subscribe();
         let job_deleted = context.job_deleted_tx.subscribe();
 
         Box::pin(async move {
@@ -82,6 +101,10 @@ impl ToCode<Box<JobToRunAsync>> for SimpleJobCode {
                 data.clone(),
                 job_create,
             ));
+            tokio::spawn(SimpleJobCode::listen_for_batch_additions(
+                data.clone(),
+                jobs_create,
+            ));
             tokio::spawn(SimpleJobCode::listen_for_removals(data, job_deleted));
             Ok(())
         })
@@ -0,0 +1,767 @@
+use crate::job::job_data_prost::{CronJob, JobType, NonCronJob};
+use crate::sqlite::SqliteStore;
+use crate::store::{DataStore, InitStore, MetaDataStorage};
+use crate::{JobAndNextTick, JobSchedulerError, JobStoredData, JobUuid};
+use chrono::{DateTime, Utc};
+use prost::Message;
+use sqlx::query::Query;
+use sqlx::sqlite::{SqliteArguments, SqliteRow};
+use sqlx::{Row, Sqlite};
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::sync::RwLock;
+use tracing::error;
+use uuid::Uuid;
+
+const TABLE: &str = "job";
+const MAX_METADATA_BYTES: usize = 1024 * 1024;
+// SQLite has no arrays, the tags are stored in one column separated by newlines
+const TAG_SEPARATOR: char = '\n';
+
+///
+/// A SQLite backed metadata store. Creates its table on init.
+#[derive(Clone)]
+pub struct SqliteMetadataStore {
+    pub store: Arc<RwLock<SqliteStore>>,
+    pub table: String,
+    pub max_metadata_bytes: usize,
+}
+
+impl Default for SqliteMetadataStore {
+    fn default() -> Self {
+        Self::new(Arc::new(RwLock::new(SqliteStore::default())))
+    }
+}
+
+impl SqliteMetadataStore {
+    ///
+    /// A metadata store on `store`, which can be shared with a `SqliteNotificationStore`
+    pub fn new(store: Arc<RwLock<SqliteStore>>) -> Self {
+        let table = std::env::var("SQLITE_METADATA_TABLE").unwrap_or_else(|_| TABLE.to_lowercase());
+        let max_metadata_bytes = std::env::var("SQLITE_MAX_METADATA_BYTES")
+            .ok()
+            .and_then(|s| s.parse().ok())
+            .unwrap_or(MAX_METADATA_BYTES);
+        Self {
+            store,
+            table,
+            max_metadata_bytes,
+        }
+    }
+}
+
+///
+/// The statement that inserts a job's metadata, or updates it when the job is stored already
+fn upsert_sql(table: &str) -> String {
+    "INSERT INTO ".to_string()
+        + table
+        + " (\
+        id, last_updated, next_tick, job_type, count, \
+        ran, stopped, schedule, repeating, repeated_every, \
+        extra, last_tick, anchor, timeout_ms, skip_overlapping, \
+        retries, retry_backoff_ms, timezone, jitter_ms, tags, misfire_policy, max_runs, \
+        dst_gap, dst_overlap, expires_at, priority, active_from, boosted_priority, boost_until \
+    ) \
+    VALUES (\
+        ?1, ?2, ?3, ?4, ?5, \
+        ?6, ?7, ?8, ?9, ?10, \
+        ?11, ?12, ?13, ?14, ?15, \
+        ?16, ?17, ?18, ?19, ?20, \
+        ?21, ?22, ?23, ?24, ?25, \
+        ?26, ?27, ?28, ?29 \
+    ) \
+    ON CONFLICT (id) \
+    DO \
+        UPDATE \
+        SET \
+            last_updated=?2, next_tick=?3, job_type=?4, count=?5, \
+            ran=?6, stopped=?7, schedule=?8, repeating=?9, repeated_every=?10, \
+            extra=?11, last_tick=?12, anchor=?13, timeout_ms=?14, \
+            skip_overlapping=?15, retries=?16, retry_backoff_ms=?17, timezone=?18, \
+            jitter_ms=?19, tags=?20, misfire_policy=?21, max_runs=?22, \
+            dst_gap=?23, dst_overlap=?24, expires_at=?25, priority=?26, \
+            active_from=?27, boosted_priority=?28, boost_until=?29"
+}
+
+///
+/// Binds a job's metadata to the statement of [upsert_sql]
+fn upsert_query(sql: &str, data: JobStoredData) -> Query<'_, Sqlite, SqliteArguments<'_>> {
+    use crate::job::job_data_prost::job_stored_data::Job::CronJob as CronJobType;
+    use crate::job::job_data_prost::job_stored_data::Job::NonCronJob as NonCronJobType;
+
+    let uuid: Uuid = data.id.as_ref().unwrap().into();
+    let last_updated = data.last_updated.as_ref().map(|i| *i as i64);
+    let next_tick = data.next_tick as i64;
+    let schedule = match data.job.as_ref() {
+        Some(CronJobType(ct)) => Some(ct.schedule.clone()),
+        _ => None,
+    };
+    let timezone = match data.job.as_ref() {
+        Some(CronJobType(ct)) if !ct.timezone.is_empty() => Some(ct.timezone.clone()),
+        _ => None,
+    };
+    let tags = if data.tags.is_empty() {
+        None
+    } else {
+        Some(data.tags.join(&TAG_SEPARATOR.to_string()))
+    };
+    let repeating = match data.job.as_ref() {
+        Some(NonCronJobType(ct)) => Some(ct.repeating),
+        _ => None,
+    };
+    let repeated_every = match data.job.as_ref() {
+        Some(NonCronJobType(ct)) => Some(ct.repeated_every as i64),
+        _ => None,
+    };
+    let anchor = match data.job.as_ref() {
+        Some(NonCronJobType(ct)) => ct.anchor.map(|a| a as i64),
+        _ => None,
+    };
+    let last_tick = data.last_tick.as_ref().map(|i| *i as i64);
+    let timeout_ms = data.timeout_ms.map(|t| t as i64);
+    let max_runs = data.max_runs.map(|m| m as i64);
+    let (dst_gap, dst_overlap) = match data.job.as_ref() {
+        Some(CronJobType(ct)) => (Some(ct.dst_gap as i64), Some(ct.dst_overlap as i64)),
+        _ => (None, None),
+    };
+
+    sqlx::query(sql)
+        .bind(uuid)
+        .bind(last_updated)
+        .bind(next_tick)
+        .bind(data.job_type)
+        .bind(data.count as i64)
+        .bind(data.ran)
+        .bind(data.stopped)
+        .bind(schedule)
+        .bind(repeating)
+        .bind(repeated_every)
+        .bind(data.extra)
+        .bind(last_tick)
+        .bind(anchor)
+        .bind(timeout_ms)
+        .bind(data.skip_overlapping)
+        .bind(data.retries as i64)
+        .bind(data.retry_backoff_ms as i64)
+        .bind(timezone)
+        .bind(data.jitter_ms as i64)
+        .bind(tags)
+        .bind(data.misfire_policy as i64)
+        .bind(max_runs)
+        .bind(dst_gap)
+        .bind(dst_overlap)
+        .bind(data.expires_at.map(|e| e as i64))
+        .bind(data.priority)
+        .bind(data.active_from.map(|a| a as i64))
+        .bind(data.boosted_priority)
+        .bind(data.boost_until.map(|b| b as i64))
+}
+
+impl DataStore<JobStoredData> for SqliteMetadataStore {
+    fn get(
+        &mut self,
+        id: Uuid,
+    ) -> Pin<Box<dyn Future<Output = Result<Option<JobStoredData>, JobSchedulerError>> + Send>>
+    {
+        let store = self.store.clone();
+        let table = self.table.clone();
+        Box::pin(async move {
+            let store = store.read().await;
+            match &*store {
+                SqliteStore::Created(_) => Err(JobSchedulerError::GetJobData),
+                SqliteStore::Inited(pool) => {
+                    let sql = "SELECT \
+                        id, last_updated, next_tick, last_tick, job_type, count, \
+                        ran, stopped, schedule, repeating, repeated_every, \
+                        extra, anchor, timeout_ms, skip_overlapping, retries, retry_backoff_ms, \
+                        timezone, jitter_ms, tags, misfire_policy, max_runs, dst_gap, dst_overlap, expires_at, \
+                        priority, active_from, boosted_priority, boost_until \
+                     FROM "
+                        .to_string()
+                        + &*table
+                        + " WHERE id = ?1 LIMIT 1";
+                    let row = sqlx::query(&*sql).bind(id).fetch_optional(pool).await;
+                    if let Err(e) = row {
+                        error!("Error getting value {:?}", e);
+                        return Err(JobSchedulerError::GetJobData);
+                    }
+                    let row = row.unwrap();
+                    Ok(row.map(|row| row.into()))
+                }
+            }
+        })
+    }
+
+    fn add_or_update(
+        &mut self,
+        data: JobStoredData,
+    ) -> Pin<Box<dyn Future<Output = Result<(), JobSchedulerError>> + Send>> {
+        let size = data.encoded_len();
+        if size > self.max_metadata_bytes {
+            error!(
+                "Metadata of {:?} is {} bytes, more than the maximum of {}",
+                data.id, size, self.max_metadata_bytes
+            );
+            return Box::pin(async { Err(JobSchedulerError::MetadataTooLarge) });
+        }
+        let store = self.store.clone();
+        let table = self.table.clone();
+        Box::pin(async move {
+            let store = store.read().await;
+            match &*store {
+                SqliteStore::Created(_) => Err(JobSchedulerError::UpdateJobData),
+                SqliteStore::Inited(pool) => {
+                    let sql = upsert_sql(&table);
+                    let val = upsert_query(&sql, data).execute(pool).await;
+                    if let Err(e) = val {
+                        error!("Error {:?}", e);
+                        Err(JobSchedulerError::CantAdd)
+                    } else {
+                        Ok(())
+                    }
+                }
+            }
+        })
+    }
+
+    fn delete(
+        &mut self,
+        guid: Uuid,
+    ) -> Pin<Box<dyn Future<Output = Result<(), JobSchedulerError>> + Send>> {
+        let store = self.store.clone();
+        let table = self.table.clone();
+
+        Box::pin(async move {
+            let store = store.read().await;
+            match &*store {
+                SqliteStore::Created(_) => Err(JobSchedulerError::CantRemove),
+                SqliteStore::Inited(pool) => {
+                    let sql = "DELETE FROM ".to_string() + &*table + " WHERE id = ?1";
+                    let val = sqlx::query(&*sql).bind(guid).execute(pool).await;
+                    match val {
+                        Ok(_) => Ok(()),
+                        Err(e) => {
+                            error!("Error deleting job data {:?}", e);
+                            Err(JobSchedulerError::CantRemove)
+                        }
+                    }
+                }
+            }
+        })
+    }
+}
+
+fn get_u64(row: &SqliteRow, index: usize) -> Option<u64> {
+    row.try_get::<Option<i64>, _>(index)
+        .ok()
+        .flatten()
+        .map(|i| i as u64)
+}
+
+fn job_and_next_tick(row: &SqliteRow) -> JobAndNextTick {
+    let id: Uuid = row.get(0);
+    let id: JobUuid = id.into();
+    let job_type = row.try_get(1).unwrap_or_default();
+    let next_tick = get_u64(row, 2).unwrap_or_default();
+    let last_tick = get_u64(row, 3);
+    let stopped = row
+        .try_get::<Option<bool>, _>(4)
+        .ok()
+        .flatten()
+        .unwrap_or_default();
+    let expires_at = get_u64(row, 5);
+    let priority = row
+        .try_get::<Option<i32>, _>(6)
+        .ok()
+        .flatten()
+        .unwrap_or_default();
+    let boosted_priority = row.try_get::<Option<i32>, _>(7).ok().flatten();
+    let boost_until = get_u64(row, 8);
+
+    JobAndNextTick {
+        id: Some(id),
+        job_type,
+        next_tick,
+        last_tick,
+        stopped,
+        expires_at,
+        priority,
+        boosted_priority,
+        boost_until,
+    }
+}
+
+impl From<SqliteRow> for JobStoredData {
+    fn from(row: SqliteRow) -> Self {
+        /*
+        id, last_updated, next_tick, last_tick, job_type, count, \
+                        ran, stopped, schedule, repeating, repeated_every, \
+                        extra, anchor, timeout_ms, skip_overlapping, retries, retry_backoff_ms, \
+                        timezone, jitter_ms, tags, misfire_policy, max_runs, dst_gap, dst_overlap, expires_at,
+                        priority, active_from, boosted_priority, boost_until
+         */
+        let get_bool = |index: usize| {
+            row.try_get::<Option<bool>, _>(index)
+                .ok()
+                .flatten()
+                .unwrap_or_default()
+        };
+        let id: Uuid = row.get(0);
+        let last_updated = get_u64(&row, 1);
+        let next_tick = get_u64(&row, 2).unwrap_or_default();
+        let last_tick = get_u64(&row, 3);
+
+        let job_type: i32 = row.try_get(4).unwrap_or_default();
+        let count = get_u64(&row, 5).unwrap_or_default() as u32;
+        let ran = get_bool(6);
+        let stopped = get_bool(7);
+        let job = {
+            use crate::job::job_data_prost::job_stored_data::Job::CronJob as CronJobType;
+            use crate::job::job_data_prost::job_stored_data::Job::NonCronJob as NonCronJobType;
+
+            let job_type = JobType::from_i32(job_type);
+            match job_type {
+                Some(JobType::Cron) => match row.try_get::<Option<String>, _>(8) {
+                    Ok(Some(schedule)) => {
+                        let timezone = row
+                            .try_get::<Option<String>, _>(17)
+                            .ok()
+                            .flatten()
+                            .unwrap_or_default();
+                        let dst_gap = get_u64(&row, 22).unwrap_or_default() as i32;
+                        let dst_overlap = get_u64(&row, 23).unwrap_or_default() as i32;
+                        Some(CronJobType(CronJob {
+                            schedule,
+                            timezone,
+                            dst_gap,
+                            dst_overlap,
+                        }))
+                    }
+                    _ => None,
+                },
+                Some(_) => {
+                    let repeating = get_bool(9);
+                    let repeated_every = get_u64(&row, 10).unwrap_or_default();
+                    let anchor = get_u64(&row, 12);
+                    Some(NonCronJobType(NonCronJob {
+                        repeating,
+                        repeated_every,
+                        anchor,
+                    }))
+                }
+                None => None,
+            }
+        };
+        let extra = row
+            .try_get::<Option<Vec<u8>>, _>(11)
+            .ok()
+            .flatten()
+            .unwrap_or_default();
+        let timeout_ms = get_u64(&row, 13);
+        let skip_overlapping = get_bool(14);
+        let retries = get_u64(&row, 15).unwrap_or_default() as u32;
+        let retry_backoff_ms = get_u64(&row, 16).unwrap_or_default();
+        let jitter_ms = get_u64(&row, 18).unwrap_or_default();
+        let tags = row
+            .try_get::<Option<String>, _>(19)
+            .ok()
+            .flatten()
+            .map(|tags| tags.split(TAG_SEPARATOR).map(String::from).collect())
+            .unwrap_or_default();
+        let misfire_policy = get_u64(&row, 20).unwrap_or_default() as i32;
+        let max_runs = get_u64(&row, 21).map(|m| m as u32);
+        let expires_at = get_u64(&row, 24);
+        let priority = row
+            .try_get::<Option<i32>, _>(25)
+            .ok()
+            .flatten()
+            .unwrap_or_default();
+        let active_from = get_u64(&row, 26);
+        let boosted_priority = row.try_get::<Option<i32>, _>(27).ok().flatten();
+        let boost_until = get_u64(&row, 28);
+
+        Self {
+            id: Some(id.into()),
+            last_updated,
+            last_tick,
+            next_tick,
+            job_type,
+            count,
+            extra,
+            ran,
+            stopped,
+            timeout_ms,
+            skip_overlapping,
+            retries,
+            retry_backoff_ms,
+            jitter_ms,
+            tags,
+            misfire_policy,
+            max_runs,
+            expires_at,
+            priority,
+            active_from,
+            boosted_priority,
+            boost_until,
+            job,
+        }
+    }
+}
+
+impl InitStore for SqliteMetadataStore {
+    fn init(&mut self) -> Pin<Box<dyn Future<Output = Result<(), JobSchedulerError>> + Send>> {
+        let store = self.store.clone();
+        let table = self.table.clone();
+        Box::pin(async move {
+            let mut w = store.write().await;
+            let v = w.clone().init().await;
+            match v {
+                Ok(v) => {
+                    if let SqliteStore::Inited(pool) = &v {
+                        let sql = "CREATE TABLE IF NOT EXISTS ".to_string()
+                            + &*table
+                            + " (\
+                                id BLOB NOT NULL PRIMARY KEY,\
+                                last_updated INTEGER,\
+                                next_tick INTEGER,\
+                                last_tick INTEGER,\
+                                job_type INTEGER NOT NULL,\
+                                count INTEGER,\
+                                ran BOOLEAN,\
+                                stopped BOOLEAN,\
+                                schedule TEXT,\
+                                repeating BOOLEAN,\
+                                repeated_every INTEGER,\
+                                extra BLOB,\
+                                anchor INTEGER,\
+                                timeout_ms INTEGER,\
+                                skip_overlapping BOOLEAN,\
+                                retries INTEGER,\
+                                retry_backoff_ms INTEGER,\
+                                timezone TEXT,\
+                                jitter_ms INTEGER,\
+                                tags TEXT,\
+                                misfire_policy INTEGER,\
+                                max_runs INTEGER,\
+                                dst_gap INTEGER,\
+                                dst_overlap INTEGER,\
+                                expires_at INTEGER,\
+                                priority INTEGER,\
+                                active_from INTEGER,\
+                                boosted_priority INTEGER,\
+                                boost_until INTEGER\
+                            )";
+                        let create = sqlx::query(&*sql).execute(pool).await;
+                        if let Err(e) = create {
+                            error!("Error on init SQLite Metadata store {:?}", e);
+                            return Err(JobSchedulerError::StoreInit(e.to_string()));
+                        }
+                    }
+                    *w = v;
+                    Ok(())
+                }
+                Err(e) => {
+                    error!("Error initialising {:?}", e);
+                    Err(e)
+                }
+            }
+        })
+    }
+
+    fn inited(&mut self) -> Pin<Box<dyn Future<Output = Result<bool, JobSchedulerError>> + Send>> {
+        let store = self.store.clone();
+        Box::pin(async move {
+            let store = store.read().await;
+            Ok(store.inited())
+        })
+    }
+}
+
+impl MetaDataStorage for SqliteMetadataStore {
+    fn is_shared(&self) -> bool {
+        true
+    }
+
+    fn list_next_ticks(
+        &mut self,
+    ) -> Pin<Box<dyn Future<Output = Result<Vec<JobAndNextTick>, JobSchedulerError>> + Send>> {
+        let store = self.store.clone();
+        let table = self.table.clone();
+
+        Box::pin(async move {
+            let store = store.read().await;
+            match &*store {
+                SqliteStore::Created(_) => Err(JobSchedulerError::CantListNextTicks),
+                SqliteStore::Inited(pool) => {
+                    let now = Utc::now().timestamp();
+                    let sql = "SELECT \
+                            id, job_type, next_tick, last_tick, stopped, expires_at, priority, \
+                            boosted_priority, boost_until \
+                        FROM "
+                        .to_string()
+                        + &*table
+                        + " \
+                        WHERE \
+                              (next_tick > 0 AND next_tick <= ?1) \
+                           OR expires_at <= ?1";
+                    let rows = sqlx::query(&*sql).bind(now).fetch_all(pool).await;
+                    match rows {
+                        Ok(rows) => Ok(rows.iter().map(job_and_next_tick).collect::<Vec<_>>()),
+                        Err(e) => {
+                            error!("Error getting next ticks {:?}", e);
+                            Err(JobSchedulerError::CantListNextTicks)
+                        }
+                    }
+                }
+            }
+        })
+    }
+
+    fn list_jobs(
+        &mut self,
+    ) -> Pin<Box<dyn Future<Output = Result<Vec<JobAndNextTick>, JobSchedulerError>> + Send>> {
+        let store = self.store.clone();
+        let table = self.table.clone();
+
+        Box::pin(async move {
+            let store = store.read().await;
+            match &*store {
+                SqliteStore::Created(_) => Err(JobSchedulerError::CantListGuids),
+                SqliteStore::Inited(pool) => {
+                    let sql =
+                        "SELECT id, job_type, next_tick, last_tick, stopped, expires_at, priority, \
+                        boosted_priority, boost_until FROM "
+                            .to_string() + &*table;
+                    let rows = sqlx::query(&*sql).fetch_all(pool).await;
+                    match rows {
+                        Ok(rows) => Ok(rows.iter().map(job_and_next_tick).collect::<Vec<_>>()),
+                        Err(e) => {
+                            error!("Error listing jobs {:?}", e);
+                            Err(JobSchedulerError::CantListGuids)
+                        }
+                    }
+                }
+            }
+        })
+    }
+
+    fn add_all(
+        &mut self,
+        jobs: Vec<JobStoredData>,
+    ) -> Pin<Box<dyn Future<Output = Result<(), JobSchedulerError>> + Send>> {
+        if let Some(data) = jobs
+            .iter()
+            .find(|data| data.encoded_len() > self.max_metadata_bytes)
+        {
+            error!(
+                "Metadata of {:?} is {} bytes, more than the maximum of {}",
+                data.id,
+                data.encoded_len(),
+                self.max_metadata_bytes
+            );
+            return Box::pin(async { Err(JobSchedulerError::MetadataTooLarge) });
+        }
+        let store = self.store.clone();
+        let table = self.table.clone();
+        Box::pin(async move {
+            let store = store.read().await;
+            match &*store {
+                SqliteStore::Created(_) => Err(JobSchedulerError::UpdateJobData),
+                SqliteStore::Inited(pool) => {
+                    let mut transaction = pool.begin().await.map_err(|e| {
+                        error!("Error starting a transaction {:?}", e);
+                        JobSchedulerError::CantAdd
+                    })?;
+                    let sql = upsert_sql(&table);
+                    // Dropping the transaction on an error rolls it back
+                    for data in jobs {
+                        if let Err(e) = upsert_query(&sql, data).execute(&mut transaction).await {
+                            error!("Error {:?}", e);
+                            return Err(JobSchedulerError::CantAdd);
+                        }
+                    }
+                    transaction.commit().await.map_err(|e| {
+                        error!("Error committing the jobs {:?}", e);
+                        JobSchedulerError::CantAdd
+                    })
+                }
+            }
+        })
+    }
+
+    fn delete_all(
+        &mut self,
+        guids: Vec<Uuid>,
+    ) -> Pin<Box<dyn Future<Output = Result<(), JobSchedulerError>> + Send>> {
+        let store = self.store.clone();
+        let table = self.table.clone();
+        Box::pin(async move {
+            let store = store.read().await;
+            match &*store {
+                SqliteStore::Created(_) => Err(JobSchedulerError::CantRemove),
+                SqliteStore::Inited(pool) => {
+                    let mut transaction = pool.begin().await.map_err(|e| {
+                        error!("Error starting a transaction {:?}", e);
+                        JobSchedulerError::CantRemove
+                    })?;
+                    let sql = "DELETE FROM ".to_string() + &*table + " WHERE id = ?1";
+                    // Dropping the transaction on an error rolls it back
+                    for guid in guids {
+                        let delete = sqlx::query(&*sql)
+                            .bind(guid)
+                            .execute(&mut transaction)
+                            .await;
+                        if let Err(e) = delete {
+                            error!("Error deleting job data {:?}", e);
+                            return Err(JobSchedulerError::CantRemove);
+                        }
+                    }
+                    transaction.commit().await.map_err(|e| {
+                        error!("Error committing the deletes {:?}", e);
+                        JobSchedulerError::CantRemove
+                    })
+                }
+            }
+        })
+    }
+
+    fn count(&mut self) -> Pin<Box<dyn Future<Output = Result<usize, JobSchedulerError>> + Send>> {
+        let store = self.store.clone();
+        let table = self.table.clone();
+
+        Box::pin(async move {
+            let store = store.read().await;
+            match &*store {
+                SqliteStore::Created(_) => Err(JobSchedulerError::CantListGuids),
+                SqliteStore::Inited(pool) => {
+                    let sql = "SELECT COUNT(*) FROM ".to_string() + &*table;
+                    let row = sqlx::query(&*sql).fetch_one(pool).await;
+                    match row {
+                        Ok(row) => Ok(row.get::<i64, _>(0) as usize),
+                        Err(e) => {
+                            error!("Error counting jobs {:?}", e);
+                            Err(JobSchedulerError::CantListGuids)
+                        }
+                    }
+                }
+            }
+        })
+    }
+
+    fn set_next_and_last_tick(
+        &mut self,
+        guid: Uuid,
+        next_tick: Option<DateTime<Utc>>,
+        last_tick: Option<DateTime<Utc>>,
+    ) -> Pin<Box<dyn Future<Output = Result<(), JobSchedulerError>> + Send>> {
+        let store = self.store.clone();
+        let table = self.table.clone();
+
+        Box::pin(async move {
+            let store = store.read().await;
+            match &*store {
+                SqliteStore::Created(_) => Err(JobSchedulerError::UpdateJobData),
+                SqliteStore::Inited(pool) => {
+                    let next_tick = next_tick.map(|b| b.timestamp()).unwrap_or(0);
+                    let last_tick = last_tick.map(|b| b.timestamp());
+                    let sql = "UPDATE ".to_string()
+                        + &*table
+                        + " \
+                        SET \
+                         next_tick=?1, last_tick=?2 \
+                        WHERE \
+                            id = ?3";
+                    let resp = sqlx::query(&*sql)
+                        .bind(next_tick)
+                        .bind(last_tick)
+                        .bind(guid)
+                        .execute(pool)
+                        .await;
+                    if let Err(e) = resp {
+                        error!("Error updating next and last tick {:?}", e);
+                        Err(JobSchedulerError::UpdateJobData)
+                    } else {
+                        Ok(())
+                    }
+                }
+            }
+        })
+    }
+
+    fn time_till_next_job(
+        &mut self,
+    ) -> Pin<Box<dyn Future<Output = Result<Option<Duration>, JobSchedulerError>> + Send>> {
+        let store = self.store.clone();
+        let table = self.table.clone();
+        Box::pin(async move {
+            let store = store.read().await;
+            match &*store {
+                SqliteStore::Created(_) => Err(JobSchedulerError::CouldNotGetTimeUntilNextTick),
+                SqliteStore::Inited(pool) => {
+                    let now = Utc::now().timestamp();
+                    let sql = "SELECT \
+                            next_tick \
+                        FROM "
+                        .to_string()
+                        + &*table
+                        + " \
+                        WHERE \
+                              next_tick > 0 \
+                          AND next_tick > ?1 \
+                          AND stopped IS NOT 1 \
+                        ORDER BY next_tick ASC \
+                        LIMIT 1";
+                    let row = sqlx::query(&*sql).bind(now).fetch_optional(pool).await;
+                    if let Err(e) = row {
+                        error!("Error getting time until next job {:?}", e);
+                        return Err(JobSchedulerError::CouldNotGetTimeUntilNextTick);
+                    }
+                    let row = row.unwrap();
+                    Ok(row
+                        .and_then(|r| r.try_get::<i64, _>(0).ok())
+                        .map(|ts| ts - now)
+                        .filter(|ts| *ts > 0)
+                        .map(|ts| ts as u64)
+                        .map(std::time::Duration::from_secs))
+                }
+            }
+        })
+    }
+
+    fn next_job(
+        &mut self,
+    ) -> Pin<Box<dyn Future<Output = Result<Option<JobAndNextTick>, JobSchedulerError>> + Send>>
+    {
+        let store = self.store.clone();
+        let table = self.table.clone();
+        Box::pin(async move {
+            let store = store.read().await;
+            match &*store {
+                SqliteStore::Created(_) => Err(JobSchedulerError::CouldNotGetTimeUntilNextTick),
+                SqliteStore::Inited(pool) => {
+                    let sql = "SELECT \
+                            id, job_type, next_tick, last_tick, stopped, expires_at, priority, \
+                            boosted_priority, boost_until \
+                        FROM "
+                        .to_string()
+                        + &*table
+                        + " \
+                        WHERE \
+                              next_tick > 0 \
+                          AND stopped IS NOT 1 \
+                        ORDER BY next_tick ASC \
+                        LIMIT 1";
+                    match sqlx::query(&*sql).fetch_optional(pool).await {
+                        Ok(row) => Ok(row.as_ref().map(job_and_next_tick)),
+                        Err(e) => {
+                            error!("Error getting the next job {:?}", e);
+                            Err(JobSchedulerError::CouldNotGetTimeUntilNextTick)
+                        }
+                    }
+                }
+            }
+        })
+    }
+}
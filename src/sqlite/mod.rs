@@ -0,0 +1,83 @@
+mod metadata_store;
+mod notification_store;
+
+use crate::JobSchedulerError;
+use sqlx::sqlite::{SqliteConnectOptions, SqlitePool, SqlitePoolOptions};
+use std::future::Future;
+use std::pin::Pin;
+use std::str::FromStr;
+use tracing::error;
+
+pub use metadata_store::SqliteMetadataStore;
+pub use notification_store::SqliteNotificationStore;
+
+const URL: &str = "tokio_cron_scheduler.db";
+
+///
+/// A SQLite database, either a file or in memory. Shared between the metadata and notification
+/// store by putting it in the same `Arc<RwLock<..>>`.
+#[derive(Clone)]
+pub enum SqliteStore {
+    Created(String),
+    Inited(SqlitePool),
+}
+
+impl SqliteStore {
+    ///
+    /// A store for the database at `url`, which is either a file path, a `sqlite://` URL or
+    /// `sqlite::memory:` for an in memory database. A database file is created if it doesn't
+    /// exist yet.
+    pub fn new(url: &str) -> Self {
+        Self::Created(url.to_string())
+    }
+
+    pub fn inited(&self) -> bool {
+        matches!(self, SqliteStore::Inited(_))
+    }
+}
+
+impl Default for SqliteStore {
+    fn default() -> Self {
+        let url = std::env::var("SQLITE_URL").unwrap_or_else(|_| URL.to_string());
+        Self::Created(url)
+    }
+}
+
+impl SqliteStore {
+    pub fn init(
+        self,
+    ) -> Pin<Box<dyn Future<Output = Result<SqliteStore, JobSchedulerError>> + Send>> {
+        Box::pin(async move {
+            match self {
+                SqliteStore::Created(url) => {
+                    let options = SqliteConnectOptions::from_str(&*url);
+                    if let Err(e) = options {
+                        error!("Error parsing SQLite url {:?}", e);
+                        return Err(JobSchedulerError::StoreInit(e.to_string()));
+                    }
+                    let options = options.unwrap().create_if_missing(true).foreign_keys(true);
+                    // Every connection to an in memory database gets a database of its own
+                    let in_memory = url.contains(":memory:");
+                    let pool = if in_memory {
+                        SqlitePoolOptions::new()
+                            .max_connections(1)
+                            .idle_timeout(None)
+                            .max_lifetime(None)
+                    } else {
+                        SqlitePoolOptions::new()
+                    }
+                    .connect_with(options)
+                    .await;
+                    match pool {
+                        Ok(pool) => Ok(SqliteStore::Inited(pool)),
+                        Err(e) => {
+                            error!("Error connecting to SQLite {:?}", e);
+                            Err(JobSchedulerError::StoreInit(e.to_string()))
+                        }
+                    }
+                }
+                SqliteStore::Inited(pool) => Ok(SqliteStore::Inited(pool)),
+            }
+        })
+    }
+}
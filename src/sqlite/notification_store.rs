@@ -0,0 +1,418 @@
+use crate::job::job_data_prost::{JobIdAndNotification, JobState, NotificationData};
+use crate::job::{JobId, NotificationId};
+use crate::sqlite::SqliteStore;
+use crate::store::{DataStore, InitStore, NotificationStore};
+use crate::JobSchedulerError;
+use sqlx::Row;
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::Arc;
+use tokio::sync::RwLock;
+use tracing::error;
+use uuid::Uuid;
+
+const MAIN_TABLE: &str = "notification";
+const STATES_TABLE: &str = "notification_state";
+
+///
+/// A SQLite backed notification store. Creates its tables on init.
+#[derive(Clone)]
+pub struct SqliteNotificationStore {
+    pub store: Arc<RwLock<SqliteStore>>,
+    pub table: String,
+    pub states_table: String,
+}
+
+impl Default for SqliteNotificationStore {
+    fn default() -> Self {
+        Self::new(Arc::new(RwLock::new(SqliteStore::default())))
+    }
+}
+
+impl SqliteNotificationStore {
+    ///
+    /// A notification store on `store`, which can be shared with a `SqliteMetadataStore`
+    pub fn new(store: Arc<RwLock<SqliteStore>>) -> Self {
+        let table = std::env::var("SQLITE_NOTIFICATION_TABLE")
+            .unwrap_or_else(|_| MAIN_TABLE.to_lowercase());
+        let states_table = std::env::var("SQLITE_NOTIFICATION_STATES_TABLE")
+            .unwrap_or_else(|_| STATES_TABLE.to_lowercase());
+        Self {
+            store,
+            table,
+            states_table,
+        }
+    }
+}
+
+impl DataStore<NotificationData> for SqliteNotificationStore {
+    fn get(
+        &mut self,
+        id: Uuid,
+    ) -> Pin<Box<dyn Future<Output = Result<Option<NotificationData>, JobSchedulerError>> + Send>>
+    {
+        let store = self.store.clone();
+        let table = self.table.clone();
+        let states_table = self.states_table.clone();
+
+        Box::pin(async move {
+            let store = store.read().await;
+            match &*store {
+                SqliteStore::Created(_) => Err(JobSchedulerError::GetJobData),
+                SqliteStore::Inited(pool) => {
+                    let sql =
+                        "SELECT id, job_id, extra FROM ".to_string() + &*table + " WHERE id = ?1";
+                    let row = sqlx::query(&*sql).bind(id).fetch_optional(pool).await;
+                    if let Err(e) = row {
+                        error!("Error fetching notification data {:?}", e);
+                        return Err(JobSchedulerError::GetJobData);
+                    }
+                    let row = match row.unwrap() {
+                        Some(row) => row,
+                        None => return Ok(None),
+                    };
+                    let notification_id: Uuid = row.get(0);
+
+                    let job_states = {
+                        let sql =
+                            "SELECT state FROM ".to_string() + &*states_table + " WHERE id = ?1";
+                        let rows = sqlx::query(&*sql)
+                            .bind(notification_id)
+                            .fetch_all(pool)
+                            .await;
+                        match rows {
+                            Ok(rows) => rows
+                                .iter()
+                                .map(|row| {
+                                    let val: i32 = row.get(0);
+                                    val
+                                })
+                                .collect::<Vec<_>>(),
+                            Err(e) => {
+                                error!("Error getting states {:?}", e);
+                                vec![]
+                            }
+                        }
+                    };
+
+                    let job_id: Uuid = row.get(1);
+
+                    let job_id = JobIdAndNotification {
+                        job_id: Some(job_id.into()),
+                        notification_id: Some(notification_id.into()),
+                    };
+
+                    let extra = row
+                        .try_get::<Option<Vec<u8>>, _>(2)
+                        .ok()
+                        .flatten()
+                        .unwrap_or_default();
+                    let job_id = Some(job_id);
+                    Ok(Some(NotificationData {
+                        job_id,
+                        job_states,
+                        extra,
+                    }))
+                }
+            }
+        })
+    }
+
+    fn add_or_update(
+        &mut self,
+        data: NotificationData,
+    ) -> Pin<Box<dyn Future<Output = Result<(), JobSchedulerError>> + Send>> {
+        let store = self.store.clone();
+        let table = self.table.clone();
+        let states_table = self.states_table.clone();
+
+        Box::pin(async move {
+            let store = store.read().await;
+            match &*store {
+                SqliteStore::Created(_) => Err(JobSchedulerError::UpdateJobData),
+                SqliteStore::Inited(pool) => {
+                    let (job_id, notification_id) =
+                        match data.job_id_and_notification_id_from_data() {
+                            Some((job_id, notification_id)) => (job_id, notification_id),
+                            None => return Err(JobSchedulerError::UpdateJobData),
+                        };
+
+                    let sql = "INSERT INTO ".to_string()
+                        + &*table
+                        + " (id, job_id, extra) \
+                    VALUES (?1, ?2, ?3) \
+                    ON CONFLICT (id) \
+                    DO \
+                        UPDATE \
+                        SET \
+                            job_id = ?2, extra = ?3";
+                    let result = sqlx::query(&*sql)
+                        .bind(notification_id)
+                        .bind(job_id)
+                        .bind(data.extra)
+                        .execute(pool)
+                        .await;
+                    if let Err(e) = result {
+                        error!("Error doing the upsert {:?}", e);
+                        return Err(JobSchedulerError::UpdateJobData);
+                    }
+
+                    let sql = "DELETE FROM ".to_string() + &*states_table + " WHERE id = ?1";
+                    let result = sqlx::query(&*sql).bind(notification_id).execute(pool).await;
+                    if let Err(e) = result {
+                        error!("Error deleting {:?}", e);
+                    }
+
+                    let sql = "INSERT INTO ".to_string()
+                        + &*states_table
+                        + " (id, state) VALUES (?1, ?2)";
+                    for state in data.job_states {
+                        let result = sqlx::query(&*sql)
+                            .bind(notification_id)
+                            .bind(state)
+                            .execute(pool)
+                            .await;
+                        if let Err(e) = result {
+                            error!("Error inserting state vals {:?}", e);
+                        }
+                    }
+                    Ok(())
+                }
+            }
+        })
+    }
+
+    fn delete(
+        &mut self,
+        guid: Uuid,
+    ) -> Pin<Box<dyn Future<Output = Result<(), JobSchedulerError>> + Send>> {
+        let store = self.store.clone();
+        let table = self.table.clone();
+        Box::pin(async move {
+            let store = store.read().await;
+            match &*store {
+                SqliteStore::Created(_) => Err(JobSchedulerError::CantRemove),
+                SqliteStore::Inited(pool) => {
+                    let sql = "DELETE FROM ".to_string() + &*table + " WHERE id = ?1";
+                    sqlx::query(&*sql)
+                        .bind(guid)
+                        .execute(pool)
+                        .await
+                        .map(|_| ())
+                        .map_err(|e| {
+                            error!("Error deleting notification {:?}", e);
+                            JobSchedulerError::CantRemove
+                        })
+                }
+            }
+        })
+    }
+}
+
+impl InitStore for SqliteNotificationStore {
+    fn init(&mut self) -> Pin<Box<dyn Future<Output = Result<(), JobSchedulerError>> + Send>> {
+        let store = self.store.clone();
+        let table = self.table.clone();
+        let states_table = self.states_table.clone();
+
+        Box::pin(async move {
+            let mut w = store.write().await;
+            let v = w.clone().init().await;
+            match v {
+                Ok(v) => {
+                    if let SqliteStore::Inited(pool) = &v {
+                        let sql = "CREATE TABLE IF NOT EXISTS ".to_string()
+                            + &*table
+                            + " (\
+                                id BLOB NOT NULL PRIMARY KEY,\
+                                job_id BLOB,\
+                                extra BLOB\
+                            )";
+                        let create = sqlx::query(&*sql).execute(pool).await;
+                        if let Err(e) = create {
+                            error!("Error creating notification table {:?}", e);
+                            return Err(JobSchedulerError::StoreInit(e.to_string()));
+                        }
+                        let sql = "CREATE TABLE IF NOT EXISTS ".to_string()
+                            + &*states_table
+                            + " (\
+                                id BLOB NOT NULL,\
+                                state INTEGER NOT NULL,\
+                                PRIMARY KEY (id, state),\
+                                FOREIGN KEY (id) REFERENCES "
+                            + &*table
+                            + " (id) ON DELETE CASCADE\
+                            )";
+                        let create = sqlx::query(&*sql).execute(pool).await;
+                        if let Err(e) = create {
+                            error!("Error creating notification states table {:?}", e);
+                            return Err(JobSchedulerError::StoreInit(e.to_string()));
+                        }
+                    }
+                    *w = v;
+                    Ok(())
+                }
+                Err(e) => {
+                    error!("Error initialising {:?}", e);
+                    Err(e)
+                }
+            }
+        })
+    }
+
+    fn inited(&mut self) -> Pin<Box<dyn Future<Output = Result<bool, JobSchedulerError>> + Send>> {
+        let store = self.store.clone();
+        Box::pin(async move {
+            let store = store.read().await;
+            Ok(store.inited())
+        })
+    }
+}
+
+impl NotificationStore for SqliteNotificationStore {
+    fn list_notification_guids_for_job_and_state(
+        &mut self,
+        job: JobId,
+        state: JobState,
+    ) -> Pin<Box<dyn Future<Output = Result<Vec<NotificationId>, JobSchedulerError>> + Send>> {
+        let store = self.store.clone();
+        let table = self.table.clone();
+        let states_table = self.states_table.clone();
+        Box::pin(async move {
+            let store = store.read().await;
+            match &*store {
+                SqliteStore::Created(_) => Err(JobSchedulerError::CantListGuids),
+                SqliteStore::Inited(pool) => {
+                    let state = state as i32;
+                    let sql = "SELECT DISTINCT n.id FROM ".to_string()
+                        + &*table
+                        + " AS n \
+                     JOIN "
+                        + &*states_table
+                        + " AS st ON st.id = n.id \
+                    WHERE \
+                         n.job_id = ?1 \
+                     AND st.state = ?2";
+                    let result = sqlx::query(&*sql)
+                        .bind(job)
+                        .bind(state)
+                        .fetch_all(pool)
+                        .await;
+                    match result {
+                        Ok(rows) => Ok(rows
+                            .iter()
+                            .map(|r| {
+                                let uuid: Uuid = r.get(0);
+                                uuid
+                            })
+                            .collect::<Vec<_>>()),
+                        Err(e) => {
+                            error!("Error listing notification guids for job and state {:?}", e);
+                            Err(JobSchedulerError::CantListGuids)
+                        }
+                    }
+                }
+            }
+        })
+    }
+
+    fn list_notification_guids_for_job_id(
+        &mut self,
+        job_id: Uuid,
+    ) -> Pin<Box<dyn Future<Output = Result<Vec<Uuid>, JobSchedulerError>> + Send>> {
+        let store = self.store.clone();
+        let table = self.table.clone();
+
+        Box::pin(async move {
+            let store = store.read().await;
+            match &*store {
+                SqliteStore::Created(_) => Err(JobSchedulerError::CantListGuids),
+                SqliteStore::Inited(pool) => {
+                    let sql =
+                        "SELECT DISTINCT id FROM ".to_string() + &*table + " WHERE job_id = ?1";
+                    let result = sqlx::query(&*sql).bind(job_id).fetch_all(pool).await;
+                    match result {
+                        Ok(rows) => Ok(rows
+                            .iter()
+                            .map(|g| {
+                                let uuid: Uuid = g.get(0);
+                                uuid
+                            })
+                            .collect::<Vec<_>>()),
+                        Err(e) => {
+                            error!(
+                                "Error getting list of notifications guids for job id {:?}",
+                                e
+                            );
+                            Err(JobSchedulerError::CantListGuids)
+                        }
+                    }
+                }
+            }
+        })
+    }
+
+    fn delete_notification_for_state(
+        &mut self,
+        notification_id: Uuid,
+        state: JobState,
+    ) -> Pin<Box<dyn Future<Output = Result<bool, JobSchedulerError>> + Send>> {
+        let store = self.store.clone();
+        let states_table = self.states_table.clone();
+
+        Box::pin(async move {
+            let store = store.read().await;
+            match &*store {
+                SqliteStore::Created(_) => Err(JobSchedulerError::CantRemove),
+                SqliteStore::Inited(pool) => {
+                    let state = state as i32;
+                    let sql = "DELETE FROM ".to_string()
+                        + &*states_table
+                        + " \
+                    WHERE \
+                            id = ?1 \
+                        AND state = ?2";
+                    let result = sqlx::query(&*sql)
+                        .bind(notification_id)
+                        .bind(state)
+                        .execute(pool)
+                        .await;
+                    match result {
+                        Ok(result) => Ok(result.rows_affected() > 0),
+                        Err(e) => {
+                            error!("Error deleting notification for state {:?}", e);
+                            Err(JobSchedulerError::CantRemove)
+                        }
+                    }
+                }
+            }
+        })
+    }
+
+    fn delete_for_job(
+        &mut self,
+        job_id: Uuid,
+    ) -> Pin<Box<dyn Future<Output = Result<(), JobSchedulerError>> + Send>> {
+        let store = self.store.clone();
+        let table = self.table.clone();
+
+        Box::pin(async move {
+            let store = store.read().await;
+            match &*store {
+                SqliteStore::Created(_) => Err(JobSchedulerError::CantRemove),
+                SqliteStore::Inited(pool) => {
+                    let sql = "DELETE FROM ".to_string() + &*table + " WHERE job_id = ?1";
+                    sqlx::query(&*sql)
+                        .bind(job_id)
+                        .execute(pool)
+                        .await
+                        .map(|_| ())
+                        .map_err(|e| {
+                            error!("Error deleting for job {:?}", e);
+                            JobSchedulerError::CantRemove
+                        })
+                }
+            }
+        })
+    }
+}
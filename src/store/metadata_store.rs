@@ -14,6 +14,48 @@ pub trait MetaDataStorage: DataStore<JobStoredData> + InitStore {
     fn list_next_ticks(
         &mut self,
     ) -> Pin<Box<dyn Future<Output = Result<Vec<JobAndNextTick>, JobSchedulerError>> + Send>>;
+    /// Lists all stored jobs, whether they are due or not
+    fn list_jobs(
+        &mut self,
+    ) -> Pin<Box<dyn Future<Output = Result<Vec<JobAndNextTick>, JobSchedulerError>> + Send>>;
+    /// Adds or updates the jobs in one go, see `JobScheduler::add_all`. This writes them one by
+    /// one and stops at the first error, leaving the jobs before it stored. Stores with
+    /// transactions should override it to store all of the jobs or none.
+    fn add_all(
+        &mut self,
+        jobs: Vec<JobStoredData>,
+    ) -> Pin<Box<dyn Future<Output = Result<(), JobSchedulerError>> + Send>> {
+        let saves = jobs
+            .into_iter()
+            .map(|data| self.add_or_update(data))
+            .collect::<Vec<_>>();
+        Box::pin(async move {
+            for save in saves {
+                save.await?;
+            }
+            Ok(())
+        })
+    }
+    /// Deletes the jobs in one go, see `JobScheduler::remove_all`. This deletes them one by one
+    /// and stops at the first error, leaving the jobs after it stored. Stores with transactions
+    /// should override it to delete all of the jobs or none.
+    fn delete_all(
+        &mut self,
+        guids: Vec<Uuid>,
+    ) -> Pin<Box<dyn Future<Output = Result<(), JobSchedulerError>> + Send>> {
+        let deletes = guids
+            .into_iter()
+            .map(|guid| self.delete(guid))
+            .collect::<Vec<_>>();
+        Box::pin(async move {
+            for delete in deletes {
+                delete.await?;
+            }
+            Ok(())
+        })
+    }
+    /// Counts all stored jobs, without loading them
+    fn count(&mut self) -> Pin<Box<dyn Future<Output = Result<usize, JobSchedulerError>> + Send>>;
     fn set_next_and_last_tick(
         &mut self,
         guid: Uuid,
@@ -23,6 +65,32 @@ pub trait MetaDataStorage: DataStore<JobStoredData> + InitStore {
     fn time_till_next_job(
         &mut self,
     ) -> Pin<Box<dyn Future<Output = Result<Option<std::time::Duration>, JobSchedulerError>> + Send>>;
+    /// The job that isn't paused and has the earliest next tick. Stores that can sort on the next
+    /// tick should override this, instead of listing all jobs.
+    fn next_job(
+        &mut self,
+    ) -> Pin<Box<dyn Future<Output = Result<Option<JobAndNextTick>, JobSchedulerError>> + Send>>
+    {
+        let jobs = self.list_jobs();
+        Box::pin(async move {
+            Ok(jobs
+                .await?
+                .into_iter()
+                .filter(|j| j.next_tick > 0 && !j.stopped)
+                .min_by_key(|j| j.next_tick))
+        })
+    }
+    /// Writes what the store buffered, called by `JobScheduler::shutdown` before the shutdown
+    /// handlers. Stores that write right away, like the built-in ones, keep this no-op; stores
+    /// that batch their writes should override it to write the batch.
+    fn flush(&mut self) -> Pin<Box<dyn Future<Output = Result<(), JobSchedulerError>> + Send>> {
+        Box::pin(std::future::ready(Ok(())))
+    }
+    /// Whether other schedulers can write jobs to the store too. The scheduler's loop then keeps
+    /// checking the store every tick interval, instead of sleeping until the earliest next tick.
+    fn is_shared(&self) -> bool {
+        false
+    }
 }
 
 pub trait JobCodeGet: CodeGet<Box<JobToRunAsync>> {}
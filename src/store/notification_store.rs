@@ -31,6 +31,11 @@ pub trait NotificationStore: DataStore<NotificationData> + InitStore {
         &mut self,
         job_id: Uuid,
     ) -> Pin<Box<dyn Future<Output = Result<(), JobSchedulerError>> + Send>>;
+
+    /// Writes what the store buffered, see `MetaDataStorage::flush`
+    fn flush(&mut self) -> Pin<Box<dyn Future<Output = Result<(), JobSchedulerError>> + Send>> {
+        Box::pin(std::future::ready(Ok(())))
+    }
 }
 
 pub trait NotificationRunnableCodeGet: CodeGet<Box<OnJobNotification>> {}